@@ -0,0 +1,46 @@
+//! Benchmarks for converting large POSIX scripts
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use nu_posix::{parse_posix_script, PosixToNuConverter};
+
+/// Build a script of `lines` commands mixing common shell constructs
+fn generate_script(lines: usize) -> String {
+    let templates = [
+        "echo \"processing item $i\"",
+        "ls -lt /var/log",
+        "grep -i error /var/log/syslog",
+        "COUNT=${#name}",
+        "for f in a b c do cat $f done",
+        "test -f config.txt && echo found",
+        "sort data.txt | uniq -c | head -n 10",
+    ];
+
+    let mut script = String::new();
+    for i in 0..lines {
+        script.push_str(templates[i % templates.len()]);
+        script.push('\n');
+    }
+    script
+}
+
+fn bench_convert(c: &mut Criterion) {
+    let converter = PosixToNuConverter::new();
+    let script = parse_posix_script(&generate_script(5000)).unwrap();
+
+    c.bench_function("convert 5000 lines", |b| {
+        b.iter(|| converter.convert(black_box(&script)).unwrap())
+    });
+
+    c.bench_function("convert_to_writer 5000 lines", |b| {
+        let mut output = String::new();
+        b.iter(|| {
+            output.clear();
+            converter
+                .convert_to_writer(black_box(&script), &mut output)
+                .unwrap();
+        })
+    });
+}
+
+criterion_group!(benches, bench_convert);
+criterion_main!(benches);
@@ -0,0 +1,31 @@
+//! Benchmarks for converter registry lookups
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use nu_posix::plugin::builtin::BuiltinRegistry;
+use nu_posix::plugin::sus::CommandRegistry;
+
+fn bench_lookup(c: &mut Criterion) {
+    let commands = CommandRegistry::new();
+    let builtins = BuiltinRegistry::new();
+    let command_names = ["ls", "grep", "whoami", "nonexistent"];
+    let builtin_names = ["cd", "test", "wait", "nonexistent"];
+
+    c.bench_function("command registry lookup", |b| {
+        b.iter(|| {
+            for name in command_names {
+                black_box(commands.find_converter(black_box(name)));
+            }
+        })
+    });
+
+    c.bench_function("builtin registry lookup", |b| {
+        b.iter(|| {
+            for name in builtin_names {
+                black_box(builtins.find_converter(black_box(name)));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_lookup);
+criterion_main!(benches);
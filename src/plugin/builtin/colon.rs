@@ -0,0 +1,68 @@
+//! Colon builtin converter
+//!
+//! Converts the POSIX `:` null command, a no-op mostly used to truncate files
+//! with `: > file`
+
+use super::{BaseBuiltinConverter, BuiltinConverter};
+use anyhow::Result;
+
+/// Converter for the `:` builtin
+pub struct ColonBuiltinConverter;
+
+impl BuiltinConverter for ColonBuiltinConverter {
+    fn convert(&self, args: &[String]) -> Result<String> {
+        Ok(truncation(args).unwrap_or_else(|| "null".to_string()))
+    }
+
+    fn builtin_name(&self) -> &'static str {
+        ":"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts the null command to a no-op or file truncation"
+    }
+}
+
+/// Convert the `> file` redirection of a command that writes nothing into
+/// saving an empty string; `>> file` only creates the file
+pub(super) fn truncation(args: &[String]) -> Option<String> {
+    let base = BaseBuiltinConverter;
+    let (operator, file) = match args {
+        [operator, file] if operator == ">" || operator == ">>" => {
+            (operator.as_str(), file.as_str())
+        }
+        [arg] if arg.starts_with(">>") => (">>", &arg[2..]),
+        [arg] if arg.starts_with('>') => (">", &arg[1..]),
+        _ => return None,
+    };
+    let flag = if operator == ">>" { "--append" } else { "-f" };
+    Some(format!("\"\" | save {} {}", flag, base.quote_arg(file)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colon_builtin_converter() {
+        let converter = ColonBuiltinConverter;
+
+        assert_eq!(converter.convert(&[]).unwrap(), "null");
+        assert_eq!(
+            converter
+                .convert(&[">".to_string(), "file".to_string()])
+                .unwrap(),
+            "\"\" | save -f file"
+        );
+        assert_eq!(
+            converter.convert(&[">out.log".to_string()]).unwrap(),
+            "\"\" | save -f out.log"
+        );
+        assert_eq!(
+            converter
+                .convert(&[">>".to_string(), "file".to_string()])
+                .unwrap(),
+            "\"\" | save --append file"
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Exec builtin converter
+//!
+//! Converts the POSIX `exec` builtin. Nushell cannot replace its own process,
+//! so `exec cmd` just runs the command, and redirect-only `exec > file`
+//! becomes a note. Commands given to `exec` are converted by the main
+//! converter, which finds them with [`exec_command`].
+
+use super::{BaseBuiltinConverter, BuiltinConverter};
+use anyhow::Result;
+
+/// Note for a command that `exec` would have run in place of the shell
+pub const EXEC_NOTE: &str = "exec: runs the command instead of replacing the shell";
+
+/// Converter for the `exec` builtin
+pub struct ExecBuiltinConverter;
+
+impl BuiltinConverter for ExecBuiltinConverter {
+    fn convert(&self, args: &[String]) -> Result<String> {
+        let base = BaseBuiltinConverter;
+
+        // Only `exec` without a command reaches here
+        if args.iter().any(|arg| is_redirection(arg)) {
+            // `exec > file` redirects the rest of the script
+            Ok(format!(
+                "null{}",
+                base.note(&format!(
+                    "exec {}: Nushell cannot redirect the whole script; redirect each command instead",
+                    args.join(" ")
+                ))
+            ))
+        } else {
+            Ok("null".to_string())
+        }
+    }
+
+    fn builtin_name(&self) -> &'static str {
+        "exec"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts exec builtin to running the command directly"
+    }
+}
+
+/// The command and arguments `exec` runs, skipping its own `-c`, `-l` and
+/// `-a NAME` options; `None` when it only redirects
+pub fn exec_command(args: &[String]) -> Option<&[String]> {
+    let mut rest = args;
+    loop {
+        match rest.first().map(String::as_str) {
+            Some("-a") => rest = rest.get(2..)?,
+            Some("--") => break rest.get(1..).filter(|rest| !rest.is_empty()),
+            Some(arg) if arg.starts_with('-') && arg.len() > 1 => rest = &rest[1..],
+            Some(arg) if !is_redirection(arg) => break Some(rest),
+            _ => break None,
+        }
+    }
+}
+
+/// Check whether an argument is a redirection such as `>`, `2>&1` or `<input`
+pub fn is_redirection(arg: &str) -> bool {
+    arg.trim_start_matches(|c: char| c.is_ascii_digit())
+        .starts_with(['>', '<'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_exec_command() {
+        assert_eq!(
+            exec_command(&args(&["server", "--port", "80"])),
+            Some(args(&["server", "--port", "80"]).as_slice())
+        );
+        assert_eq!(
+            exec_command(&args(&["-a", "name", "-c", "cmd", "x"])),
+            Some(args(&["cmd", "x"]).as_slice())
+        );
+        assert_eq!(exec_command(&args(&[">", "log.txt"])), None);
+        assert_eq!(exec_command(&args(&[])), None);
+    }
+
+    #[test]
+    fn test_exec_redirect_only() {
+        let converter = ExecBuiltinConverter;
+
+        assert_eq!(
+            converter.convert(&args(&[">", "log.txt"])).unwrap(),
+            "null # Note: exec > log.txt: Nushell cannot redirect the whole script; redirect each command instead"
+        );
+        assert_eq!(
+            converter.convert(&args(&["2>&1"])).unwrap(),
+            "null # Note: exec 2>&1: Nushell cannot redirect the whole script; redirect each command instead"
+        );
+        assert_eq!(converter.convert(&args(&[])).unwrap(), "null");
+    }
+}
@@ -0,0 +1,121 @@
+//! History builtin converters
+//!
+//! Converts the interactive `history` and `fc` builtins. Nushell has its own
+//! `history` command, which also stands in for `fc -l`; editing and rerunning
+//! with `fc` has no equivalent and becomes a commented note.
+
+use super::{BaseBuiltinConverter, BuiltinConverter};
+use anyhow::Result;
+
+/// Converter for the `history` builtin
+pub struct HistoryBuiltinConverter;
+
+impl BuiltinConverter for HistoryBuiltinConverter {
+    fn convert(&self, args: &[String]) -> Result<String> {
+        let base = BaseBuiltinConverter;
+
+        match args {
+            [] => Ok("history".to_string()),
+            [flag] if flag == "-c" => Ok("history --clear".to_string()),
+            // `history N` shows the last N entries
+            [count] if count.parse::<usize>().is_ok() => Ok(format!("history | last {}", count)),
+            _ => Ok(format!(
+                "history{}",
+                base.note(&format!(
+                    "history {}: options have no Nushell equivalent",
+                    args.join(" ")
+                ))
+            )),
+        }
+    }
+
+    fn builtin_name(&self) -> &'static str {
+        "history"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts history builtin to Nushell history"
+    }
+}
+
+/// Converter for the `fc` builtin
+pub struct FcBuiltinConverter;
+
+impl BuiltinConverter for FcBuiltinConverter {
+    fn convert(&self, args: &[String]) -> Result<String> {
+        let base = BaseBuiltinConverter;
+
+        // `fc -l` lists past commands, which `history` does too
+        if args.first().is_some_and(|arg| arg == "-l") {
+            let mut result = "history".to_string();
+            if args.len() > 1 {
+                result.push_str(&base.note(&format!(
+                    "fc {}: options have no Nushell equivalent",
+                    args.join(" ")
+                )));
+            }
+            return Ok(result);
+        }
+
+        let command = if args.is_empty() {
+            "fc".to_string()
+        } else {
+            format!("fc {}", args.join(" "))
+        };
+        Ok(format!(
+            "# {}: no Nushell equivalent; use `history` to list past commands",
+            command
+        ))
+    }
+
+    fn builtin_name(&self) -> &'static str {
+        "fc"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts fc builtin to a commented note"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_builtin_converter() {
+        let converter = HistoryBuiltinConverter;
+
+        assert_eq!(converter.convert(&[]).unwrap(), "history");
+        assert_eq!(
+            converter.convert(&["10".to_string()]).unwrap(),
+            "history | last 10"
+        );
+        assert_eq!(
+            converter.convert(&["-c".to_string()]).unwrap(),
+            "history --clear"
+        );
+        assert_eq!(
+            converter
+                .convert(&["-d".to_string(), "3".to_string()])
+                .unwrap(),
+            "history # Note: history -d 3: options have no Nushell equivalent"
+        );
+    }
+
+    #[test]
+    fn test_fc_builtin_converter() {
+        let converter = FcBuiltinConverter;
+
+        assert_eq!(converter.convert(&["-l".to_string()]).unwrap(), "history");
+        assert_eq!(
+            converter
+                .convert(&["-l".to_string(), "-5".to_string()])
+                .unwrap(),
+            "history # Note: fc -l -5: options have no Nushell equivalent"
+        );
+        assert_eq!(
+            converter.convert(&["-s".to_string()]).unwrap(),
+            "# fc -s: no Nushell equivalent; use `history` to list past commands"
+        );
+    }
+}
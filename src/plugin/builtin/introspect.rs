@@ -0,0 +1,189 @@
+//! Shell introspection builtin converters
+//!
+//! Converts POSIX `type`, `hash` and `command` builtins to Nushell equivalents
+
+use super::{BaseBuiltinConverter, BuiltinConverter};
+use anyhow::Result;
+
+/// Converter for the `type` builtin
+pub struct TypeBuiltinConverter;
+
+impl BuiltinConverter for TypeBuiltinConverter {
+    fn convert(&self, args: &[String]) -> Result<String> {
+        let base = BaseBuiltinConverter;
+
+        // `which` is the closest equivalent; it also reports builtins and aliases
+        let names: Vec<String> = args
+            .iter()
+            .filter(|arg| !arg.starts_with('-'))
+            .cloned()
+            .collect();
+        if names.is_empty() {
+            Ok("which".to_string())
+        } else {
+            Ok(format!("which {}", base.format_args(&names)))
+        }
+    }
+
+    fn builtin_name(&self) -> &'static str {
+        "type"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts type lookups to Nushell which commands"
+    }
+}
+
+/// Converter for the `hash` builtin
+pub struct HashBuiltinConverter;
+
+impl BuiltinConverter for HashBuiltinConverter {
+    fn convert(&self, args: &[String]) -> Result<String> {
+        let base = BaseBuiltinConverter;
+
+        // Nushell does not cache command locations
+        if args.is_empty() {
+            Ok("# hash: Nushell does not cache command paths".to_string())
+        } else {
+            Ok(format!(
+                "# hash {}: Nushell does not cache command paths",
+                base.format_args(args)
+            ))
+        }
+    }
+
+    fn builtin_name(&self) -> &'static str {
+        "hash"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts hash builtin to a no-op comment"
+    }
+}
+
+/// Skip the `-p` and `--` options of `command`, leaving `-v`/`-V` and operands
+pub fn command_operands(args: &[String]) -> &[String] {
+    let skip = args
+        .iter()
+        .take_while(|arg| *arg == "-p" || *arg == "--")
+        .count();
+    &args[skip..]
+}
+
+/// Converter for the `command` builtin
+pub struct CommandBuiltinConverter;
+
+impl BuiltinConverter for CommandBuiltinConverter {
+    fn convert(&self, args: &[String]) -> Result<String> {
+        let base = BaseBuiltinConverter;
+        let args = command_operands(args);
+
+        match args.first().map(String::as_str) {
+            Some("-v") | Some("-V") => {
+                // Report how a command name would be resolved
+                let names = &args[1..];
+                if names.is_empty() {
+                    Ok("which".to_string())
+                } else {
+                    Ok(format!("which {}", base.format_args(names)))
+                }
+            }
+            None => Ok("command".to_string()),
+            Some(name) => {
+                // Run the external command directly, bypassing custom commands
+                let rest = &args[1..];
+                if rest.is_empty() {
+                    Ok(format!("^{}", name))
+                } else {
+                    Ok(format!("^{} {}", name, base.format_args(rest)))
+                }
+            }
+        }
+    }
+
+    fn builtin_name(&self) -> &'static str {
+        "command"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts command lookups and invocations to Nushell"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_builtin_converter() {
+        let converter = TypeBuiltinConverter;
+
+        assert_eq!(converter.convert(&["ls".to_string()]).unwrap(), "which ls");
+        assert_eq!(
+            converter
+                .convert(&["-a".to_string(), "ls".to_string(), "cd".to_string()])
+                .unwrap(),
+            "which ls cd"
+        );
+    }
+
+    #[test]
+    fn test_hash_builtin_converter() {
+        let converter = HashBuiltinConverter;
+
+        assert_eq!(
+            converter.convert(&[]).unwrap(),
+            "# hash: Nushell does not cache command paths"
+        );
+        assert_eq!(
+            converter.convert(&["-r".to_string()]).unwrap(),
+            "# hash -r: Nushell does not cache command paths"
+        );
+    }
+
+    #[test]
+    fn test_command_builtin_converter() {
+        let converter = CommandBuiltinConverter;
+
+        // Command lookup
+        assert_eq!(
+            converter
+                .convert(&["-v".to_string(), "git".to_string()])
+                .unwrap(),
+            "which git"
+        );
+
+        // Verbose lookup
+        assert_eq!(
+            converter
+                .convert(&["-V".to_string(), "ls".to_string()])
+                .unwrap(),
+            "which ls"
+        );
+
+        // Lookup without a name
+        assert_eq!(converter.convert(&["-v".to_string()]).unwrap(), "which");
+
+        // Direct invocation bypasses custom commands
+        assert_eq!(
+            converter
+                .convert(&["cat".to_string(), "f".to_string()])
+                .unwrap(),
+            "^cat f"
+        );
+
+        // `-p` only changes the search path
+        assert_eq!(
+            converter
+                .convert(&["-p".to_string(), "ls".to_string()])
+                .unwrap(),
+            "^ls"
+        );
+        assert_eq!(
+            converter
+                .convert(&["-p".to_string(), "-v".to_string(), "ls".to_string()])
+                .unwrap(),
+            "which ls"
+        );
+    }
+}
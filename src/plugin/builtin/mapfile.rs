@@ -0,0 +1,139 @@
+//! Mapfile builtin converter
+//!
+//! Converts Bash `mapfile`/`readarray` builtins, which read lines into an
+//! array, to `let` bindings of Nushell `lines` output
+
+use super::{BaseBuiltinConverter, BuiltinConverter};
+use anyhow::Result;
+
+/// Converter for the `mapfile` builtin
+pub struct MapfileBuiltinConverter;
+
+impl BuiltinConverter for MapfileBuiltinConverter {
+    fn convert(&self, args: &[String]) -> Result<String> {
+        let base = BaseBuiltinConverter;
+
+        let mut variable = "MAPFILE".to_string();
+        let mut input = None;
+        let mut count = None;
+        let mut skip = None;
+
+        let mut i = 0;
+        while i < args.len() {
+            match args[i].as_str() {
+                // Nushell's `lines` already strips the trailing newlines
+                "-t" => {}
+                "-n" => {
+                    i += 1;
+                    count = args.get(i).cloned();
+                }
+                "-s" => {
+                    i += 1;
+                    skip = args.get(i).cloned();
+                }
+                "<" => {
+                    i += 1;
+                    input = args.get(i).cloned();
+                }
+                arg if arg.starts_with('<') => input = Some(arg[1..].to_string()),
+                arg if arg.starts_with('-') => {
+                    // Unknown flag, skip
+                }
+                arg => variable = arg.to_string(),
+            }
+            i += 1;
+        }
+
+        let mut value = match input {
+            Some(file) => format!("open {} | lines", base.quote_arg(&file)),
+            None => "$in | lines".to_string(),
+        };
+        if let Some(skip) = skip {
+            value.push_str(&format!(" | skip {}", skip));
+        }
+        // `-n 0` means no limit
+        if let Some(count) = count.filter(|count| count != "0") {
+            value.push_str(&format!(" | first {}", count));
+        }
+
+        Ok(format!("let {} = ({})", variable, value))
+    }
+
+    fn builtin_name(&self) -> &'static str {
+        "mapfile"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts mapfile builtin to a let binding of lines"
+    }
+}
+
+/// Converter for the `readarray` builtin, a synonym for `mapfile`
+pub struct ReadarrayBuiltinConverter;
+
+impl BuiltinConverter for ReadarrayBuiltinConverter {
+    fn convert(&self, args: &[String]) -> Result<String> {
+        MapfileBuiltinConverter.convert(args)
+    }
+
+    fn builtin_name(&self) -> &'static str {
+        "readarray"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts readarray builtin to a let binding of lines"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_mapfile_builtin_converter() {
+        let converter = MapfileBuiltinConverter;
+
+        assert_eq!(
+            converter
+                .convert(&args(&["-t", "arr", "<", "file"]))
+                .unwrap(),
+            "let arr = (open file | lines)"
+        );
+        assert_eq!(
+            converter.convert(&args(&["-t", "arr"])).unwrap(),
+            "let arr = ($in | lines)"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&["-t", "-s", "1", "-n", "5", "rows", "<data.txt"]))
+                .unwrap(),
+            "let rows = (open data.txt | lines | skip 1 | first 5)"
+        );
+
+        // Without a name the lines go to MAPFILE
+        assert_eq!(
+            converter.convert(&[]).unwrap(),
+            "let MAPFILE = ($in | lines)"
+        );
+    }
+
+    #[test]
+    fn test_readarray_builtin_converter() {
+        let converter = ReadarrayBuiltinConverter;
+
+        assert_eq!(
+            converter.convert(&args(&["arr"])).unwrap(),
+            "let arr = ($in | lines)"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&["-t", "arr", "<", "file"]))
+                .unwrap(),
+            "let arr = (open file | lines)"
+        );
+    }
+}
@@ -3,7 +3,10 @@
 //! This module contains converters for POSIX shell builtin commands that are
 //! built into the shell itself rather than being external utilities.
 
+use super::expansion::is_nu_expression;
+use super::sus::NOTE_MARKER;
 use anyhow::Result;
+use std::collections::HashMap;
 
 /// Trait for converting POSIX builtin commands to Nushell syntax
 pub trait BuiltinConverter {
@@ -25,7 +28,9 @@ fn description(&self) -> &'static str {
 impl BaseBuiltinConverter {
     /// Quote an argument if it contains spaces or special characters
     pub fn quote_arg(&self, arg: &str) -> String {
-        if arg.contains(' ') || arg.contains('$') || arg.contains('*') || arg.contains('?') {
+        if is_nu_expression(arg) {
+            arg.to_string()
+        } else if arg.contains(' ') || arg.contains('$') || arg.contains('*') || arg.contains('?') {
             format!("\"{}\"", arg.replace('"', "\\\""))
         } else {
             arg.to_string()
@@ -39,75 +44,106 @@ pub fn format_args(&self, args: &[String]) -> String {
             .collect::<Vec<_>>()
             .join(" ")
     }
+
+    /// Format an advisory `# Note:` comment to append to a conversion
+    ///
+    /// The converter moves notes onto their own line above the statement.
+    pub fn note(&self, message: &str) -> String {
+        format!("{}{}", NOTE_MARKER, message)
+    }
 }
 
 // Builtin converter modules
 pub mod cd;
+pub mod colon;
+pub mod exec;
 pub mod exit;
 pub mod false_builtin;
+pub mod history;
+pub mod introspect;
 pub mod jobs;
 pub mod kill;
+pub mod mapfile;
 pub mod pwd;
 pub mod read;
 pub mod test;
+pub mod times;
 pub mod true_builtin;
+pub mod ulimit;
+pub mod wait;
 
 // Re-export all converters
 pub use cd::CdBuiltinConverter;
+pub use colon::ColonBuiltinConverter;
+pub use exec::ExecBuiltinConverter;
 pub use exit::ExitBuiltinConverter;
 pub use false_builtin::FalseBuiltinConverter;
+pub use history::{FcBuiltinConverter, HistoryBuiltinConverter};
+pub use introspect::{CommandBuiltinConverter, HashBuiltinConverter, TypeBuiltinConverter};
 pub use jobs::JobsBuiltinConverter;
 pub use kill::KillBuiltinConverter;
+pub use mapfile::{MapfileBuiltinConverter, ReadarrayBuiltinConverter};
 pub use pwd::PwdBuiltinConverter;
 pub use read::ReadBuiltinConverter;
 pub use test::TestBuiltinConverter;
+pub use times::TimesBuiltinConverter;
 pub use true_builtin::TrueBuiltinConverter;
+pub use ulimit::UlimitBuiltinConverter;
+pub use wait::WaitBuiltinConverter;
 
 /// Registry of all builtin converters
 pub struct BuiltinRegistry {
-    converters: Vec<Box<dyn BuiltinConverter>>,
+    converters: HashMap<&'static str, Box<dyn BuiltinConverter>>,
 }
 
 impl BuiltinRegistry {
     /// Create a new builtin registry with all standard converters
     pub fn new() -> Self {
         let mut registry = Self {
-            converters: Vec::new(),
+            converters: HashMap::new(),
         };
 
         // Register all standard builtin converters
         registry.register(Box::new(CdBuiltinConverter));
+        registry.register(Box::new(ColonBuiltinConverter));
+        registry.register(Box::new(CommandBuiltinConverter));
+        registry.register(Box::new(ExecBuiltinConverter));
         registry.register(Box::new(ExitBuiltinConverter));
         registry.register(Box::new(FalseBuiltinConverter));
+        registry.register(Box::new(FcBuiltinConverter));
+        registry.register(Box::new(HashBuiltinConverter));
+        registry.register(Box::new(HistoryBuiltinConverter));
         registry.register(Box::new(JobsBuiltinConverter));
         registry.register(Box::new(KillBuiltinConverter));
+        registry.register(Box::new(MapfileBuiltinConverter));
         registry.register(Box::new(PwdBuiltinConverter));
         registry.register(Box::new(ReadBuiltinConverter));
+        registry.register(Box::new(ReadarrayBuiltinConverter));
         registry.register(Box::new(TestBuiltinConverter));
+        registry.register(Box::new(TimesBuiltinConverter));
         registry.register(Box::new(TrueBuiltinConverter));
+        registry.register(Box::new(TypeBuiltinConverter));
+        registry.register(Box::new(UlimitBuiltinConverter));
+        registry.register(Box::new(WaitBuiltinConverter));
 
         registry
     }
 
     /// Register a new builtin converter
     pub fn register(&mut self, converter: Box<dyn BuiltinConverter>) {
-        self.converters.push(converter);
+        self.converters.insert(converter.builtin_name(), converter);
     }
 
     /// Find a converter for the given builtin name
     pub fn find_converter(&self, builtin: &str) -> Option<&dyn BuiltinConverter> {
-        self.converters
-            .iter()
-            .find(|conv| conv.builtin_name() == builtin)
-            .map(|conv| conv.as_ref())
+        self.converters.get(builtin).map(|conv| conv.as_ref())
     }
 
     /// Get all registered builtin names
     pub fn get_builtin_names(&self) -> Vec<&'static str> {
-        self.converters
-            .iter()
-            .map(|conv| conv.builtin_name())
-            .collect()
+        let mut names: Vec<&'static str> = self.converters.keys().copied().collect();
+        names.sort_unstable();
+        names
     }
 
     /// Convert a builtin command using the appropriate converter
@@ -148,12 +184,31 @@ fn test_builtin_registry() {
         assert!(registry.find_converter("exit").is_some());
         assert!(registry.find_converter("pwd").is_some());
         assert!(registry.find_converter("test").is_some());
+        assert!(registry.find_converter("history").is_some());
+        assert!(registry.find_converter("fc").is_some());
+        assert!(registry.find_converter("mapfile").is_some());
+        assert!(registry.find_converter("readarray").is_some());
+        assert!(registry.find_converter(":").is_some());
+        assert!(registry.find_converter("exec").is_some());
         assert!(registry.find_converter("nonexistent").is_none());
 
         // Test that [ is handled as alias for test
         assert!(registry.convert_builtin("[", &["arg".to_string()]).is_ok());
     }
 
+    #[test]
+    fn test_builtin_registry_lookup() {
+        let registry = BuiltinRegistry::new();
+
+        // Every registered name resolves to the converter for that name
+        let names = registry.get_builtin_names();
+        assert!(names.windows(2).all(|pair| pair[0] < pair[1]));
+        for name in names {
+            let converter = registry.find_converter(name).unwrap();
+            assert_eq!(converter.builtin_name(), name);
+        }
+    }
+
     #[test]
     fn test_base_builtin_converter_quoting() {
         let base = BaseBuiltinConverter;
@@ -162,6 +217,7 @@ fn test_base_builtin_converter_quoting() {
         assert_eq!(base.quote_arg("with space"), "\"with space\"");
         assert_eq!(base.quote_arg("with$var"), "\"with$var\"");
         assert_eq!(base.quote_arg("with*glob"), "\"with*glob\"");
+        assert_eq!(base.quote_arg("($rest | length)"), "($rest | length)");
     }
 
     #[test]
@@ -3,6 +3,7 @@
 //! Converts POSIX `test` and `[` builtin commands to Nushell conditional expressions
 
 use super::{BaseBuiltinConverter, BuiltinConverter};
+use crate::plugin::expansion::is_nu_expression;
 use anyhow::Result;
 
 /// Converter for the `test` builtin
@@ -12,10 +13,19 @@ impl BuiltinConverter for TestBuiltinConverter {
     fn convert(&self, args: &[String]) -> Result<String> {
         let base = BaseBuiltinConverter;
 
+        // `[ expr ]` tests the same expression as `test expr`
+        let args = args.strip_prefix(&["[".to_string()]).unwrap_or(args);
+        let args = args.strip_suffix(&["]".to_string()]).unwrap_or(args);
+
         if args.is_empty() {
             return Ok("false".to_string());
         }
 
+        // A leading `!` negates the rest of the expression
+        if args[0] == "!" && args.len() > 1 {
+            return Ok(negate(&self.convert(&args[1..])?));
+        }
+
         // Handle different test patterns
         match args.len() {
             1 => self.convert_unary_test(args, &base),
@@ -54,7 +64,7 @@ fn convert_binary_test(&self, args: &[String], base: &BaseBuiltinConverter) -> R
             // File tests
             "-f" => Ok(format!("({} | path exists)", base.quote_arg(arg))),
             "-d" => Ok(format!("({} | path type) == \"dir\"", base.quote_arg(arg))),
-            "-e" => Ok(format!("({} | path exists)", base.quote_arg(arg))),
+            "-e" | "-a" => Ok(format!("({} | path exists)", base.quote_arg(arg))),
             "-r" => Ok(format!(
                 "({} | path exists and ({} | path type) == \"file\")",
                 base.quote_arg(arg),
@@ -85,8 +95,21 @@ fn convert_binary_test(&self, args: &[String], base: &BaseBuiltinConverter) -> R
             // String tests
             "-z" => Ok(format!("({} | is-empty)", base.quote_arg(arg))),
             "-n" => Ok(format!("({} | is-not-empty)", base.quote_arg(arg))),
+            // Variable is set; upper-case names are taken to be environment variables
+            "-v" if arg
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_') =>
+            {
+                Ok(format!("(\"{}\" in $env)", arg))
+            }
+            "-v" => Ok(format!("(\"${}\" in (scope variables).name)", arg)),
+            // Shell options have no Nushell counterpart
+            "-o" => Ok(format!(
+                "false{}",
+                base.note(&format!("test -o {}: Nushell has no shell options", arg))
+            )),
             // Negation
-            "!" => Ok(format!("not ({})", self.convert(&[arg.clone()])?)),
+            "!" => Ok(negate(&self.convert(&[arg.clone()])?)),
             _ => Ok(format!("test {} {}", op, base.quote_arg(arg))),
         }
     }
@@ -110,12 +133,22 @@ fn convert_ternary_test(&self, args: &[String], base: &BaseBuiltinConverter) ->
                 base.quote_arg(right)
             )),
             // Numeric comparisons
-            "-eq" => Ok(format!("{} == {}", left, right)),
-            "-ne" => Ok(format!("{} != {}", left, right)),
-            "-lt" => Ok(format!("{} < {}", left, right)),
-            "-le" => Ok(format!("{} <= {}", left, right)),
-            "-gt" => Ok(format!("{} > {}", left, right)),
-            "-ge" => Ok(format!("{} >= {}", left, right)),
+            "-eq" | "-ne" | "-lt" | "-le" | "-gt" | "-ge" => {
+                let operator = match op.as_str() {
+                    "-eq" => "==",
+                    "-ne" => "!=",
+                    "-lt" => "<",
+                    "-le" => "<=",
+                    "-gt" => ">",
+                    _ => ">=",
+                };
+                Ok(format!(
+                    "{} {} {}",
+                    numeric_operand(left),
+                    operator,
+                    numeric_operand(right)
+                ))
+            }
             // File comparisons
             "-nt" => Ok(format!(
                 "({} | path exists) and ({} | path exists) and (({} | get modified) > ({} | get modified))",
@@ -168,6 +201,20 @@ fn convert_bracket_test(
         }
     }
 
+    /// Convert one operand of a logical expression, honouring a leading `!`
+    fn convert_operand(&self, part: &[String], base: &BaseBuiltinConverter) -> Result<String> {
+        if part.len() > 1 && part[0] == "!" {
+            return Ok(negate(&self.convert_operand(&part[1..], base)?));
+        }
+
+        match part.len() {
+            1 => self.convert_unary_test(part, base),
+            2 => self.convert_binary_test(part, base),
+            3 => self.convert_ternary_test(part, base),
+            _ => Ok(format!("test {}", base.format_args(part))),
+        }
+    }
+
     /// Convert complex test expressions with logical operators
     fn convert_complex_test(&self, args: &[String], base: &BaseBuiltinConverter) -> Result<String> {
         // Handle [ ... ] wrapper
@@ -188,18 +235,15 @@ fn convert_complex_test(&self, args: &[String], base: &BaseBuiltinConverter) ->
 
         while i < actual_args.len() {
             match actual_args[i].as_str() {
-                "-a" | "&&" => {
-                    if !current_part.is_empty() {
-                        parts.push((current_part.clone(), "and".to_string()));
-                        current_part.clear();
-                    }
+                // In operand position `-a` is the unary file-exists test
+                "-a" | "&&" if !current_part.is_empty() => {
+                    parts.push((current_part.clone(), "and".to_string()));
+                    current_part.clear();
                     i += 1;
                 }
-                "-o" | "||" => {
-                    if !current_part.is_empty() {
-                        parts.push((current_part.clone(), "or".to_string()));
-                        current_part.clear();
-                    }
+                "-o" | "||" if !current_part.is_empty() => {
+                    parts.push((current_part.clone(), "or".to_string()));
+                    current_part.clear();
                     i += 1;
                 }
                 _ => {
@@ -228,12 +272,7 @@ fn convert_complex_test(&self, args: &[String], base: &BaseBuiltinConverter) ->
                 result.push_str(" ");
             }
 
-            let part_result = match part.len() {
-                1 => self.convert_unary_test(part, base)?,
-                2 => self.convert_binary_test(part, base)?,
-                3 => self.convert_ternary_test(part, base)?,
-                _ => format!("test {}", base.format_args(part)),
-            };
+            let part_result = self.convert_operand(part, base)?;
 
             result.push_str(&format!("({})", part_result));
         }
@@ -246,6 +285,24 @@ fn convert_complex_test(&self, args: &[String], base: &BaseBuiltinConverter) ->
     }
 }
 
+/// Format a numeric comparison operand, converting variables to integers
+fn numeric_operand(arg: &str) -> String {
+    if arg.starts_with('$') && is_nu_expression(arg) {
+        format!("({} | into int)", arg)
+    } else {
+        arg.to_string()
+    }
+}
+
+/// Wrap a converted condition in `not`, reusing its parentheses when it has them
+fn negate(condition: &str) -> String {
+    if condition.starts_with('(') && is_nu_expression(condition) {
+        format!("not {}", condition)
+    } else {
+        format!("not ({})", condition)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -390,4 +447,125 @@ fn test_complex_expressions() {
             "((\"file\" | path exists)) and ((\"file\" | path exists and (\"file\" | path type) == \"file\"))"
         );
     }
+
+    #[test]
+    fn test_deprecated_a_operator() {
+        let converter = TestBuiltinConverter;
+
+        // Two arguments: `-a` is the unary file-exists test
+        assert_eq!(
+            converter
+                .convert(&["-a".to_string(), "file".to_string()])
+                .unwrap(),
+            "(file | path exists)"
+        );
+
+        // Between operands: `-a` is AND
+        assert_eq!(
+            converter
+                .convert(&[
+                    "-f".to_string(),
+                    "a".to_string(),
+                    "-a".to_string(),
+                    "-f".to_string(),
+                    "b".to_string()
+                ])
+                .unwrap(),
+            "((a | path exists)) and ((b | path exists))"
+        );
+    }
+
+    #[test]
+    fn test_shell_option_check() {
+        let converter = TestBuiltinConverter;
+
+        // Two arguments: `-o` names a shell option
+        let result = converter
+            .convert(&["-o".to_string(), "errexit".to_string()])
+            .unwrap();
+        assert_eq!(
+            result,
+            "false # Note: test -o errexit: Nushell has no shell options"
+        );
+        assert!(!result.contains(" or "));
+
+        // Between operands: `-o` is OR
+        assert_eq!(
+            converter
+                .convert(&[
+                    "-f".to_string(),
+                    "a".to_string(),
+                    "-o".to_string(),
+                    "-d".to_string(),
+                    "b".to_string()
+                ])
+                .unwrap(),
+            "((a | path exists)) or ((b | path type) == \"dir\")"
+        );
+    }
+
+    #[test]
+    fn test_variable_set_check() {
+        let converter = TestBuiltinConverter;
+        let args = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            converter.convert(&args(&["[", "-v", "HOME", "]"])).unwrap(),
+            "(\"HOME\" in $env)"
+        );
+        assert_eq!(
+            converter.convert(&args(&["-v", "count"])).unwrap(),
+            "(\"$count\" in (scope variables).name)"
+        );
+    }
+
+    #[test]
+    fn test_negation_in_brackets() {
+        let converter = TestBuiltinConverter;
+        let args = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            converter
+                .convert(&args(&["[", "!", "-f", "f", "]"]))
+                .unwrap(),
+            "not (f | path exists)"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&["[", "!", "$x", "=", "y", "]"]))
+                .unwrap(),
+            "not ($x == y)"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&["[", "!", "-z", "$s", "]"]))
+                .unwrap(),
+            "not ($s | is-empty)"
+        );
+
+        // `[` as a command name leaves only the closing bracket in the arguments
+        assert_eq!(
+            converter.convert(&args(&["!", "-f", "f", "]"])).unwrap(),
+            "not (f | path exists)"
+        );
+    }
+
+    #[test]
+    fn test_numeric_operands() {
+        let converter = TestBuiltinConverter;
+        let args = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            converter.convert(&args(&["$n", "-gt", "5"])).unwrap(),
+            "($n | into int) > 5"
+        );
+        assert_eq!(
+            converter.convert(&args(&["5", "-lt", "$m"])).unwrap(),
+            "5 < ($m | into int)"
+        );
+        assert_eq!(
+            converter.convert(&args(&["$a", "-eq", "$b"])).unwrap(),
+            "($a | into int) == ($b | into int)"
+        );
+    }
 }
@@ -0,0 +1,39 @@
+//! Times builtin converter
+//!
+//! Converts POSIX `times` builtin commands to a commented note
+
+use super::BuiltinConverter;
+use anyhow::Result;
+
+/// Converter for the `times` builtin
+pub struct TimesBuiltinConverter;
+
+impl BuiltinConverter for TimesBuiltinConverter {
+    fn convert(&self, _args: &[String]) -> Result<String> {
+        // Nushell does not track accumulated process times
+        Ok("# times: no Nushell equivalent; use `timeit { ... }` to time a block".to_string())
+    }
+
+    fn builtin_name(&self) -> &'static str {
+        "times"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts times builtin to a commented note"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_times_builtin_converter() {
+        let converter = TimesBuiltinConverter;
+
+        assert_eq!(
+            converter.convert(&[]).unwrap(),
+            "# times: no Nushell equivalent; use `timeit { ... }` to time a block"
+        );
+    }
+}
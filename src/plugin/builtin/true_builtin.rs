@@ -2,18 +2,18 @@
 //!
 //! Converts POSIX `true` builtin commands to Nushell `true` commands
 
-use super::{BaseBuiltinConverter, BuiltinConverter};
+use super::colon::truncation;
+use super::BuiltinConverter;
 use anyhow::Result;
 
 /// Converter for the `true` builtin
 pub struct TrueBuiltinConverter;
 
 impl BuiltinConverter for TrueBuiltinConverter {
-    fn convert(&self, _args: &[String]) -> Result<String> {
-        // TODO: args parameter is not used in current implementation
-        // The true builtin ignores all arguments and always returns success
-        // In Nushell, `true` also ignores arguments
-        Ok("true".to_string())
+    fn convert(&self, args: &[String]) -> Result<String> {
+        // The true builtin ignores its arguments and always returns success,
+        // so only a `> file` redirection changes anything
+        Ok(truncation(args).unwrap_or_else(|| "true".to_string()))
     }
 
     fn builtin_name(&self) -> &'static str {
@@ -49,5 +49,13 @@ fn test_true_builtin_converter() {
 
         // True with flags (should be ignored)
         assert_eq!(converter.convert(&["--help".to_string()]).unwrap(), "true");
+
+        // Redirecting nothing truncates the file
+        assert_eq!(
+            converter
+                .convert(&[">".to_string(), "file".to_string()])
+                .unwrap(),
+            "\"\" | save -f file"
+        );
     }
 }
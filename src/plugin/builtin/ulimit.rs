@@ -0,0 +1,55 @@
+//! Ulimit builtin converter
+//!
+//! Converts POSIX `ulimit` builtin commands to a commented note
+
+use super::{BaseBuiltinConverter, BuiltinConverter};
+use anyhow::Result;
+
+/// Converter for the `ulimit` builtin
+pub struct UlimitBuiltinConverter;
+
+impl BuiltinConverter for UlimitBuiltinConverter {
+    fn convert(&self, args: &[String]) -> Result<String> {
+        let base = BaseBuiltinConverter;
+
+        // Resource limits must be set before Nushell starts
+        let command = if args.is_empty() {
+            "ulimit".to_string()
+        } else {
+            format!("ulimit {}", base.format_args(args))
+        };
+        Ok(format!(
+            "# {}: no Nushell equivalent; set resource limits in the parent shell",
+            command
+        ))
+    }
+
+    fn builtin_name(&self) -> &'static str {
+        "ulimit"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts ulimit builtin to a commented note"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ulimit_builtin_converter() {
+        let converter = UlimitBuiltinConverter;
+
+        assert_eq!(
+            converter
+                .convert(&["-n".to_string(), "1024".to_string()])
+                .unwrap(),
+            "# ulimit -n 1024: no Nushell equivalent; set resource limits in the parent shell"
+        );
+        assert_eq!(
+            converter.convert(&[]).unwrap(),
+            "# ulimit: no Nushell equivalent; set resource limits in the parent shell"
+        );
+    }
+}
@@ -0,0 +1,61 @@
+//! Wait builtin converter
+//!
+//! Converts POSIX `wait` builtin commands to Nushell job commands
+
+use super::{BaseBuiltinConverter, BuiltinConverter};
+use anyhow::Result;
+
+/// Converter for the `wait` builtin
+pub struct WaitBuiltinConverter;
+
+impl BuiltinConverter for WaitBuiltinConverter {
+    fn convert(&self, args: &[String]) -> Result<String> {
+        let base = BaseBuiltinConverter;
+
+        if args.is_empty() {
+            // Wait for every background job
+            return Ok(
+                "# wait: waiting for all background jobs\njob list | each { |job| job wait $job.id }"
+                    .to_string(),
+            );
+        }
+
+        // Wait for specific jobs or process IDs
+        let ids: Vec<String> = args
+            .iter()
+            .map(|arg| base.quote_arg(arg.trim_start_matches('%')))
+            .collect();
+        Ok(format!(
+            "# wait {}: waiting for the given jobs\n[{}] | each {{ |id| job wait $id }}",
+            base.format_args(args),
+            ids.join(", ")
+        ))
+    }
+
+    fn builtin_name(&self) -> &'static str {
+        "wait"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts wait builtin to Nushell job waits"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wait_builtin_converter() {
+        let converter = WaitBuiltinConverter;
+
+        assert_eq!(
+            converter.convert(&[]).unwrap(),
+            "# wait: waiting for all background jobs\njob list | each { |job| job wait $job.id }"
+        );
+        assert_eq!(
+            converter.convert(&["$pid".to_string()]).unwrap(),
+            "# wait $pid: waiting for the given jobs\n[$pid] | each { |id| job wait $id }"
+        );
+    }
+}
@@ -1,17 +1,52 @@
+use super::builtin::exec::{exec_command, EXEC_NOTE};
+use super::builtin::introspect::command_operands;
 use super::builtin::BuiltinRegistry;
+use super::expansion::{
+    arithmetic_expression, default_assignments, escape_literal_parens, expand_word, glob_to_regex,
+    interpolate_word, is_nu_expression, references_positional_params,
+};
 use super::parser_posix::{
-    AndOrData, AndOrOperator, Assignment, CompoundCommandData, CompoundCommandKind, ListData,
-    ListSeparator, PipelineData, PosixCommand, PosixScript, Redirection, RedirectionOp,
-    SimpleCommandData,
+    parse_posix_script, split_words, AndOrData, AndOrOperator, Assignment, CaseItemData,
+    CaseTerminator, CompoundCommandData, CompoundCommandKind, ElifPart, ListData, ListSeparator,
+    PipelineData, PosixCommand, PosixScript, Redirection, RedirectionOp, SimpleCommandData,
 };
-use super::sus::CommandRegistry;
+use super::sus::{echo_string, CommandRegistry, EchoConverter, EchoStyle, NOTE_MARKER};
 use anyhow::Result;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// How many commands a conversion translated and how many it passed through
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConversionStats {
+    /// Simple commands seen, including assignments
+    pub commands_total: usize,
+    /// Commands translated by a builtin or command converter
+    pub converted: usize,
+    /// Commands with no converter, emitted unchanged or run with `^`
+    pub passthrough: usize,
+    /// Advisory notes and commands left unconverted
+    pub warnings: usize,
+}
 
 pub struct PosixToNuConverter {
     // Configuration options for conversion
     _use_modern_syntax: bool,
     _preserve_comments: bool,
     _convert_pipes: bool,
+    preserve_exit_semantics: bool,
+    string_capture: bool,
+    exit_as_return: bool,
+    external_prefix: bool,
+    notes: bool,
+    echo_style: EchoStyle,
+    suppressed_notes: RefCell<Vec<String>>,
+    pending_notes: RefCell<Vec<String>>,
+    in_function: Cell<bool>,
+    pipefail: Cell<bool>,
+    scopes: RefCell<Vec<Scope>>,
+    stats: Cell<ConversionStats>,
+    passed_through: Cell<bool>,
     command_registry: CommandRegistry,
     builtin_registry: BuiltinRegistry,
 }
@@ -22,25 +57,461 @@ pub fn new() -> Self {
             _use_modern_syntax: true,
             _preserve_comments: true,
             _convert_pipes: true,
+            preserve_exit_semantics: false,
+            string_capture: false,
+            exit_as_return: false,
+            external_prefix: false,
+            notes: true,
+            echo_style: EchoStyle::default(),
+            suppressed_notes: RefCell::new(Vec::new()),
+            pending_notes: RefCell::new(Vec::new()),
+            in_function: Cell::new(false),
+            pipefail: Cell::new(false),
+            scopes: RefCell::new(Vec::new()),
+            stats: Cell::new(ConversionStats::default()),
+            passed_through: Cell::new(false),
             command_registry: CommandRegistry::new(),
             builtin_registry: BuiltinRegistry::new(),
         }
     }
 
+    /// Choose whether `echo` converts to `print` (the default) or Nushell's `echo`
+    pub fn with_echo_style(mut self, style: EchoStyle) -> Self {
+        self.echo_style = style;
+        self.command_registry
+            .register(Box::new(EchoConverter::new(style)));
+        self
+    }
+
+    /// Convert `&&`/`||` between commands into exit-code checks instead of boolean operators
+    pub fn with_preserve_exit_semantics(mut self, preserve: bool) -> Self {
+        self.preserve_exit_semantics = preserve;
+        self
+    }
+
+    /// Capture `$(...)` assignments as trimmed strings, like the shell does
+    pub fn with_string_capture(mut self, capture: bool) -> Self {
+        self.string_capture = capture;
+        self
+    }
+
+    /// Convert `exit` inside function bodies to `return`; top-level `exit` is kept
+    pub fn with_exit_as_return(mut self, exit_as_return: bool) -> Self {
+        self.exit_as_return = exit_as_return;
+        self
+    }
+
+    /// Prefix commands with no known conversion with `^` so they always run externally
+    pub fn with_external_prefix(mut self, prefix: bool) -> Self {
+        self.external_prefix = prefix;
+        self
+    }
+
+    /// Choose whether converters append advisory `# Note:` comments to their output
+    ///
+    /// Suppressed notes are collected and available from [`Self::suppressed_notes`].
+    pub fn with_notes(mut self, notes: bool) -> Self {
+        self.notes = notes;
+        self
+    }
+
+    /// Notes left out of the output because of `with_notes(false)`
+    pub fn suppressed_notes(&self) -> Vec<String> {
+        self.suppressed_notes.borrow().clone()
+    }
+
+    /// Move the notes trailing a converted command out of it, to be printed
+    /// above its statement or collected as suppressed
+    fn take_notes(&self, mut converted: String) -> String {
+        let Some(pos) = converted.find(NOTE_MARKER) else {
+            return converted;
+        };
+        let notes: Vec<String> = converted[pos..]
+            .split(NOTE_MARKER)
+            .filter(|note| !note.is_empty())
+            .map(str::to_string)
+            .collect();
+        self.record(|stats| stats.warnings += notes.len());
+        if self.notes {
+            self.pending_notes.borrow_mut().extend(notes);
+        } else {
+            self.suppressed_notes.borrow_mut().extend(notes);
+        }
+        converted.truncate(pos);
+        converted
+    }
+
+    /// Counts of converted and passed-through commands, across every
+    /// conversion made with this converter
+    pub fn stats(&self) -> ConversionStats {
+        self.stats.get()
+    }
+
+    /// Update the conversion counts
+    fn record(&self, update: impl FnOnce(&mut ConversionStats)) {
+        let mut stats = self.stats.get();
+        update(&mut stats);
+        self.stats.set(stats);
+    }
+
     pub fn convert(&self, script: &PosixScript) -> Result<String> {
         let mut output = String::new();
+        self.convert_to_writer(script, &mut output)?;
+        Ok(output)
+    }
+
+    /// Convert a script and write the result into `sink`, one top-level
+    /// command at a time
+    ///
+    /// Scripts wrapped in `def main` or an EXIT trap's `try` are written
+    /// once complete.
+    pub fn convert_to_writer<W: Write>(&self, script: &PosixScript, sink: &mut W) -> Result<()> {
+        let commands = &script.commands;
+        let convert_one = |command: &PosixCommand| self.convert_command(command);
+        if commands
+            .iter()
+            .any(|command| getopts_loop(command).is_some() || exit_trap_handler(command).is_some())
+        {
+            sink.write_str(&self.convert_script(commands, &convert_one)?)?;
+            return Ok(());
+        }
+
+        self.with_scope(commands, || {
+            let mut first = true;
+            let mut write_line = |line: &str| -> Result<()> {
+                if !std::mem::take(&mut first) {
+                    sink.write_char('\n')?;
+                }
+                sink.write_str(line)?;
+                Ok(())
+            };
+            for declaration in self.hoisted_declarations() {
+                write_line(&declaration)?;
+            }
+            for command in commands {
+                for line in self.convert_statement(command, &convert_one)? {
+                    write_line(&line)?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Convert a script best-effort, keeping commands that fail to convert
+    /// as `# UNCONVERTED: ...` comments quoting the original `source`
+    pub fn convert_partial(&self, script: &PosixScript, source: &str) -> String {
+        let unconverted = |command: &PosixCommand, err: anyhow::Error| {
+            self.record(|stats| stats.warnings += 1);
+            let original = command_span(command)
+                .and_then(|(start, end)| source.get(start..end))
+                .map(|text| text.replace('\n', " "))
+                .unwrap_or_else(|| err.to_string());
+            format!("# UNCONVERTED: {}", original)
+        };
+
+        self.convert_script(&script.commands, &|command| {
+            Ok(self
+                .convert_command(command)
+                .unwrap_or_else(|err| unconverted(command, err)))
+        })
+        .unwrap_or_else(|err| format!("# UNCONVERTED: {}", err))
+    }
 
-        for (i, command) in script.commands.iter().enumerate() {
-            if i > 0 {
-                output.push('\n');
+    /// Convert top-level commands with `convert_one`, wrapping a script that
+    /// parses options with getopts in `def main` and running EXIT traps last
+    fn convert_script(
+        &self,
+        commands: &[PosixCommand],
+        convert_one: &ConvertOne,
+    ) -> Result<String> {
+        match self.convert_getopts_script(commands, convert_one)? {
+            Some(main) => Ok(main),
+            None => self.convert_exit_trap_block(commands, convert_one),
+        }
+    }
+
+    /// Convert commands with `convert_one`, wrapping those after a
+    /// `trap 'cmd' EXIT` in `try` and running the cleanup once they finish
+    /// or fail
+    ///
+    /// Nushell has no EXIT traps; an `exit` in the body still skips the cleanup.
+    fn convert_exit_trap_block(
+        &self,
+        commands: &[PosixCommand],
+        convert_one: &ConvertOne,
+    ) -> Result<String> {
+        self.with_scope(commands, || {
+            let converted = self.convert_trap_commands(commands, convert_one)?;
+            let mut lines = self.hoisted_declarations();
+            if !converted.is_empty() {
+                lines.push(converted);
             }
-            let converted = self.convert_command(command)?;
-            output.push_str(&converted);
+            Ok(lines.join("\n"))
+        })
+    }
+
+    /// Convert one statement with `convert_one`, preceded by the notes from
+    /// within it on their own lines
+    fn convert_statement(
+        &self,
+        command: &PosixCommand,
+        convert_one: &ConvertOne,
+    ) -> Result<Vec<String>> {
+        let first_note = self.pending_notes.borrow().len();
+        let output = convert_one(command)?;
+        let mut lines: Vec<String> = self
+            .pending_notes
+            .borrow_mut()
+            .drain(first_note..)
+            .map(|note| format!("{}{}", NOTE_MARKER.trim_start(), note))
+            .collect();
+        if !output.is_empty() {
+            lines.push(output);
         }
+        Ok(lines)
+    }
+
+    /// Convert the commands of [`Self::convert_exit_trap_block`] within their scope
+    fn convert_trap_commands(
+        &self,
+        commands: &[PosixCommand],
+        convert_one: &ConvertOne,
+    ) -> Result<String> {
+        let convert_all = |commands: &[PosixCommand]| -> Result<Vec<String>> {
+            let mut converted = Vec::new();
+            for command in commands {
+                converted.extend(self.convert_statement(command, convert_one)?);
+            }
+            Ok(converted)
+        };
+
+        let Some((position, handler)) = commands
+            .iter()
+            .enumerate()
+            .find_map(|(i, command)| Some((i, exit_trap_handler(command)?)))
+        else {
+            return Ok(convert_all(commands)?.join("\n"));
+        };
+        let cleanup = self.convert(&parse_posix_script(&handler)?)?;
+
+        let before = convert_all(&commands[..position])?;
+        let body: String = convert_all(&commands[position + 1..])?
+            .iter()
+            .flat_map(|command| command.lines())
+            .map(|line| format!("  {}\n", line))
+            .collect();
+        let indented_cleanup: String = cleanup
+            .lines()
+            .map(|line| format!("  {}\n", line))
+            .collect();
 
+        let mut output = before.join("\n");
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        if !body.is_empty() {
+            output.push_str(&format!(
+                "try {{\n{}}} catch {{ |err|\n{}  error make {{msg: $err.msg}}\n}}\n",
+                body, indented_cleanup
+            ));
+        }
+        output.push_str(&cleanup);
         Ok(output)
     }
 
+    /// Convert `commands` with their block's variables in scope, so those
+    /// assigned more than once are declared `mut`
+    fn with_scope<T>(
+        &self,
+        commands: &[PosixCommand],
+        convert: impl FnOnce() -> Result<T>,
+    ) -> Result<T> {
+        self.scopes.borrow_mut().push(Scope::for_commands(commands));
+        let result = convert();
+        self.scopes.borrow_mut().pop();
+        result
+    }
+
+    /// Declare the variables the innermost scope first assigns in a nested
+    /// block up front, so they outlive that block
+    fn hoisted_declarations(&self) -> Vec<String> {
+        self.scopes
+            .borrow()
+            .last()
+            .map(|scope| {
+                scope
+                    .hoisted
+                    .iter()
+                    .map(|name| format!("mut {}: any = null", name))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Check whether `body` assigns a variable its scope declares `mut`,
+    /// which an `each` closure can't capture
+    fn assigns_mutable(&self, body: &[PosixCommand]) -> bool {
+        let scopes = self.scopes.borrow();
+        scopes.last().is_some_and(|scope| {
+            body_contains(body, &|cmd| {
+                cmd.name.is_empty()
+                    && cmd
+                        .assignments
+                        .iter()
+                        .any(|assignment| scope.reassigned.contains(&assignment.name))
+            })
+        })
+    }
+
+    /// Turn `let name = value` into `mut name = value` or `$name = value`
+    /// when the enclosing block assigns `name` more than once
+    fn bind(&self, name: &str, converted: String) -> String {
+        let Some(value) = converted.strip_prefix(&format!("let {} = ", name)) else {
+            return converted;
+        };
+        let mut scopes = self.scopes.borrow_mut();
+        match scopes.last_mut() {
+            Some(scope) if scope.reassigned.contains(name) => {
+                if scope.declared.insert(name.to_string()) {
+                    format!("mut {} = {}", name, value)
+                } else {
+                    format!("${} = {}", name, value)
+                }
+            }
+            _ => converted,
+        }
+    }
+
+    /// Convert a script parsing its options with a `while getopts` loop
+    /// into `def main` with one flag per option letter
+    ///
+    /// Case arms that only assign a variable name the flag after it, and an
+    /// assignment to that variable before the loop becomes the flag's default.
+    /// Other arms run when their flag is set. The `shift` past the options is
+    /// dropped.
+    fn convert_getopts_script(
+        &self,
+        commands: &[PosixCommand],
+        convert_one: &ConvertOne,
+    ) -> Result<Option<String>> {
+        let Some((position, optstring, items)) = commands
+            .iter()
+            .enumerate()
+            .find_map(|(i, command)| getopts_loop(command).map(|(o, items)| (i, o, items)))
+        else {
+            return Ok(None);
+        };
+
+        // Plain assignments before the loop give the options their defaults
+        let mut defaults = HashMap::new();
+        for command in &commands[..position] {
+            if let PosixCommand::Simple(cmd) = command {
+                if cmd.name.is_empty() && cmd.redirections.is_empty() {
+                    for assignment in &cmd.assignments {
+                        defaults.insert(assignment.name.as_str(), assignment.value.as_str());
+                    }
+                }
+            }
+        }
+
+        let mut flags = Vec::new();
+        let mut flag_names = HashSet::new();
+        let mut actions = Vec::new();
+        let mut letters = optstring.trim_start_matches(':').chars().peekable();
+        while let Some(letter) = letters.next() {
+            let takes_value = letters.next_if_eq(&':').is_some();
+            let item = items.iter().find(|item| {
+                item.patterns
+                    .iter()
+                    .any(|p| p.trim_matches(|c| c == '"' || c == '\'') == letter.to_string())
+            });
+            let assigned = item.and_then(|item| match item.body.as_slice() {
+                [PosixCommand::Simple(cmd)] if cmd.name.is_empty() => {
+                    match cmd.assignments.as_slice() {
+                        [assignment] => Some(assignment.name.clone()),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            });
+
+            let flag = match &assigned {
+                Some(name) => format!("--{} (-{})", name, letter),
+                None => format!("-{}", letter),
+            };
+            // Switches are false unless given, so only valued flags keep a default
+            let default = assigned
+                .as_deref()
+                .and_then(|name| defaults.get(name))
+                .filter(|_| takes_value)
+                .map(|value| format!(" = {}", self.format_value(value)))
+                .unwrap_or_default();
+            flags.push(if takes_value {
+                format!("{}: string{}", flag, default)
+            } else {
+                flag
+            });
+
+            // Arms doing more than an assignment run when their flag is given
+            match (item, assigned) {
+                (_, Some(name)) => {
+                    flag_names.insert(name);
+                }
+                (Some(item), None) => {
+                    let body = item
+                        .body
+                        .iter()
+                        .map(|command| self.convert_command(command))
+                        .collect::<Result<Vec<_>>>()?
+                        .join("; ")
+                        .replace("$OPTARG", &format!("${}", letter));
+                    let condition = if takes_value {
+                        format!("${} != null", letter)
+                    } else {
+                        format!("${}", letter)
+                    };
+                    actions.push(format!("if {} {{ {} }}", condition, body));
+                }
+                (None, None) => {}
+            }
+        }
+
+        if body_contains(&commands[position + 1..], &uses_positional_params) {
+            flags.push("...rest".to_string());
+        }
+
+        // The loop itself becomes the flag actions; the flags replace the
+        // defaults, and Nushell has already consumed the options
+        let loop_command = &commands[position];
+        let body = self.convert_exit_trap_block(commands, &|command| {
+            if std::ptr::eq(command, loop_command) {
+                return Ok(actions.join("\n"));
+            }
+            if let PosixCommand::Simple(cmd) = command {
+                let is_default = cmd.name.is_empty()
+                    && !cmd.assignments.is_empty()
+                    && cmd
+                        .assignments
+                        .iter()
+                        .all(|assignment| flag_names.contains(&assignment.name));
+                let is_shift =
+                    cmd.name == "shift" && cmd.args.iter().any(|arg| arg.contains("OPTIND"));
+                if is_default || is_shift {
+                    return Ok(String::new());
+                }
+            }
+            convert_one(command)
+        })?;
+
+        let mut output = format!("def main [\n  {}\n] {{\n", flags.join("\n  "));
+        for line in body.lines() {
+            output.push_str(&format!("  {}\n", line));
+        }
+        output.push('}');
+
+        Ok(Some(output))
+    }
+
     fn convert_command(&self, command: &PosixCommand) -> Result<String> {
         match command {
             PosixCommand::Simple(cmd) => self.convert_simple_command(cmd),
@@ -52,22 +523,80 @@ fn convert_command(&self, command: &PosixCommand) -> Result<String> {
     }
 
     pub fn convert_simple_command(&self, cmd: &SimpleCommandData) -> Result<String> {
-        let mut output = String::new();
+        // Commands are counted once they convert; commands nested inside
+        // this one, as in `time ( ls; pwd )`, are counted on their own
+        let outer = self.passed_through.replace(false);
+        let converted = self.convert_simple_command_text(cmd);
+        let passed_through = self.passed_through.replace(outer);
+        let output = converted?;
 
-        // Handle variable assignments
-        if !cmd.assignments.is_empty() {
-            for assignment in &cmd.assignments {
-                output.push_str(&format!(
-                    "${} = \"{}\"; ",
-                    assignment.name, assignment.value
-                ));
+        self.record(|stats| {
+            stats.commands_total += 1;
+            if passed_through || output.starts_with('^') {
+                stats.passthrough += 1;
+            } else {
+                stats.converted += 1;
             }
+        });
+        Ok(output)
+    }
+
+    fn convert_simple_command_text(&self, cmd: &SimpleCommandData) -> Result<String> {
+        if let Some(converted) = self.convert_heredoc(cmd)? {
+            return Ok(converted);
+        }
+
+        // `${NAME:=WORD}` arguments assign NAME before the command runs
+        let defaults: Vec<String> = cmd
+            .args
+            .iter()
+            .flat_map(|arg| default_assignments(arg))
+            .map(|(name, value)| self.bind(&name, format!("let {} = {}", name, value)))
+            .collect();
+        if !defaults.is_empty() && cmd.name == ":" && cmd.redirections.is_empty() {
+            return Ok(defaults.join("\n"));
         }
 
-        // Convert the command name and arguments
-        if !cmd.name.is_empty() {
-            let converted_cmd = self.convert_command_name(&cmd.name, &cmd.args)?;
-            output.push_str(&converted_cmd);
+        let mut output = String::new();
+        for assignment in &defaults {
+            output.push_str(assignment);
+            output.push('\n');
+        }
+
+        // Standalone assignments define variables
+        if cmd.name == "export" && !cmd.args.is_empty() {
+            let exports: Vec<String> = cmd
+                .args
+                .iter()
+                .map(|arg| self.convert_export(arg))
+                .collect();
+            output.push_str(&exports.join("; "));
+        } else if let Some(comment) = self.convert_set_pipefail(cmd) {
+            output.push_str(&comment);
+        } else if let Some(declarations) = self.convert_declaration(cmd) {
+            output.push_str(&declarations);
+        } else if cmd.name.is_empty() {
+            let lets: Vec<String> = cmd
+                .assignments
+                .iter()
+                .map(|a| self.convert_assignment(a))
+                .collect();
+            output.push_str(&lets.join("; "));
+        } else if cmd.name == "echo" {
+            let args = self.convert_echo_args(&cmd.args);
+            output.push_str(&self.convert_command_name(&cmd.name, &args)?);
+        } else {
+            let args = cmd
+                .args
+                .iter()
+                .map(|arg| match self.convert_process_substitution(arg)? {
+                    Some(substitution) => Ok(substitution),
+                    None => Ok(self
+                        .convert_command_substitution(arg)
+                        .unwrap_or_else(|| expand_word(arg))),
+                })
+                .collect::<Result<Vec<String>>>()?;
+            output.push_str(&self.convert_command_name(&cmd.name, &args)?);
         }
 
         // Handle redirections
@@ -78,47 +607,140 @@ pub fn convert_simple_command(&self, cmd: &SimpleCommandData) -> Result<String>
             }
         }
 
+        // Assignments prefixing a command only apply to that command
+        if !cmd.name.is_empty() && !cmd.assignments.is_empty() {
+            let env: Vec<String> = cmd
+                .assignments
+                .iter()
+                .map(|a| format!("{}: {}", a.name, self.format_value(&a.value)))
+                .collect();
+            // The command sees the values as environment variables
+            for assignment in &cmd.assignments {
+                output = env_references(&output, &assignment.name);
+            }
+            output = format!("with-env {{ {} }} {{ {} }}", env.join(", "), output);
+        }
+
         Ok(output)
     }
 
     fn convert_command_name(&self, name: &str, args: &[String]) -> Result<String> {
+        // `command NAME ARGS` runs NAME itself, so convert it directly
+        if name == "command" {
+            if let Some((inner, rest)) = command_operands(args).split_first() {
+                if inner != "-v" && inner != "-V" {
+                    return self.convert_command_name(inner, rest);
+                }
+            }
+        }
+
+        // `time CMD` and `time { ...; }` time a command or group with `timeit`
+        if name == "time" {
+            let words: Vec<&String> = args.iter().filter(|arg| *arg != "-p").collect();
+            if let Some((inner, rest)) = words.split_first() {
+                let text = words
+                    .iter()
+                    .map(|w| w.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let group = text
+                    .strip_prefix('(')
+                    .and_then(|t| t.strip_suffix(')'))
+                    .or_else(|| text.strip_prefix('{')?.strip_suffix('}'));
+                let timed = match group {
+                    Some(group) => {
+                        // Each `;`-separated command of the group is its own line
+                        let lines: Vec<&str> = group
+                            .split(';')
+                            .map(str::trim)
+                            .filter(|line| !line.is_empty())
+                            .collect();
+                        let script = parse_posix_script(&lines.join("\n"))?;
+                        self.convert(&script)?.replace('\n', "; ")
+                    }
+                    None => {
+                        let rest: Vec<String> = rest.iter().map(|arg| arg.to_string()).collect();
+                        self.convert_command_name(inner, &rest)?
+                    }
+                };
+                return Ok(format!("timeit {{ {} }}", timed));
+            }
+        }
+
+        // `exec NAME ARGS` can only run NAME, since Nushell can't replace itself
+        if name == "exec" {
+            if let Some((inner, rest)) =
+                exec_command(args).and_then(|command| command.split_first())
+            {
+                let inner = inner.trim_matches('"');
+                // A command from a variable, as in `exec "$@"`, runs as given
+                let command = if matches!(inner, "$@" | "$*" | "$rest") {
+                    format!("run-external ...$rest {}", self.format_args(rest))
+                } else if inner.starts_with('$') {
+                    format!(
+                        "run-external {} {}",
+                        expand_word(inner),
+                        self.format_args(rest)
+                    )
+                } else {
+                    self.convert_command_name(inner, rest)?
+                };
+                return Ok(self.take_notes(format!(
+                    "{}{}{}",
+                    command.trim_end(),
+                    NOTE_MARKER,
+                    EXEC_NOTE
+                )));
+            }
+        }
+
         // First try to use the builtin registry for shell builtins
-        if let Ok(nu_command) = self.builtin_registry.convert_builtin(name, args) {
-            return Ok(nu_command);
+        let builtin_name = if name == "[" { "test" } else { name };
+        if self.builtin_registry.find_converter(builtin_name).is_some() {
+            let converted = self.take_notes(self.builtin_registry.convert_builtin(name, args)?);
+            if name == "exit" && self.exit_as_return && self.in_function.get() {
+                if let Some(rest) = converted.strip_prefix("exit") {
+                    return Ok(format!("return{}", rest));
+                }
+            }
+            return Ok(converted);
         }
 
         // Then try to use the SUS command registry for external utilities
-        if let Ok(nu_command) = self.command_registry.convert_command(name, args) {
-            return Ok(nu_command);
+        if self.command_registry.find_converter(name).is_some() {
+            let converted = self.command_registry.convert_command(name, args)?;
+            return Ok(self.take_notes(converted));
         }
 
         // Fallback to legacy conversions for commands not in SUS or builtin
         match name {
             "awk" => {
                 // Basic awk conversion - this is very limited
-                if args.is_empty() {
-                    Ok("awk".to_string())
-                } else if args.len() == 1 {
-                    let pattern = &args[0];
-                    if pattern.starts_with('{') && pattern.ends_with('}') {
-                        // Simple awk script conversion
-                        if pattern.contains("print") {
-                            Ok("each { |row| print $row }".to_string())
-                        } else {
-                            Ok(format!("awk {}", self.format_args(args)))
-                        }
-                    } else {
-                        Ok(format!("awk {}", self.format_args(args)))
+                if let [pattern] = args {
+                    // Simple awk script conversion
+                    if pattern.starts_with('{')
+                        && pattern.ends_with('}')
+                        && pattern.contains("print")
+                    {
+                        return Ok("each { |row| print $row }".to_string());
                     }
-                } else {
-                    Ok(format!("awk {}", self.format_args(args)))
                 }
+                self.passed_through.set(true);
+                Ok(format!("awk {}", self.format_args(args))
+                    .trim_end()
+                    .to_string())
             }
 
             _ => {
                 // Unknown command, pass through with args
+                self.passed_through.set(true);
+                let name = if self.external_prefix {
+                    format!("^{}", name)
+                } else {
+                    name.to_string()
+                };
                 if args.is_empty() {
-                    Ok(name.to_string())
+                    Ok(name)
                 } else {
                     Ok(format!("{} {}", name, self.format_args(args)))
                 }
@@ -127,10 +749,99 @@ fn convert_command_name(&self, name: &str, args: &[String]) -> Result<String> {
     }
 
     fn convert_pipeline(&self, pipe: &PipelineData) -> Result<String> {
+        // `time cmd | other` times the whole pipeline
+        if let Some(PosixCommand::Simple(first)) = pipe.commands.first() {
+            if let (true, Some((inner, rest))) = (first.name == "time", first.args.split_first()) {
+                let mut untimed = pipe.clone();
+                untimed.commands[0] = PosixCommand::Simple(SimpleCommandData {
+                    name: inner.clone(),
+                    args: rest.to_vec(),
+                    ..first.clone()
+                });
+                return Ok(format!("timeit {{ {} }}", self.convert_pipeline(&untimed)?));
+            }
+        }
+
         let mut parts = Vec::new();
+        // Stages already folded into an earlier one
+        let mut fused = 0;
 
-        for command in &pipe.commands {
-            parts.push(self.convert_command(command)?);
+        for (i, command) in pipe.commands.iter().enumerate() {
+            if fused > 0 {
+                fused -= 1;
+                continue;
+            }
+            // `echo $VAR | cmd` feeds the value straight into the pipeline
+            if let (0, PosixCommand::Simple(cmd)) = (i, command) {
+                if let Some(value) = self.echoed_value(cmd).filter(|_| pipe.commands.len() > 1) {
+                    parts.push(value);
+                    continue;
+                }
+            }
+            // `grep ... | wc -l` or `ls | wc -l` counts the list items directly
+            if let (PosixCommand::Simple(stage), Some(PosixCommand::Simple(wc))) =
+                (command, pipe.commands.get(i + 1))
+            {
+                if produces_list(stage) && wc.name == "wc" && wc.args == ["-l"] {
+                    parts.push(format!("{} | length", self.convert_command(command)?));
+                    fused = 1;
+                    continue;
+                }
+            }
+            // `find ... -print0 | xargs -0 cmd` runs cmd on each globbed path
+            if let (PosixCommand::Simple(find), Some(PosixCommand::Simple(xargs))) =
+                (command, pipe.commands.get(i + 1))
+            {
+                if let Some(fused_stage) = self.convert_find_xargs(find, xargs)? {
+                    parts.push(fused_stage);
+                    fused = 1;
+                    continue;
+                }
+            }
+            // `sort | uniq -c | sort -rn` ranks lines by how often they occur
+            if is_frequency_ranking(&pipe.commands[i..]) {
+                let previous = i.checked_sub(1).and_then(|i| pipe.commands.get(i));
+                let split = match (command, previous) {
+                    // The first sort may read a file itself
+                    (PosixCommand::Simple(sort), _) if !sort.args.is_empty() => format!(
+                        "open --raw {} | lines | ",
+                        self.quote_arg(&expand_word(&sort.args[0]))
+                    ),
+                    (_, Some(PosixCommand::Simple(previous))) if self.produces_text(previous) => {
+                        "lines | ".to_string()
+                    }
+                    _ => String::new(),
+                };
+                parts.push(format!("{}uniq --count | sort-by count --reverse", split));
+                fused = 2;
+                continue;
+            }
+            let mut converted = self.convert_command(command)?;
+            // Under pipefail a failing external stage stops the pipeline
+            if let PosixCommand::Simple(stage) = command {
+                if self.pipefail.get()
+                    && i + 1 < pipe.commands.len()
+                    && self.produces_text(stage)
+                    && stage.name != "cat"
+                {
+                    converted = format!(
+                        "({} | complete | if $in.exit_code != 0 {{ error make {{msg: \"{} failed\"}} }} else {{ $in.stdout }})",
+                        converted, stage.name
+                    );
+                }
+            }
+            // `first`/`last` work on lists, so text from the previous stage is split first
+            if let (PosixCommand::Simple(stage), Some(PosixCommand::Simple(previous))) =
+                (command, i.checked_sub(1).and_then(|i| pipe.commands.get(i)))
+            {
+                if matches!(stage.name.as_str(), "head" | "tail")
+                    && (converted.starts_with("first ") || converted.starts_with("last "))
+                    && self.produces_text(previous)
+                {
+                    converted = format!("lines | {}", converted);
+                }
+            }
+            parts.push(converted);
         }
 
         let result = parts.join(" | ");
@@ -142,7 +853,177 @@ fn convert_pipeline(&self, pipe: &PipelineData) -> Result<String> {
         }
     }
 
+    /// Convert `find DIR -name PAT -print0 | xargs -0 cmd args` into
+    /// `glob DIR/**/PAT | each { |f| cmd args $f }`
+    ///
+    /// Nushell passes paths as structured data, so the null delimiters are dropped.
+    fn convert_find_xargs(
+        &self,
+        find: &SimpleCommandData,
+        xargs: &SimpleCommandData,
+    ) -> Result<Option<String>> {
+        if find.name != "find" || xargs.name != "xargs" {
+            return Ok(None);
+        }
+        let (Some(glob), [null, name, args @ ..]) = (find_glob(find), xargs.args.as_slice()) else {
+            return Ok(None);
+        };
+        if !matches!(null.as_str(), "-0" | "--null") || name.starts_with('-') {
+            return Ok(None);
+        }
+
+        let mut args = args.to_vec();
+        args.push("$f".to_string());
+        let command = PosixCommand::Simple(SimpleCommandData {
+            name: name.clone(),
+            args,
+            assignments: Vec::new(),
+            redirections: Vec::new(),
+            span: xargs.span,
+        });
+        Ok(Some(format!(
+            "glob {} | each {{ |f| {} }}",
+            glob,
+            self.convert_command(&command)?
+        )))
+    }
+
+    /// Check whether a pipeline stage outputs raw text rather than a list:
+    /// `cat` and commands with no conversion, which run externally
+    fn produces_text(&self, command: &SimpleCommandData) -> bool {
+        command.name == "cat"
+            || (self
+                .builtin_registry
+                .find_converter(&command.name)
+                .is_none()
+                && self
+                    .command_registry
+                    .find_converter(&command.name)
+                    .is_none())
+    }
+
+    /// Convert a `<(cmd)` process substitution into a `(cmd)` subexpression
+    ///
+    /// Nushell has no named pipes, so `>(cmd)` output substitutions keep their
+    /// form with the command converted; `tee` turns them into closures.
+    fn convert_process_substitution(&self, arg: &str) -> Result<Option<String>> {
+        if let Some(inner) = arg.strip_prefix("<(").and_then(|a| a.strip_suffix(')')) {
+            let script = parse_posix_script(inner)?;
+            return Ok(Some(format!("({})", self.convert(&script)?)));
+        }
+        if let Some(inner) = arg.strip_prefix(">(").and_then(|a| a.strip_suffix(')')) {
+            let script = parse_posix_script(inner)?;
+            return Ok(Some(format!(">({})", self.convert(&script)?)));
+        }
+        Ok(None)
+    }
+
+    /// Convert a command reading a here-document or here-string into a
+    /// string piped to it
+    ///
+    /// `cat <<EOF > file` writes the document with `save`; other commands
+    /// receive it on their input.
+    fn convert_heredoc(&self, cmd: &SimpleCommandData) -> Result<Option<String>> {
+        let Some(mut output) = cmd.redirections.iter().find_map(|r| match r.operator {
+            RedirectionOp::InputHereDoc { literal: true } => Some(literal_string(&r.target)),
+            RedirectionOp::InputHereDoc { literal: false } => {
+                // Without expansions the document is a plain string
+                let text = if r.target.contains('$') {
+                    escape_literal_parens(&r.target)
+                } else {
+                    r.target.clone()
+                };
+                Some(self.format_value(&format!("\"{}\"", text)))
+            }
+            RedirectionOp::InputHereString => {
+                let value = expand_word(&r.target);
+                // A bare word would run as a command
+                Some(
+                    if is_nu_expression(&value) || value.starts_with(['"', '\'', '$']) {
+                        value
+                    } else {
+                        format!("\"{}\"", value)
+                    },
+                )
+            }
+            _ => None,
+        }) else {
+            return Ok(None);
+        };
+
+        if cmd.name != "cat" || !cmd.args.is_empty() {
+            let args: Vec<String> = cmd.args.iter().map(|arg| expand_word(arg)).collect();
+            output.push_str(&format!(
+                " | {}",
+                self.convert_command_name(&cmd.name, &args)?
+            ));
+        }
+
+        for redir in &cmd.redirections {
+            match redir.operator {
+                RedirectionOp::Output | RedirectionOp::Clobber => {
+                    output.push_str(&format!(
+                        " | save --force {}",
+                        self.quote_arg(&redir.target)
+                    ));
+                }
+                RedirectionOp::Append => {
+                    output.push_str(&format!(
+                        " | save --append {}",
+                        self.quote_arg(&redir.target)
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Some(output))
+    }
+
+    /// Return the value `echo` writes, as a Nushell expression, see [`echo_string`]
+    fn echoed_value(&self, cmd: &SimpleCommandData) -> Option<String> {
+        if cmd.name != "echo" || !cmd.assignments.is_empty() || !cmd.redirections.is_empty() {
+            return None;
+        }
+        // The trailing newline doesn't matter to the next stage
+        let args = match cmd.args.split_first() {
+            Some((flag, rest)) if matches!(flag.as_str(), "-n" | "-e" | "-E") => rest,
+            _ => cmd.args.as_slice(),
+        };
+        let value = echo_string(&self.convert_echo_args(args));
+        // A bare word at the head of a pipeline would run as a command
+        if value.starts_with(['$', '"', '(']) {
+            Some(value)
+        } else {
+            Some(format!("\"{}\"", value))
+        }
+    }
+
+    /// Convert the command substitutions among `echo`'s words, leaving the
+    /// other words as written so it can tell quoted text from expansions
+    fn convert_echo_args(&self, args: &[String]) -> Vec<String> {
+        args.iter()
+            .map(|arg| {
+                self.convert_command_substitution(arg)
+                    .unwrap_or_else(|| arg.clone())
+            })
+            .collect()
+    }
+
     fn convert_compound_command(&self, comp: &CompoundCommandData) -> Result<String> {
+        // `while read VAR; do ...; done < file` reads the file line by line
+        if let CompoundCommandKind::While { condition, body } = &comp.kind {
+            if let (Some(variable), [redir]) = (
+                self.read_loop_variable(condition),
+                comp.redirections.as_slice(),
+            ) {
+                if matches!(redir.operator, RedirectionOp::Input) {
+                    let input = format!("open {}", self.quote_arg(&expand_word(&redir.target)));
+                    return self.convert_lines_each(&variable, Some(&input), body);
+                }
+            }
+        }
+
         let mut output = self.convert_compound_kind(&comp.kind)?;
 
         // Handle redirections
@@ -180,6 +1061,8 @@ fn convert_compound_kind(&self, kind: &CompoundCommandKind) -> Result<String> {
             } => {
                 let items = if words.is_empty() {
                     "$in".to_string()
+                } else if let Some(range) = self.convert_seq_words(words)? {
+                    range
                 } else {
                     format!(
                         "[{}]",
@@ -196,15 +1079,81 @@ fn convert_compound_kind(&self, kind: &CompoundCommandKind) -> Result<String> {
                     body_str.push_str(&format!("  {}\n", self.convert_command(command)?));
                 }
 
+                // `each` closures can't break or continue or assign outer variables,
+                // and collect results that imperative loops throw away, so those
+                // become native loops
+                if breaks_loop(body) || self.only_side_effects(body) || self.assigns_mutable(body) {
+                    return Ok(format!("for {} in {} {{\n{}}}", variable, items, body_str));
+                }
+
                 Ok(format!(
                     "{} | each {{ |{}| \n{}}}",
                     items, variable, body_str
                 ))
             }
+            CompoundCommandKind::ArithmeticFor {
+                init,
+                condition,
+                update,
+                body,
+            } => {
+                let mut body_str = String::new();
+                for command in body {
+                    body_str.push_str(&format!("  {}\n", self.convert_command(command)?));
+                }
+
+                // A counter stepping by one up to a bound is a range
+                if let Some((variable, range)) = arithmetic_for_range(init, condition, update) {
+                    if breaks_loop(body)
+                        || self.only_side_effects(body)
+                        || self.assigns_mutable(body)
+                    {
+                        return Ok(format!("for {} in {} {{\n{}}}", variable, range, body_str));
+                    }
+                    return Ok(format!(
+                        "{} | each {{ |{}| \n{}}}",
+                        range, variable, body_str
+                    ));
+                }
+
+                let Some((variable, start)) = init.split_once('=') else {
+                    anyhow::bail!("unsupported arithmetic for loop initializer: {}", init);
+                };
+                let variable = variable.trim();
+                let step = match update.trim() {
+                    u if u == format!("{}++", variable) || u == format!("++{}", variable) => {
+                        format!("${} += 1", variable)
+                    }
+                    u if u == format!("{}--", variable) || u == format!("--{}", variable) => {
+                        format!("${} -= 1", variable)
+                    }
+                    u => match u.strip_prefix(variable).map(str::trim_start) {
+                        Some(rest) if rest.starts_with("+=") || rest.starts_with("-=") => {
+                            let (operator, amount) = rest.split_at(2);
+                            format!(
+                                "${} {} {}",
+                                variable,
+                                operator,
+                                arithmetic_expression(amount)
+                            )
+                        }
+                        _ => anyhow::bail!("unsupported arithmetic for loop update: {}", update),
+                    },
+                };
+
+                Ok(format!(
+                    "mut {} = {}\nwhile {} {{\n{}  {}\n}}",
+                    variable,
+                    start.trim(),
+                    arithmetic_expression(condition),
+                    body_str,
+                    step
+                ))
+            }
             CompoundCommandKind::While { condition, body } => {
-                let mut cond_parts = Vec::new();
-                for command in condition {
-                    cond_parts.push(self.convert_command(command)?);
+                // `while read VAR` consumes piped input one line at a time
+                if let Some(variable) = self.read_loop_variable(condition) {
+                    return self.convert_lines_each(&variable, None, body);
                 }
 
                 let mut body_str = String::new();
@@ -212,18 +1161,20 @@ fn convert_compound_kind(&self, kind: &CompoundCommandKind) -> Result<String> {
                     body_str.push_str(&format!("  {}\n", self.convert_command(command)?));
                 }
 
+                // `while true` (or `while :`) loops forever
+                if let [PosixCommand::Simple(cmd)] = condition.as_slice() {
+                    if matches!(cmd.name.as_str(), "true" | ":") && cmd.args.is_empty() {
+                        return Ok(format!("loop {{\n{}}}", body_str));
+                    }
+                }
+
                 Ok(format!(
                     "while {} {{\n{}}}",
-                    cond_parts.join("; "),
+                    self.convert_condition(condition)?,
                     body_str
                 ))
             }
             CompoundCommandKind::Until { condition, body } => {
-                let mut cond_parts = Vec::new();
-                for command in condition {
-                    cond_parts.push(self.convert_command(command)?);
-                }
-
                 let mut body_str = String::new();
                 for command in body {
                     body_str.push_str(&format!("  {}\n", self.convert_command(command)?));
@@ -231,7 +1182,7 @@ fn convert_compound_kind(&self, kind: &CompoundCommandKind) -> Result<String> {
 
                 Ok(format!(
                     "while not ({}) {{\n{}}}",
-                    cond_parts.join("; "),
+                    self.convert_condition(condition)?,
                     body_str
                 ))
             }
@@ -241,24 +1192,24 @@ fn convert_compound_kind(&self, kind: &CompoundCommandKind) -> Result<String> {
                 elif_parts,
                 else_body,
             } => {
-                let mut cond_parts = Vec::new();
-                for command in condition {
-                    cond_parts.push(self.convert_command(command)?);
+                // Collapse branches that only assign one variable into an expression
+                if let Some(expression) =
+                    self.convert_if_expression(condition, then_body, elif_parts, else_body)?
+                {
+                    return Ok(expression);
                 }
 
-                let mut output = format!("if {} {{\n", cond_parts.join("; "));
+                let mut output = format!("if {} {{\n", self.convert_condition(condition)?);
 
                 for command in then_body {
                     output.push_str(&format!("  {}\n", self.convert_command(command)?));
                 }
 
                 for elif in elif_parts {
-                    let mut elif_cond_parts = Vec::new();
-                    for command in &elif.condition {
-                        elif_cond_parts.push(self.convert_command(command)?);
-                    }
-
-                    output.push_str(&format!("}} else if {} {{\n", elif_cond_parts.join("; ")));
+                    output.push_str(&format!(
+                        "}} else if {} {{\n",
+                        self.convert_condition(&elif.condition)?
+                    ));
 
                     for command in &elif.body {
                         output.push_str(&format!("  {}\n", self.convert_command(command)?));
@@ -276,21 +1227,37 @@ fn convert_compound_kind(&self, kind: &CompoundCommandKind) -> Result<String> {
                 Ok(output)
             }
             CompoundCommandKind::Case { word, items } => {
-                let mut output = format!("match {} {{\n", self.quote_arg(word));
+                let mut output = format!("match {} {{\n", self.quote_arg(&expand_word(word)));
 
-                for item in items {
-                    let patterns = item
-                        .patterns
-                        .iter()
-                        .map(|p| self.quote_arg(p))
-                        .collect::<Vec<_>>()
-                        .join(" | ");
-                    output.push_str(&format!("  {} => {{\n", patterns));
+                for (index, item) in items.iter().enumerate() {
+                    output.push_str(&format!(
+                        "  {} => {{\n",
+                        self.convert_case_patterns(&item.patterns)
+                    ));
 
                     for command in &item.body {
                         output.push_str(&format!("    {}\n", self.convert_command(command)?));
                     }
 
+                    // `match` has no fallthrough, so repeat the bodies `;&` runs into
+                    let mut previous = item;
+                    for next in &items[index + 1..] {
+                        if previous.terminator != CaseTerminator::Fallthrough {
+                            break;
+                        }
+                        output.push_str("    # fallthrough (;&) into the next case body\n");
+                        for command in &next.body {
+                            output.push_str(&format!("    {}\n", self.convert_command(command)?));
+                        }
+                        previous = next;
+                    }
+
+                    if item.terminator == CaseTerminator::Continue {
+                        output.push_str(
+                            "    # ;;& would test the remaining patterns; match stops at the first arm\n",
+                        );
+                    }
+
                     output.push_str("  }\n");
                 }
 
@@ -298,10 +1265,25 @@ fn convert_compound_kind(&self, kind: &CompoundCommandKind) -> Result<String> {
                 Ok(output)
             }
             CompoundCommandKind::Function { name, body } => {
-                let mut output = format!("def {} [] {{\n", name);
+                // Shell functions receive their arguments positionally
+                let params = if body_contains(body, &uses_positional_params) {
+                    "...rest"
+                } else {
+                    ""
+                };
+                let mut output = format!("def {} [{}] {{\n", name, params);
 
-                for command in body {
-                    output.push_str(&format!("  {}\n", self.convert_command(command)?));
+                let outer = self.in_function.replace(true);
+                let converted = self.with_scope(body, || {
+                    let mut converted = self.hoisted_declarations();
+                    for command in body {
+                        converted.push(self.convert_command(command)?);
+                    }
+                    Ok(converted)
+                });
+                self.in_function.set(outer);
+                for command in converted? {
+                    output.push_str(&format!("  {}\n", command));
                 }
 
                 output.push('}');
@@ -315,20 +1297,117 @@ fn convert_compound_kind(&self, kind: &CompoundCommandKind) -> Result<String> {
         }
     }
 
-    fn convert_and_or(&self, and_or: &AndOrData) -> Result<String> {
-        let left = self.convert_command(&and_or.left)?;
-        let right = self.convert_command(&and_or.right)?;
+    /// Convert an if/else whose branches each assign the same variable to
+    /// `let var = if ... { } else { }`
+    ///
+    /// Returns `None` when the statement is not collapsible, e.g. when a branch
+    /// runs a command, assigns a different variable, or there is no else branch.
+    fn convert_if_expression(
+        &self,
+        condition: &[PosixCommand],
+        then_body: &[PosixCommand],
+        elif_parts: &[ElifPart],
+        else_body: &Option<Vec<PosixCommand>>,
+    ) -> Result<Option<String>> {
+        let Some(else_body) = else_body else {
+            return Ok(None);
+        };
 
-        match and_or.operator {
-            AndOrOperator::And => Ok(format!("({}) and ({})", left, right)),
-            AndOrOperator::Or => Ok(format!("({}) or ({})", left, right)),
-        }
-    }
+        let mut branches = vec![(Some(condition), then_body)];
+        branches.extend(
+            elif_parts
+                .iter()
+                .map(|elif| (Some(elif.condition.as_slice()), elif.body.as_slice())),
+        );
+        branches.push((None, else_body.as_slice()));
 
-    fn convert_list(&self, list: &ListData) -> Result<String> {
-        let mut parts = Vec::new();
+        let mut variable = None;
+        let mut output = String::new();
 
-        for command in &list.commands {
+        for (condition, body) in branches {
+            let [PosixCommand::Simple(cmd)] = body else {
+                return Ok(None);
+            };
+            let [assignment] = cmd.assignments.as_slice() else {
+                return Ok(None);
+            };
+            if !cmd.name.is_empty() || !cmd.redirections.is_empty() {
+                return Ok(None);
+            }
+            if variable.get_or_insert(assignment.name.as_str()) != &assignment.name {
+                return Ok(None);
+            }
+
+            let value = self.format_value(&assignment.value);
+            match condition {
+                Some(condition) => {
+                    if !output.is_empty() {
+                        output.push_str(" else ");
+                    }
+                    output.push_str(&format!(
+                        "if {} {{ {} }}",
+                        self.convert_condition(condition)?,
+                        value
+                    ));
+                }
+                None => output.push_str(&format!(" else {{ {} }}", value)),
+            }
+        }
+
+        Ok(variable.map(|name| self.bind(name, format!("let {} = {}", name, output))))
+    }
+
+    /// Convert the commands of an `if`, `while` or `until` condition
+    ///
+    /// The `:` null command always succeeds, so as a condition it is `true`.
+    fn convert_condition(&self, condition: &[PosixCommand]) -> Result<String> {
+        let mut parts = Vec::new();
+        for command in condition {
+            match command {
+                PosixCommand::Simple(cmd) if cmd.name == ":" && cmd.args.is_empty() => {
+                    parts.push("true".to_string())
+                }
+                _ => parts.push(self.convert_command(command)?),
+            }
+        }
+        Ok(parts.join("; "))
+    }
+
+    fn convert_and_or(&self, and_or: &AndOrData) -> Result<String> {
+        let left = self.convert_condition(std::slice::from_ref(&*and_or.left))?;
+        let right = self.convert_command(&and_or.right)?;
+
+        // `grep -q` converts to a boolean pipeline, so branch on it directly
+        if is_quiet_grep(&and_or.left) {
+            let condition = match and_or.operator {
+                AndOrOperator::And => format!("({})", left),
+                AndOrOperator::Or => format!("not ({})", left),
+            };
+            return Ok(format!("if {} {{ {} }}", condition, right));
+        }
+
+        // Commands other than tests run the right side based on the exit status
+        if self.preserve_exit_semantics && !is_boolean_command(&and_or.left) {
+            let comparison = match and_or.operator {
+                AndOrOperator::And => "==",
+                AndOrOperator::Or => "!=",
+            };
+            return Ok(format!(
+                "if ({} | complete).exit_code {} 0 {{ {} }}",
+                left, comparison, right
+            ));
+        }
+
+        match and_or.operator {
+            AndOrOperator::And => Ok(format!("({}) and ({})", left, right)),
+            AndOrOperator::Or => Ok(format!("({}) or ({})", left, right)),
+        }
+    }
+
+    fn convert_list(&self, list: &ListData) -> Result<String> {
+        let mut parts = Vec::new();
+
+        for command in &list.commands {
             parts.push(self.convert_command(command)?);
         }
 
@@ -358,7 +1437,7 @@ fn convert_redirections(&self, redirections: &[Redirection]) -> Result<String> {
                 RedirectionOp::Clobber => {
                     parts.push(format!("out> {}", self.quote_arg(&redir.target)));
                 }
-                RedirectionOp::InputHereDoc => {
+                RedirectionOp::InputHereDoc { .. } => {
                     // Here documents need to be converted to string input
                     parts.push(format!(
                         "echo {} | {}",
@@ -397,6 +1476,410 @@ fn convert_redirections(&self, redirections: &[Redirection]) -> Result<String> {
         Ok(parts.join(" "))
     }
 
+    /// Convert a `for` word list that is a single `$(seq ...)` substitution
+    /// into a Nushell range
+    fn convert_seq_words(&self, words: &[String]) -> Result<Option<String>> {
+        let [word] = words else {
+            return Ok(None);
+        };
+        let inner = word
+            .strip_prefix("$(")
+            .and_then(|w| w.strip_suffix(')'))
+            .or_else(|| word.strip_prefix('`').and_then(|w| w.strip_suffix('`')));
+        let Some(args) = inner.and_then(|cmd| cmd.trim().strip_prefix("seq ")) else {
+            return Ok(None);
+        };
+
+        let args: Vec<String> = args.split_whitespace().map(String::from).collect();
+        let range = self.command_registry.convert_command("seq", &args)?;
+
+        // Fall back to iterating the substitution when seq could not be mapped
+        if range.starts_with("seq") {
+            return Ok(None);
+        }
+        Ok(Some(range))
+    }
+
+    /// Convert `case` labels into a `match` arm pattern
+    ///
+    /// Literal labels become string patterns; since `match` does not glob,
+    /// labels with wildcards become a regex guard over the matched value.
+    fn convert_case_patterns(&self, patterns: &[String]) -> String {
+        let unquote = |p: &str| -> Option<String> {
+            ['"', '\'']
+                .iter()
+                .find_map(|q| p.strip_prefix(*q).and_then(|p| p.strip_suffix(*q)))
+                .map(String::from)
+        };
+        let is_glob = |p: &str| unquote(p).is_none() && p.contains(['*', '?', '[']);
+
+        if patterns.iter().any(|p| p == "*") {
+            return "_".to_string();
+        }
+
+        if !patterns.iter().any(|p| is_glob(p)) {
+            return patterns
+                .iter()
+                .map(|p| {
+                    let literal = unquote(p).unwrap_or_else(|| p.replace('\\', ""));
+                    format!("\"{}\"", literal.replace('"', "\\\""))
+                })
+                .collect::<Vec<_>>()
+                .join(" | ");
+        }
+
+        let alternatives: Vec<String> = patterns
+            .iter()
+            .map(|p| match unquote(p) {
+                // Quoted labels match literally
+                Some(literal) => {
+                    let escaped: String = literal.chars().flat_map(|c| ['\\', c]).collect();
+                    glob_to_regex(&escaped, false)
+                }
+                None => glob_to_regex(p, false),
+            })
+            .collect();
+        let regex = if alternatives.len() == 1 {
+            alternatives[0].clone()
+        } else {
+            format!("(?:{})", alternatives.join("|"))
+        };
+        format!("$value if $value =~ '^{}$'", regex)
+    }
+
+    /// Return the variable name when `condition` is a bare `read VAR`
+    fn read_loop_variable(&self, condition: &[PosixCommand]) -> Option<String> {
+        match condition {
+            [PosixCommand::Simple(cmd)]
+                if cmd.name == "read"
+                    && cmd.args.len() == 1
+                    && cmd.assignments.is_empty()
+                    && cmd.redirections.is_empty() =>
+            {
+                let variable = &cmd.args[0];
+                let is_name = variable
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                    && variable
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_');
+                is_name.then(|| variable.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Convert a `while read` loop body into `lines | each` over `input`,
+    /// or over piped input when there is none
+    fn convert_lines_each(
+        &self,
+        variable: &str,
+        input: Option<&str>,
+        body: &[PosixCommand],
+    ) -> Result<String> {
+        let mut body_str = String::new();
+        for command in body {
+            body_str.push_str(&format!("  {}\n", self.convert_command(command)?));
+        }
+
+        // `each` closures can't break or continue or assign outer variables,
+        // so those need a native loop
+        if breaks_loop(body) || self.assigns_mutable(body) {
+            return Ok(format!(
+                "for {} in ({} | lines) {{\n{}}}",
+                variable,
+                input.unwrap_or("$in"),
+                body_str
+            ));
+        }
+
+        Ok(match input {
+            Some(input) => format!("{} | lines | each {{ |{}|\n{}}}", input, variable, body_str),
+            None => format!("lines | each {{ |{}|\n{}}}", variable, body_str),
+        })
+    }
+
+    /// Check whether every command in `body` runs for its effect rather than its output
+    fn only_side_effects(&self, body: &[PosixCommand]) -> bool {
+        !any_command(body, &mut |command| match command {
+            // Nushell's `echo` returns its arguments instead of printing them
+            PosixCommand::Simple(cmd) if cmd.name == "echo" => match self.echo_style {
+                EchoStyle::Print => Visit::Skip,
+                EchoStyle::Echo => Visit::Found,
+            },
+            PosixCommand::Simple(cmd) => match cmd.name.as_str() {
+                // Standalone assignments have no name
+                "" | "printf" | "cd" | "mkdir" | "rm" | "rmdir" | "cp" | "mv" | "touch"
+                | "chmod" | "chown" | "ln" | "export" | "unset" | "read" | "shift" | "sleep"
+                | "kill" | "exit" | "return" | "break" | "continue" => Visit::Skip,
+                _ => Visit::Found,
+            },
+            // Only the last stage's output leaves a pipeline
+            PosixCommand::Pipeline(pipe) => {
+                if pipe
+                    .commands
+                    .last()
+                    .is_some_and(|last| self.only_side_effects(std::slice::from_ref(last)))
+                {
+                    Visit::Skip
+                } else {
+                    Visit::Found
+                }
+            }
+            PosixCommand::Compound(comp) => match comp.kind {
+                CompoundCommandKind::Function { .. } => Visit::Skip,
+                CompoundCommandKind::Arithmetic { .. } => Visit::Found,
+                _ => Visit::EnterBodies,
+            },
+            _ => Visit::EnterBodies,
+        })
+    }
+
+    /// Convert one `export NAME[=VALUE]` argument to an environment assignment
+    fn convert_export(&self, arg: &str) -> String {
+        match arg.split_once('=') {
+            Some(("PATH", value)) => self
+                .convert_path_update(value)
+                .unwrap_or_else(|| format!("$env.PATH = {}", self.format_value(value))),
+            Some((name, value)) => format!("$env.{} = {}", name, self.format_value(value)),
+            None => format!("$env.{} = ${}", arg, arg),
+        }
+    }
+
+    /// Record `set -o pipefail` (or `+o pipefail`) for the pipelines that follow
+    ///
+    /// Nushell has no shell options, so the command becomes a comment.
+    fn convert_set_pipefail(&self, cmd: &SimpleCommandData) -> Option<String> {
+        if cmd.name != "set" {
+            return None;
+        }
+        let position = cmd.args.iter().position(|arg| arg == "pipefail")?;
+        let option = cmd.args.get(position.checked_sub(1)?)?;
+        if !option.ends_with('o') {
+            return None;
+        }
+        let enabled = option.starts_with('-');
+        self.pipefail.set(enabled);
+        Some(format!(
+            "# set {}: {}",
+            cmd.args.join(" "),
+            if enabled {
+                "external pipeline stages are checked with complete"
+            } else {
+                "pipeline stage failures are no longer checked"
+            }
+        ))
+    }
+
+    /// Convert `declare`, `typeset`, `local` and `readonly` into bindings
+    ///
+    /// Flags may be combined as in `declare -ir n=5`: `-x` exports, `-i` converts
+    /// to an int and `-r` makes literal values `const`; Nushell's `let` is
+    /// already read-only for the rest. Returns `None` for other flags.
+    fn convert_declaration(&self, cmd: &SimpleCommandData) -> Option<String> {
+        if !matches!(
+            cmd.name.as_str(),
+            "declare" | "typeset" | "local" | "readonly"
+        ) {
+            return None;
+        }
+
+        // Array values such as `arr=(a b)` arrive split into words
+        let mut args: Vec<String> = Vec::new();
+        for arg in &cmd.args {
+            match args.last_mut() {
+                Some(last) if last.contains("=(") && !last.ends_with(')') => {
+                    last.push(' ');
+                    last.push_str(arg);
+                }
+                _ => args.push(arg.clone()),
+            }
+        }
+        let (flags, names): (Vec<&String>, Vec<&String>) =
+            args.iter().partition(|arg| arg.starts_with('-'));
+        let flags: String = flags.iter().map(|flag| &flag[1..]).collect();
+        if names.is_empty() || !flags.chars().all(|flag| "rixaA".contains(flag)) {
+            return None;
+        }
+        let readonly = cmd.name == "readonly" || flags.contains('r');
+
+        let declarations: Vec<String> = names
+            .iter()
+            .map(|arg| {
+                if flags.contains('x') {
+                    return self.convert_export(arg);
+                }
+                let Some((name, value)) = arg.split_once('=') else {
+                    // `readonly NAME` only protects an existing variable
+                    return if readonly {
+                        format!("let {} = ${}", arg, arg)
+                    } else {
+                        self.bind(arg, format!("let {} = null", arg))
+                    };
+                };
+                let formatted = self.format_value(value);
+                if flags.contains('i') && formatted.parse::<i64>().is_err() {
+                    return self.bind(name, format!("let {} = ({} | into int)", name, formatted));
+                }
+                let literal = formatted.parse::<f64>().is_ok()
+                    || (formatted.starts_with('"') && !value.starts_with("$("))
+                    || formatted.starts_with('\'');
+                if readonly && literal {
+                    return format!("const {} = {}", name, formatted);
+                }
+                self.convert_assignment(&Assignment {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                    append: false,
+                })
+            })
+            .collect();
+        Some(declarations.join("; "))
+    }
+
+    /// Convert `PATH=...` values that extend `$PATH` into list appends/prepends
+    fn convert_path_update(&self, value: &str) -> Option<String> {
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        let segments: Vec<&str> = value.split(':').collect();
+        let position = segments
+            .iter()
+            .position(|s| *s == "$PATH" || *s == "${PATH}")?;
+
+        let format_dirs = |dirs: &[&str]| {
+            let dirs: Vec<String> = dirs.iter().map(|d| self.format_value(d)).collect();
+            if dirs.len() == 1 {
+                dirs[0].clone()
+            } else {
+                format!("[{}]", dirs.join(" "))
+            }
+        };
+
+        let mut update = String::from("$env.PATH");
+        let (before, after) = (&segments[..position], &segments[position + 1..]);
+        if !before.is_empty() {
+            update.push_str(&format!(" | prepend {}", format_dirs(before)));
+        }
+        if !after.is_empty() {
+            update.push_str(&format!(" | append {}", format_dirs(after)));
+        }
+        Some(format!("$env.PATH = ({})", update))
+    }
+
+    /// Convert a standalone `NAME=VALUE`, `NAME+=VALUE` or `NAME=(a b c)` assignment
+    fn convert_assignment(&self, assignment: &Assignment) -> String {
+        self.bind(&assignment.name, self.assignment_expression(assignment))
+    }
+
+    /// Convert an assignment as if it were the variable's only one
+    fn assignment_expression(&self, assignment: &Assignment) -> String {
+        let name = &assignment.name;
+        if name == "PATH" && !assignment.append {
+            if let Some(update) = self.convert_path_update(&assignment.value) {
+                return update;
+            }
+        }
+        if !assignment.append {
+            if let Some(capture) = self.convert_command_substitution(&assignment.value) {
+                return format!("let {} = {}", name, capture);
+            }
+        }
+
+        let items = assignment
+            .value
+            .strip_prefix('(')
+            .and_then(|v| v.strip_suffix(')'))
+            .map(|inner| {
+                split_words(inner)
+                    .iter()
+                    .map(|item| self.format_list_item(item))
+                    .collect::<Vec<_>>()
+            });
+
+        match (items, assignment.append) {
+            (Some(items), false) => format!("let {} = [{}]", name, items.join(" ")),
+            (Some(items), true) if items.len() == 1 => {
+                format!("${} = (${} | append {})", name, name, items[0])
+            }
+            (Some(items), true) => {
+                format!("${} = (${} | append [{}])", name, name, items.join(" "))
+            }
+            (None, true) => format!("${} += {}", name, self.format_value(&assignment.value)),
+            (None, false) => format!("let {} = {}", name, self.format_value(&assignment.value)),
+        }
+    }
+
+    /// Convert a `$(cmd)` or `` `cmd` `` value into a Nushell subexpression
+    fn convert_command_substitution(&self, value: &str) -> Option<String> {
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        let inner = value
+            .strip_prefix("$(")
+            .and_then(|v| v.strip_suffix(')'))
+            .filter(|inner| !inner.starts_with('('))
+            .map(str::to_string)
+            .or_else(|| {
+                // Backslashes escape nested backticks inside the legacy form
+                let inner = value.strip_prefix('`')?.strip_suffix('`')?;
+                (!inner.ends_with('\\')).then(|| inner.replace("\\`", "`"))
+            })?;
+
+        let script = parse_posix_script(&inner).ok()?;
+        // `$(echo WORD)` is just the word
+        if let [PosixCommand::Simple(cmd)] = script.commands.as_slice() {
+            if let (true, [arg]) = (cmd.name == "echo", cmd.args.as_slice()) {
+                return Some(
+                    self.convert_command_substitution(arg)
+                        .unwrap_or_else(|| self.format_value(arg)),
+                );
+            }
+        }
+        let command = self.convert(&script).ok()?;
+        if self.string_capture {
+            // Shell substitutions are text with trailing newlines removed
+            Some(format!("({} | into string | str trim --right)", command))
+        } else {
+            Some(format!("({})", command))
+        }
+    }
+
+    /// Format a list element, leaving plain words bare
+    fn format_list_item(&self, item: &str) -> String {
+        let plain = item
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./".contains(c));
+        if plain {
+            item.to_string()
+        } else {
+            self.format_value(item)
+        }
+    }
+
+    /// Format an assigned value, leaving numbers and Nushell expressions bare
+    fn format_value(&self, value: &str) -> String {
+        let expanded = expand_word(value);
+        let single_quoted =
+            expanded.len() >= 2 && expanded.starts_with('\'') && expanded.ends_with('\'');
+        if is_nu_expression(&expanded) || expanded.parse::<f64>().is_ok() || single_quoted {
+            return expanded;
+        }
+
+        let text = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        if text.contains('$') {
+            format!("$\"{}\"", interpolate_word(text))
+        } else {
+            format!("\"{}\"", text.replace('"', "\\\""))
+        }
+    }
+
     fn format_args(&self, args: &[String]) -> String {
         args.iter()
             .map(|arg| self.quote_arg(arg))
@@ -406,7 +1889,10 @@ fn format_args(&self, args: &[String]) -> String {
 
     fn quote_arg(&self, arg: &str) -> String {
         // Simple quoting logic
-        if arg.contains(' ') || arg.contains('"') || arg.contains('\'') || arg.contains('$') {
+        if is_nu_expression(arg) {
+            arg.to_string()
+        } else if arg.contains(' ') || arg.contains('"') || arg.contains('\'') || arg.contains('$')
+        {
             format!("\"{}\"", arg.replace('"', "\\\""))
         } else {
             arg.to_string()
@@ -414,6 +1900,414 @@ fn quote_arg(&self, arg: &str) -> String {
     }
 }
 
+/// Rewrite references to the variable `name` in converted `text` as `$env.name`
+fn env_references(text: &str, name: &str) -> String {
+    let variable = format!("${}", name);
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(pos) = rest.find(&variable) {
+        let end = pos + variable.len();
+        let whole_name = !rest[end..].starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_');
+        result.push_str(&rest[..pos]);
+        if whole_name {
+            result.push_str(&format!("$env.{}", name));
+        } else {
+            result.push_str(&variable);
+        }
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Quote here-document text that is not expanded, using a raw string when it
+/// contains single quotes
+fn literal_string(text: &str) -> String {
+    if text.contains('\'') {
+        let hashes = "#".repeat(text.matches("'#").count() + 1);
+        format!("r{}'{}'{}", hashes, text, hashes)
+    } else {
+        format!("'{}'", text)
+    }
+}
+
+/// The glob matching the paths of a `find [DIR] [-name PAT] [-type f] [-print0]`
+///
+/// Returns `None` for other tests, which the glob cannot express.
+fn find_glob(find: &SimpleCommandData) -> Option<String> {
+    let mut args = find.args.iter().peekable();
+    let dir = args.next_if(|arg| !arg.starts_with('-'));
+    let mut pattern = "*".to_string();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-name" => {
+                pattern = args
+                    .next()?
+                    .trim_matches(|c| c == '\'' || c == '"')
+                    .to_string()
+            }
+            "-type" if args.next()? == "f" => {}
+            "-print0" | "-print" => {}
+            _ => return None,
+        }
+    }
+    Some(match dir.map(String::as_str) {
+        None | Some(".") => format!("**/{}", pattern),
+        Some(dir) => format!("{}/**/{}", dir.trim_end_matches('/'), pattern),
+    })
+}
+
+/// Check whether `stages` start with the `sort | uniq -c | sort -rn` idiom,
+/// the first sort reading standard input or a single file
+fn is_frequency_ranking(stages: &[PosixCommand]) -> bool {
+    let [PosixCommand::Simple(sort), PosixCommand::Simple(uniq), PosixCommand::Simple(rank), ..] =
+        stages
+    else {
+        return false;
+    };
+    let rank_args = rank.args.join(" ");
+    sort.name == "sort"
+        && match sort.args.as_slice() {
+            [] => true,
+            [file] => !file.starts_with('-'),
+            _ => false,
+        }
+        && uniq.name == "uniq"
+        && uniq.args == ["-c"]
+        && rank.name == "sort"
+        && matches!(rank_args.as_str(), "-rn" | "-nr" | "-r -n" | "-n -r")
+}
+
+/// The handler of a `trap 'cmd' EXIT` command (`0` also names EXIT)
+fn exit_trap_handler(command: &PosixCommand) -> Option<String> {
+    let PosixCommand::Simple(cmd) = command else {
+        return None;
+    };
+    if cmd.name != "trap" {
+        return None;
+    }
+    // The handler may arrive split into words; the signals follow it
+    let signals = cmd
+        .args
+        .iter()
+        .rev()
+        .take_while(|arg| {
+            arg.chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+                && !arg.is_empty()
+        })
+        .count();
+    let (handler, signals) = cmd.args.split_at(cmd.args.len() - signals);
+    if handler.is_empty() || !signals.iter().any(|s| s == "EXIT" || s == "0") {
+        return None;
+    }
+    let handler = handler.join(" ");
+    let handler = ['\'', '"']
+        .into_iter()
+        .find_map(|quote| handler.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(&handler);
+    (handler != "-" && !handler.is_empty()).then(|| handler.to_string())
+}
+
+/// Check whether a command converts to a list with one item per output line
+///
+/// Text-producing stages such as `cat` still need `lines` before counting.
+fn produces_list(command: &SimpleCommandData) -> bool {
+    matches!(
+        command.name.as_str(),
+        "ls" | "grep" | "find" | "sort" | "uniq" | "head" | "tail" | "cut" | "seq"
+    )
+}
+
+/// Converts one top-level command within its block's scope
+type ConvertOne<'a> = dyn Fn(&PosixCommand) -> Result<String> + 'a;
+
+/// Variables of one block that need `mut` because they are assigned more than once
+struct Scope {
+    reassigned: HashSet<String>,
+    declared: HashSet<String>,
+    /// Reassigned variables first assigned inside a nested block, in name order
+    hoisted: Vec<String>,
+}
+
+impl Scope {
+    /// Count the assignments and declarations in `commands`, including those
+    /// in nested loop, if and case bodies; appends always reassign
+    ///
+    /// Function bodies and subshells have scopes of their own.
+    fn for_commands(commands: &[PosixCommand]) -> Self {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let mut nested = HashSet::new();
+        for command in commands {
+            let seen: HashSet<String> = counts.keys().cloned().collect();
+            count_assignments(std::slice::from_ref(command), &mut counts);
+            if !matches!(command, PosixCommand::Simple(_)) {
+                nested.extend(counts.keys().filter(|name| !seen.contains(*name)).cloned());
+            }
+        }
+
+        let reassigned: HashSet<String> = counts
+            .into_iter()
+            .filter(|&(_, count)| count > 1)
+            .map(|(name, _)| name)
+            .collect();
+        let mut hoisted: Vec<String> = nested.intersection(&reassigned).cloned().collect();
+        hoisted.sort();
+
+        Self {
+            declared: hoisted.iter().cloned().collect(),
+            reassigned,
+            hoisted,
+        }
+    }
+}
+
+/// Add the variables `commands` assign to `counts`, see [`Scope::for_commands`]
+fn count_assignments(commands: &[PosixCommand], counts: &mut HashMap<String, usize>) {
+    // Nothing is found, so every command is visited
+    any_command(commands, &mut |command| match command {
+        PosixCommand::Simple(cmd) if cmd.name.is_empty() => {
+            for assignment in &cmd.assignments {
+                let count = counts.entry(assignment.name.clone()).or_default();
+                *count += if assignment.append { 2 } else { 1 };
+            }
+            Visit::Skip
+        }
+        PosixCommand::Simple(cmd)
+            if matches!(cmd.name.as_str(), "local" | "declare" | "typeset") =>
+        {
+            for arg in cmd.args.iter().filter(|arg| !arg.starts_with('-')) {
+                let name = arg.split_once('=').map_or(arg.as_str(), |(name, _)| name);
+                *counts.entry(name.to_string()).or_default() += 1;
+            }
+            Visit::Skip
+        }
+        PosixCommand::Simple(cmd) => {
+            for (name, _) in cmd.args.iter().flat_map(|arg| default_assignments(arg)) {
+                *counts.entry(name).or_default() += 1;
+            }
+            Visit::Skip
+        }
+        PosixCommand::Compound(comp)
+            if matches!(
+                comp.kind,
+                CompoundCommandKind::Subshell(_) | CompoundCommandKind::Function { .. }
+            ) =>
+        {
+            Visit::Skip
+        }
+        _ => Visit::Enter,
+    });
+}
+
+/// Convert `i=START; i<END; i++` loop clauses into the variable and an inclusive range
+///
+/// Returns `None` when the loop never runs, since Nushell counts a range
+/// such as `5..2` downwards.
+fn arithmetic_for_range(init: &str, condition: &str, update: &str) -> Option<(String, String)> {
+    let (variable, start) = init.split_once('=')?;
+    let variable = variable.trim();
+    let start: i64 = start.trim().parse().ok()?;
+
+    let update = update.replace(' ', "");
+    let increments = [
+        format!("{}++", variable),
+        format!("++{}", variable),
+        format!("{}+=1", variable),
+        format!("{}={}+1", variable, variable),
+    ];
+    if !increments.contains(&update) {
+        return None;
+    }
+
+    let bound = condition.replace(' ', "");
+    let bound = bound.strip_prefix(variable)?;
+    let end = if let Some(end) = bound.strip_prefix("<=") {
+        end.parse::<i64>().ok()?
+    } else {
+        bound.strip_prefix('<')?.parse::<i64>().ok()? - 1
+    };
+
+    if end < start {
+        return None;
+    }
+    Some((variable.to_string(), format!("{}..{}", start, end)))
+}
+
+/// Match `while getopts OPTSTRING VAR; do case $VAR in ... esac; done`,
+/// returning the unquoted optstring and the case arms
+fn getopts_loop(command: &PosixCommand) -> Option<(String, &[CaseItemData])> {
+    let PosixCommand::Compound(CompoundCommandData {
+        kind: CompoundCommandKind::While { condition, body },
+        ..
+    }) = command
+    else {
+        return None;
+    };
+    let [PosixCommand::Simple(getopts)] = condition.as_slice() else {
+        return None;
+    };
+    let [optstring, variable] = getopts.args.as_slice() else {
+        return None;
+    };
+    if getopts.name != "getopts" {
+        return None;
+    }
+    let [PosixCommand::Compound(CompoundCommandData {
+        kind: CompoundCommandKind::Case { word, items },
+        ..
+    })] = body.as_slice()
+    else {
+        return None;
+    };
+
+    let unquote = |word: &str| word.trim_matches(|c| c == '"' || c == '\'').to_string();
+    let variable = variable.trim_end_matches(';');
+    if unquote(word) != format!("${}", variable) {
+        return None;
+    }
+    Some((unquote(optstring), items))
+}
+
+/// How [`any_command`] treats each command it reaches
+enum Visit {
+    /// The command is the one being looked for
+    Found,
+    /// Don't look inside the command
+    Skip,
+    /// Look inside the command, conditions included
+    Enter,
+    /// Look inside the command's bodies, but not its loop or `if` conditions
+    EnterBodies,
+}
+
+/// Check whether `visit` finds any of `commands` or the commands nested in them
+fn any_command(commands: &[PosixCommand], visit: &mut dyn FnMut(&PosixCommand) -> Visit) -> bool {
+    commands.iter().any(|command| {
+        let conditions = match visit(command) {
+            Visit::Found => return true,
+            Visit::Skip => return false,
+            Visit::Enter => true,
+            Visit::EnterBodies => false,
+        };
+        match command {
+            PosixCommand::Simple(_) => false,
+            PosixCommand::Pipeline(pipe) => any_command(&pipe.commands, visit),
+            PosixCommand::List(list) => any_command(&list.commands, visit),
+            PosixCommand::AndOr(and_or) => {
+                any_command(std::slice::from_ref(&and_or.left), visit)
+                    || any_command(std::slice::from_ref(&and_or.right), visit)
+            }
+            PosixCommand::Compound(comp) => match &comp.kind {
+                CompoundCommandKind::BraceGroup(body)
+                | CompoundCommandKind::Subshell(body)
+                | CompoundCommandKind::For { body, .. }
+                | CompoundCommandKind::ArithmeticFor { body, .. }
+                | CompoundCommandKind::Function { body, .. } => any_command(body, visit),
+                CompoundCommandKind::While { condition, body }
+                | CompoundCommandKind::Until { condition, body } => {
+                    (conditions && any_command(condition, visit)) || any_command(body, visit)
+                }
+                CompoundCommandKind::If {
+                    condition,
+                    then_body,
+                    elif_parts,
+                    else_body,
+                } => {
+                    (conditions && any_command(condition, visit))
+                        || any_command(then_body, visit)
+                        || elif_parts.iter().any(|elif| {
+                            (conditions && any_command(&elif.condition, visit))
+                                || any_command(&elif.body, visit)
+                        })
+                        || else_body
+                            .as_deref()
+                            .is_some_and(|body| any_command(body, visit))
+                }
+                CompoundCommandKind::Case { items, .. } => {
+                    items.iter().any(|item| any_command(&item.body, visit))
+                }
+                CompoundCommandKind::Arithmetic { .. } => false,
+            },
+        }
+    })
+}
+
+/// Check whether any simple command within `commands` satisfies `pred`
+fn body_contains(commands: &[PosixCommand], pred: &dyn Fn(&SimpleCommandData) -> bool) -> bool {
+    any_command(commands, &mut |command| match command {
+        PosixCommand::Simple(cmd) if pred(cmd) => Visit::Found,
+        _ => Visit::Enter,
+    })
+}
+
+/// Check whether a command's arguments or assigned values use `$@`, `$*` or `$#`
+fn uses_positional_params(cmd: &SimpleCommandData) -> bool {
+    cmd.args.iter().any(|arg| references_positional_params(arg))
+        || cmd
+            .assignments
+            .iter()
+            .any(|assignment| references_positional_params(&assignment.value))
+}
+
+/// Check whether `break` or `continue` in `body` applies to the enclosing loop,
+/// ignoring those inside nested loops and functions
+fn breaks_loop(body: &[PosixCommand]) -> bool {
+    any_command(body, &mut |command| match command {
+        PosixCommand::Simple(cmd) if matches!(cmd.name.as_str(), "break" | "continue") => {
+            Visit::Found
+        }
+        PosixCommand::Compound(comp)
+            if matches!(
+                comp.kind,
+                CompoundCommandKind::For { .. }
+                    | CompoundCommandKind::ArithmeticFor { .. }
+                    | CompoundCommandKind::While { .. }
+                    | CompoundCommandKind::Until { .. }
+                    | CompoundCommandKind::Function { .. }
+            ) =>
+        {
+            Visit::Skip
+        }
+        _ => Visit::EnterBodies,
+    })
+}
+
+/// Check whether a command is a test whose conversion is already a boolean
+fn is_boolean_command(command: &PosixCommand) -> bool {
+    matches!(
+        command,
+        PosixCommand::Simple(cmd) if matches!(cmd.name.as_str(), "test" | "[" | "true" | "false" | ":")
+    )
+}
+
+/// Check whether a command is `grep -q`, whose conversion is a boolean
+fn is_quiet_grep(command: &PosixCommand) -> bool {
+    matches!(
+        command,
+        PosixCommand::Simple(cmd) if cmd.name == "grep" && cmd.args.iter().any(|arg| {
+            arg == "--quiet"
+                || arg == "--silent"
+                || (arg.starts_with('-') && !arg.starts_with("--") && arg.contains('q'))
+        })
+    )
+}
+
+/// Byte range covered by a command in its source, when known
+fn command_span(command: &PosixCommand) -> Option<(usize, usize)> {
+    let covering = |first: &PosixCommand, last: &PosixCommand| {
+        Some((command_span(first)?.0, command_span(last)?.1))
+    };
+
+    match command {
+        PosixCommand::Simple(cmd) => Some(cmd.span),
+        PosixCommand::Compound(comp) => Some(comp.span),
+        PosixCommand::Pipeline(pipe) => covering(pipe.commands.first()?, pipe.commands.last()?),
+        PosixCommand::AndOr(and_or) => covering(&and_or.left, &and_or.right),
+        PosixCommand::List(list) => covering(list.commands.first()?, list.commands.last()?),
+    }
+}
+
 impl Default for PosixToNuConverter {
     fn default() -> Self {
         Self::new()
@@ -433,6 +2327,7 @@ fn test_convert_simple_echo() {
             args: vec!["hello".to_string(), "world".to_string()],
             assignments: vec![],
             redirections: vec![],
+            span: (0, 0),
         };
 
         let result = converter.convert_simple_command(&cmd).unwrap();
@@ -440,61 +2335,1507 @@ fn test_convert_simple_echo() {
     }
 
     #[test]
-    fn test_convert_pipeline() {
+    fn test_convert_exit_status() {
         let converter = PosixToNuConverter::new();
-        let pipe = PipelineData {
-            commands: vec![
-                PosixCommand::Simple(SimpleCommandData {
-                    name: "ls".to_string(),
-                    args: vec![],
-                    assignments: vec![],
-                    redirections: vec![],
-                }),
-                PosixCommand::Simple(SimpleCommandData {
-                    name: "grep".to_string(),
-                    args: vec!["test".to_string()],
-                    assignments: vec![],
-                    redirections: vec![],
-                }),
-            ],
-            negated: false,
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
         };
 
-        let result = converter.convert_pipeline(&pipe).unwrap();
-        assert_eq!(result, "ls | where $it =~ test");
+        assert_eq!(convert("echo $?"), "print $env.LAST_EXIT_CODE");
+        assert_eq!(convert("ret=$?"), "let ret = $env.LAST_EXIT_CODE");
+        assert_eq!(
+            convert("[ $? -ne 0 ]"),
+            "($env.LAST_EXIT_CODE | into int) != 0"
+        );
     }
 
     #[test]
-    fn test_convert_if_statement() {
+    fn test_convert_special_parameters() {
         let converter = PosixToNuConverter::new();
-        let if_cmd = CompoundCommandKind::If {
-            condition: vec![PosixCommand::Simple(SimpleCommandData {
-                name: "true".to_string(),
-                args: vec![],
-                assignments: vec![],
-                redirections: vec![],
-            })],
-            then_body: vec![PosixCommand::Simple(SimpleCommandData {
-                name: "echo".to_string(),
-                args: vec!["yes".to_string()],
-                assignments: vec![],
-                redirections: vec![],
-            })],
-            elif_parts: vec![],
-            else_body: None,
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
         };
 
-        let result = converter.convert_compound_kind(&if_cmd).unwrap();
-        assert!(result.contains("if true"));
-        assert!(result.contains("print yes"));
+        assert_eq!(convert("echo $$"), "print $nu.pid");
+        assert_eq!(convert("pid=$!"), "let pid = (job list | get id | last)");
+        assert_eq!(convert("name=$0"), "let name = $env.CURRENT_FILE");
+        assert_eq!(
+            convert("lock=/tmp/lock.$$"),
+            "let lock = $\"/tmp/lock.($nu.pid)\""
+        );
     }
 
     #[test]
-    fn test_quote_arg() {
+    fn test_convert_and_or_exit_semantics() {
+        let script = parse_posix_script("mkdir d && cd d").unwrap();
+
         let converter = PosixToNuConverter::new();
+        let result = converter.convert(&script).unwrap();
+        assert!(result.contains(" and "));
 
-        assert_eq!(converter.quote_arg("simple"), "simple");
-        assert_eq!(converter.quote_arg("with space"), "\"with space\"");
-        assert_eq!(converter.quote_arg("with\"quote"), "\"with\\\"quote\"");
+        let converter = PosixToNuConverter::new().with_preserve_exit_semantics(true);
+        assert_eq!(
+            converter.convert(&script).unwrap(),
+            "if (mkdir d | complete).exit_code == 0 { cd d }"
+        );
+
+        let script = parse_posix_script("mkdir d || exit 1").unwrap();
+        assert!(converter
+            .convert(&script)
+            .unwrap()
+            .starts_with("if (mkdir d | complete).exit_code != 0 {"));
+
+        // Tests keep the boolean form
+        let script = parse_posix_script("[ -f f ] && rm f").unwrap();
+        assert!(converter.convert(&script).unwrap().contains(" and "));
+
+        // `grep -q` is a boolean that guards the right side
+        let script = parse_posix_script("grep -q x f && echo found").unwrap();
+        let expected = "if (open f | lines | where $it =~ x | is-not-empty) { print found }";
+        assert_eq!(converter.convert(&script).unwrap(), expected);
+        assert_eq!(
+            PosixToNuConverter::new().convert(&script).unwrap(),
+            expected
+        );
+
+        let script = parse_posix_script("grep -q x f || echo missing").unwrap();
+        assert_eq!(
+            converter.convert(&script).unwrap(),
+            "if not (open f | lines | where $it =~ x | is-not-empty) { print missing }"
+        );
+    }
+
+    #[test]
+    fn test_convert_command_substitution_assignment() {
+        let script = parse_posix_script("now=$(date)").unwrap();
+
+        let converter = PosixToNuConverter::new();
+        assert_eq!(converter.convert(&script).unwrap(), "let now = (date now)");
+
+        let converter = PosixToNuConverter::new().with_string_capture(true);
+        assert_eq!(
+            converter.convert(&script).unwrap(),
+            "let now = (date now | into string | str trim --right)"
+        );
+
+        // Backticks capture the same way
+        let script = parse_posix_script("user=`whoami`").unwrap();
+        assert!(converter
+            .convert(&script)
+            .unwrap()
+            .ends_with("| into string | str trim --right)"));
+
+        // A captured pipeline stays within its substitution
+        let script = parse_posix_script("x=$(ls | wc -l)").unwrap();
+        assert_eq!(
+            PosixToNuConverter::new().convert(&script).unwrap(),
+            "let x = (ls | length)"
+        );
+    }
+
+    #[test]
+    fn test_convert_heredoc_to_file() {
+        let converter = PosixToNuConverter::new();
+        let heredoc = |operator: RedirectionOp| SimpleCommandData {
+            name: "cat".to_string(),
+            args: vec![],
+            assignments: vec![],
+            redirections: vec![
+                Redirection {
+                    fd: None,
+                    operator: RedirectionOp::InputHereDoc { literal: false },
+                    target: "line one\nhome is $HOME".to_string(),
+                },
+                Redirection {
+                    fd: None,
+                    operator,
+                    target: "out.txt".to_string(),
+                },
+            ],
+            span: (0, 0),
+        };
+
+        assert_eq!(
+            converter
+                .convert_simple_command(&heredoc(RedirectionOp::Output))
+                .unwrap(),
+            "$\"line one\nhome is ($HOME)\" | save --force out.txt"
+        );
+        assert_eq!(
+            converter
+                .convert_simple_command(&heredoc(RedirectionOp::Append))
+                .unwrap(),
+            "$\"line one\nhome is ($HOME)\" | save --append out.txt"
+        );
+
+        // Parsed from source, the body spans the following lines
+        let script = parse_posix_script("cat <<EOF > out.txt\nhello\nEOF\necho done").unwrap();
+        assert_eq!(
+            converter.convert(&script).unwrap(),
+            "\"hello\" | save --force out.txt\nprint done"
+        );
+
+        // Quoted delimiters keep the body literal; otherwise only variables
+        // and `$(...)` are interpolated
+        let script = parse_posix_script("cat <<'EOF'\nhome $HOME (x)\nEOF").unwrap();
+        assert_eq!(converter.convert(&script).unwrap(), "'home $HOME (x)'");
+        let script = parse_posix_script("cat <<\"EOF\"\nit's $HOME\nEOF").unwrap();
+        assert_eq!(converter.convert(&script).unwrap(), "r#'it's $HOME'#");
+        let script = parse_posix_script("cat <<EOF\nhome $HOME (x) $(date)\nEOF").unwrap();
+        assert_eq!(
+            converter.convert(&script).unwrap(),
+            "$\"home ($HOME) \\(x\\) (date)\""
+        );
+    }
+
+    #[test]
+    fn test_convert_here_string() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        assert_eq!(
+            convert("grep foo <<< \"$bar\""),
+            "$bar | lines | where $it =~ foo"
+        );
+        assert_eq!(convert("tr a b <<< $x"), "$x | tr a b");
+        assert_eq!(convert("cat <<< hello"), "\"hello\"");
+        assert_eq!(
+            convert("wc -w <<< \"a b c\""),
+            "\"a b c\" | split words | length"
+        );
+    }
+
+    #[test]
+    fn test_convert_grep_count_pipeline() {
+        let converter = PosixToNuConverter::new();
+        let script = parse_posix_script("grep x file | wc -l").unwrap();
+        assert_eq!(
+            converter.convert(&script).unwrap(),
+            "open file | lines | where $it =~ x | length"
+        );
+
+        // A grep later in the pipeline fuses the same way
+        let script = parse_posix_script("cat log | grep error | wc -l").unwrap();
+        assert!(converter
+            .convert(&script)
+            .unwrap()
+            .ends_with("| where $it =~ error | length"));
+    }
+
+    #[test]
+    fn test_convert_list_count_pipeline() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        assert_eq!(convert("ls | wc -l"), "ls | length");
+        assert_eq!(convert("ls -1 | wc -l"), "ls | length");
+
+        // Text from cat is split into lines before counting
+        assert_eq!(convert("cat f | wc -l"), "open --raw f | lines | length");
+    }
+
+    #[test]
+    fn test_convert_echo_style() {
+        let cmd = SimpleCommandData {
+            name: "echo".to_string(),
+            args: vec!["hello".to_string()],
+            assignments: vec![],
+            redirections: vec![],
+            span: (0, 0),
+        };
+
+        let converter = PosixToNuConverter::new();
+        assert_eq!(
+            converter.convert_simple_command(&cmd).unwrap(),
+            "print hello"
+        );
+
+        let converter = PosixToNuConverter::new().with_echo_style(EchoStyle::Echo);
+        assert_eq!(
+            converter.convert_simple_command(&cmd).unwrap(),
+            "echo hello"
+        );
+
+        // Nushell's `echo` output is collected, so the loop keeps `each`
+        let script = parse_posix_script("for f in a b; do echo $f; done").unwrap();
+        assert_eq!(
+            converter.convert(&script).unwrap(),
+            "[a, b] | each { |f| \n  echo $f\n}"
+        );
+        assert_eq!(
+            PosixToNuConverter::new().convert(&script).unwrap(),
+            "for f in [a, b] {\n  print $f\n}"
+        );
+    }
+
+    #[test]
+    fn test_convert_introspection_builtins() {
+        let converter = PosixToNuConverter::new();
+        let simple = |name: &str, args: &[&str]| SimpleCommandData {
+            name: name.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            assignments: vec![],
+            redirections: vec![],
+            span: (0, 0),
+        };
+
+        let result = converter.convert_simple_command(&simple("type", &["ls"]));
+        assert_eq!(result.unwrap(), "which ls");
+
+        // `command` converts the wrapped command itself
+        let result = converter.convert_simple_command(&simple("command", &["cat", "f"]));
+        assert_eq!(
+            result.unwrap(),
+            converter
+                .convert_simple_command(&simple("cat", &["f"]))
+                .unwrap()
+        );
+        let result = converter.convert_simple_command(&simple("command", &["-p", "ls"]));
+        assert_eq!(
+            result.unwrap(),
+            converter
+                .convert_simple_command(&simple("ls", &[]))
+                .unwrap()
+        );
+        let result = converter.convert_simple_command(&simple("command", &["-v", "ls"]));
+        assert_eq!(result.unwrap(), "which ls");
+
+        let result = converter.convert_simple_command(&simple("hash", &[]));
+        assert!(result.unwrap().starts_with('#'));
+    }
+
+    #[test]
+    fn test_convert_assignments() {
+        let converter = PosixToNuConverter::new();
+
+        // Assignments prefixing a command are scoped to it
+        let cmd = SimpleCommandData {
+            name: "echo".to_string(),
+            args: vec!["$FOO".to_string()],
+            assignments: vec![Assignment {
+                name: "FOO".to_string(),
+                value: "bar".to_string(),
+                append: false,
+            }],
+            redirections: vec![],
+            span: (0, 0),
+        };
+        let result = converter.convert_simple_command(&cmd).unwrap();
+        assert_eq!(result, "with-env { FOO: \"bar\" } { print $env.FOO }");
+
+        // Several leading assignments share one record
+        let script = parse_posix_script("A=1 B=2 printenv A").unwrap();
+        assert_eq!(
+            converter.convert(&script).unwrap(),
+            "with-env { A: 1, B: 2 } { printenv A }"
+        );
+
+        // Standalone assignments define variables
+        let PosixCommand::Simple(cmd) = assign("FOO", "bar") else {
+            unreachable!()
+        };
+        let result = converter.convert_simple_command(&cmd).unwrap();
+        assert_eq!(result, "let FOO = \"bar\"");
+
+        let PosixCommand::Simple(cmd) = assign("GREETING", "'a b'") else {
+            unreachable!()
+        };
+        let result = converter.convert_simple_command(&cmd).unwrap();
+        assert_eq!(result, "let GREETING = 'a b'");
+    }
+
+    #[test]
+    fn test_convert_reassigned_variables() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        // Variables assigned more than once are declared mutable once
+        assert_eq!(convert("x=1\nx=2\nx=3"), "mut x = 1\n$x = 2\n$x = 3");
+
+        // Appending also reassigns
+        assert_eq!(
+            convert("path=/bin\npath+=:/usr/bin"),
+            "mut path = \"/bin\"\n$path += \":/usr/bin\""
+        );
+
+        // Single assignments stay immutable
+        assert_eq!(convert("x=1\ny=2"), "let x = 1\nlet y = 2");
+
+        // Reassigning inside a loop body updates the outer variable
+        assert_eq!(
+            convert("x=0\nfor i in 1 2; do x=$((x+1)); done\necho $x"),
+            "mut x = 0\nfor i in [1, 2] {\n  $x = ($x + 1)\n}\nprint $x"
+        );
+
+        // `each` closures can't assign captured variables, so read loops run natively
+        assert_eq!(
+            convert("n=0\ncat f | while read l; do n=$((n+1)); done\necho $n"),
+            "mut n = 0\nopen --raw f | for l in ($in | lines) {\n  $n = ($n + 1)\n}\nprint $n"
+        );
+        assert_eq!(
+            convert("n=0\nfor f in a b; do echo $f; n=$((n+1)); done"),
+            "mut n = 0\nfor f in [a, b] {\n  print $f\n  $n = ($n + 1)\n}"
+        );
+
+        // Variables first assigned in a nested block are declared before it
+        assert_eq!(
+            convert("if true; then x=1; x=2; fi\necho $x"),
+            "mut x: any = null\nif true {\n  $x = 1\n  $x = 2\n}\nprint $x"
+        );
+
+        // Function bodies have their own scope, including `local` declarations
+        let function = CompoundCommandKind::Function {
+            name: "f".to_string(),
+            body: vec![
+                PosixCommand::Simple(SimpleCommandData {
+                    name: "local".to_string(),
+                    args: vec!["count=0".to_string()],
+                    assignments: vec![],
+                    redirections: vec![],
+                    span: (0, 0),
+                }),
+                assign("count", "5"),
+            ],
+        };
+        assert_eq!(
+            converter.convert_compound_kind(&function).unwrap(),
+            "def f [] {\n  mut count = 0\n  $count = 5\n}"
+        );
+    }
+
+    #[test]
+    fn test_convert_exit_in_function() {
+        let exit = |code: &str| {
+            PosixCommand::Simple(SimpleCommandData {
+                name: "exit".to_string(),
+                args: vec![code.to_string()],
+                assignments: vec![],
+                redirections: vec![],
+                span: (0, 0),
+            })
+        };
+        let script = PosixScript {
+            commands: vec![
+                PosixCommand::Compound(CompoundCommandData {
+                    kind: CompoundCommandKind::Function {
+                        name: "fail".to_string(),
+                        body: vec![exit("1")],
+                    },
+                    redirections: vec![],
+                    span: (0, 0),
+                }),
+                exit("2"),
+            ],
+            interpreter: None,
+        };
+
+        // By default `exit` is kept everywhere
+        let result = PosixToNuConverter::new().convert(&script).unwrap();
+        assert_eq!(result, "def fail [] {\n  exit 1\n}\nexit 2");
+
+        // With the flag only the function's `exit` becomes `return`
+        let result = PosixToNuConverter::new()
+            .with_exit_as_return(true)
+            .convert(&script)
+            .unwrap();
+        assert_eq!(result, "def fail [] {\n  return 1\n}\nexit 2");
+    }
+
+    #[test]
+    fn test_convert_getopts_loop() {
+        let converter = PosixToNuConverter::new();
+        let source = "while getopts \"vo:h\" opt; do case $opt in v) verbose=1;; o) output=$OPTARG;; h) usage;; esac; done\nshift $((OPTIND - 1))\nprocess $@";
+        let result = converter
+            .convert(&parse_posix_script(source).unwrap())
+            .unwrap();
+
+        // Assigning arms name their flags, other arms run when their flag is set
+        assert_eq!(
+            result,
+            "def main [\n  --verbose (-v)\n  --output (-o): string\n  -h\n  ...rest\n] {\n  if $h { usage }\n  process $rest\n}"
+        );
+
+        // Assignments before the loop become defaults instead of shadowing the flags
+        let source = "verbose=0\noutput=out.txt\nwhile getopts \"vo:\" opt; do case $opt in v) verbose=1;; o) output=$OPTARG;; esac; done\necho $output";
+        let script = parse_posix_script(source).unwrap();
+        let expected = "def main [\n  --verbose (-v)\n  --output (-o): string = \"out.txt\"\n] {\n  print $output\n}";
+        assert_eq!(converter.convert(&script).unwrap(), expected);
+        assert_eq!(converter.convert_partial(&script, source), expected);
+
+        // An EXIT trap still runs its cleanup inside main
+        let source = "trap 'rm -f /tmp/x' EXIT\nwhile getopts \"v\" opt; do case $opt in v) verbose=1;; esac; done\necho hi";
+        assert_eq!(
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap(),
+            "def main [\n  --verbose (-v)\n] {\n  try {\n    print hi\n  } catch { |err|\n    rm --force /tmp/x\n    error make {msg: $err.msg}\n  }\n  rm --force /tmp/x\n}"
+        );
+    }
+
+    #[test]
+    fn test_convert_external_prefix() {
+        let converter = PosixToNuConverter::new().with_external_prefix(true);
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        assert_eq!(convert("somecli --flag"), "^somecli --flag");
+        assert_eq!(convert("somecli"), "^somecli");
+
+        // Commands with a conversion are left alone
+        assert_eq!(convert("ls -la"), "ls --long --all");
+        assert_eq!(convert("cd /tmp"), "cd /tmp");
+
+        // Without the flag unknown commands pass through bare
+        let result = PosixToNuConverter::new()
+            .convert(&parse_posix_script("somecli --flag").unwrap())
+            .unwrap();
+        assert_eq!(result, "somecli --flag");
+    }
+
+    #[test]
+    fn test_convert_head_tail_after_text() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        // Structured input is already a list
+        assert_eq!(convert("ls | head -3"), "ls | first 3");
+        assert_eq!(convert("ls | tail -3"), "ls | last 3");
+
+        // Text input is split into lines first
+        assert_eq!(convert("cat f | head -3"), "open --raw f | lines | first 3");
+        assert_eq!(convert("somecli | tail -3"), "somecli | lines | last 3");
+    }
+
+    #[test]
+    fn test_convert_backtick_substitution() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        // Backticks convert like `$(...)`
+        assert_eq!(convert("echo `date`"), "print (date now)");
+        assert_eq!(convert("echo $(date)"), "print (date now)");
+        assert_eq!(convert("ls `pwd`"), "ls (pwd)");
+
+        // Escaped inner backticks are a nested substitution
+        assert_eq!(convert("echo `echo \\`date\\``"), "print (date now)");
+        assert_eq!(
+            convert("x=`basename \\`pwd\\``"),
+            "let x = ((pwd) | path basename)"
+        );
+    }
+
+    #[test]
+    fn test_conversion_stats() {
+        let script = parse_posix_script(
+            "time ( ls; pwd )\nexec mytool\ncommand cat f\njq --tab . f\ncd /tmp",
+        )
+        .unwrap();
+        let converter = PosixToNuConverter::new();
+        converter.convert(&script).unwrap();
+
+        // `time` and the two commands it times each count once; `mytool` and
+        // the `^jq` fallback are passed through
+        let stats = converter.stats();
+        assert_eq!(stats.commands_total, 7);
+        assert_eq!(stats.converted, 5);
+        assert_eq!(stats.passthrough, 2);
+        assert_eq!(stats.commands_total, stats.converted + stats.passthrough);
+    }
+
+    #[test]
+    fn test_convert_without_notes() {
+        let script = parse_posix_script("chmod 755 run.sh").unwrap();
+
+        let converter = PosixToNuConverter::new();
+        assert_eq!(
+            converter.convert(&script).unwrap(),
+            "# Note: uses external chmod command\nchmod 755 run.sh"
+        );
+        assert!(converter.suppressed_notes().is_empty());
+
+        // Suppressed notes are collected instead of printed
+        let converter = PosixToNuConverter::new().with_notes(false);
+        assert_eq!(converter.convert(&script).unwrap(), "chmod 755 run.sh");
+        assert_eq!(
+            converter.suppressed_notes(),
+            vec!["uses external chmod command"]
+        );
+
+        // Dropping a note keeps the stages before it
+        let script = parse_posix_script("uniq -i in.txt out.txt").unwrap();
+        assert_eq!(
+            converter.convert(&script).unwrap(),
+            "open in.txt | lines | uniq | save out.txt"
+        );
+    }
+
+    #[test]
+    fn test_convert_empty_variable_tests() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        // Quoted variables are tested by value, not as literal strings
+        assert_eq!(convert("[ -z \"$VAR\" ]"), "($VAR | is-empty)");
+        assert_eq!(convert("[ -n \"$VAR\" ]"), "($VAR | is-not-empty)");
+        assert_eq!(convert("test -z \"${VAR}\""), "($VAR | is-empty)");
+
+        // The same holds for if conditions
+        assert_eq!(
+            convert("if [ -z \"$VAR\" ]; then echo empty; fi"),
+            "if ($VAR | is-empty) {\n  print empty\n}"
+        );
+    }
+
+    #[test]
+    fn test_convert_find_xargs_null_pipeline() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        assert_eq!(
+            convert("find . -name '*.c' -print0 | xargs -0 rm"),
+            "glob **/*.c | each { |f| rm $f }"
+        );
+        assert_eq!(
+            convert("find src -type f -name '*.tmp' -print0 | xargs -0 rm -f"),
+            "glob src/**/*.tmp | each { |f| rm --force $f }"
+        );
+    }
+
+    #[test]
+    fn test_convert_frequency_ranking() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        assert_eq!(
+            convert("sort | uniq -c | sort -rn"),
+            "uniq --count | sort-by count --reverse"
+        );
+        assert_eq!(
+            convert("cat words.txt | sort | uniq -c | sort -nr | head -3"),
+            "open --raw words.txt | lines | uniq --count | sort-by count --reverse | first 3"
+        );
+        assert_eq!(
+            convert("sort words.txt | uniq -c | sort -rn"),
+            "open --raw words.txt | lines | uniq --count | sort-by count --reverse"
+        );
+    }
+
+    #[test]
+    fn test_convert_nested_path_commands() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        assert_eq!(
+            convert("dir=$(dirname $(readlink -f x))"),
+            "let dir = (x | path expand | path dirname)"
+        );
+        assert_eq!(
+            convert("basename $(dirname x)"),
+            "x | path dirname | path basename"
+        );
+    }
+
+    #[test]
+    fn test_convert_colon_condition() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        assert_eq!(convert("if :; then echo y; fi"), "if true {\n  print y\n}");
+        assert_eq!(convert(": && echo y"), "(true) and (print y)");
+        // As a command it stays a no-op
+        assert_eq!(convert(":"), "null");
+    }
+
+    #[test]
+    fn test_convert_default_assignment() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        // `: ${NAME:=WORD}` only assigns
+        assert_eq!(
+            convert(": ${MODE:=fast}"),
+            "let MODE = ($MODE | default \"fast\")"
+        );
+        // Other commands use the value after assigning it
+        assert_eq!(
+            convert("echo ${MODE:=fast}"),
+            "let MODE = ($MODE | default \"fast\")\nprint ($MODE | default \"fast\")"
+        );
+        // A variable assigned before is reassigned
+        assert_eq!(
+            convert("MODE=slow\n: ${MODE:=fast}\necho $MODE"),
+            "mut MODE = \"slow\"\n$MODE = ($MODE | default \"fast\")\nprint $MODE"
+        );
+    }
+
+    #[test]
+    fn test_convert_declarations() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        assert_eq!(convert("local -r x=1"), "const x = 1");
+        assert_eq!(convert("declare -ir n=5"), "const n = 5");
+        assert_eq!(convert("readonly name=app"), "const name = \"app\"");
+        // Values only known at run time stay `let`, which is already read-only
+        assert_eq!(convert("local -r dir=$HOME"), "let dir = $HOME");
+        assert_eq!(
+            convert("declare -i n=$count"),
+            "let n = ($count | into int)"
+        );
+        assert_eq!(convert("local x=1 y"), "let x = 1; let y = null");
+        assert_eq!(convert("declare -a arr=(a b c)"), "let arr = [a b c]");
+        assert_eq!(convert("declare -x EDITOR=vim"), "$env.EDITOR = \"vim\"");
+        // Flags with no Nushell equivalent keep the command
+        assert_eq!(convert("declare -p x"), "declare -p x");
+    }
+
+    #[test]
+    fn test_convert_pipefail() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        assert_eq!(convert("somecli | sort"), "somecli | sort");
+        assert_eq!(
+            convert("set -euo pipefail\nsomecli | sort"),
+            "# set -euo pipefail: external pipeline stages are checked with complete\n(somecli | complete | if $in.exit_code != 0 { error make {msg: \"somecli failed\"} } else { $in.stdout }) | sort"
+        );
+        // Converted stages already raise errors; `+o` turns the checks off
+        assert_eq!(convert("ls | sort"), "ls | sort");
+        assert_eq!(
+            convert("set +o pipefail\nsomecli | sort"),
+            "# set +o pipefail: pipeline stage failures are no longer checked\nsomecli | sort"
+        );
+    }
+
+    #[test]
+    fn test_convert_exit_trap() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        let result = convert("tmp=/tmp/work\ntrap 'rm -f /tmp/work' EXIT\nmkdir $tmp\necho done");
+        assert_eq!(
+            result,
+            "let tmp = \"/tmp/work\"\ntry {\n  mkdir $tmp\n  print done\n} catch { |err|\n  rm --force /tmp/work\n  error make {msg: $err.msg}\n}\nrm --force /tmp/work"
+        );
+        assert!(result.ends_with("rm --force /tmp/work"));
+
+        // Other signals leave the script as it is
+        assert!(!convert("trap 'echo bye' INT\necho hi").contains("try"));
+    }
+
+    #[test]
+    fn test_convert_exec() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        let note = "# Note: exec: runs the command instead of replacing the shell";
+        assert_eq!(convert("exec ls -a"), format!("{}\nls --all", note));
+        assert!(convert("exec > out.log").starts_with("# Note: exec > out.log:"));
+
+        // The command stays part of its pipeline or condition
+        assert_eq!(
+            convert("exec myserver --port 80 | tee log"),
+            format!("{}\nmyserver --port 80 | tee {{ save log }}", note)
+        );
+        assert_eq!(
+            convert("exec sort f && echo x"),
+            format!("{}\n(open f | lines | sort) and (print x)", note)
+        );
+
+        // exec's own options are skipped, and argument lists run externally
+        assert_eq!(
+            convert("exec -a name mytool x"),
+            format!("{}\nmytool x", note)
+        );
+        assert_eq!(
+            convert("exec \"$@\""),
+            format!("{}\nrun-external ...$rest", note)
+        );
+    }
+
+    #[test]
+    fn test_convert_time() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        assert_eq!(
+            convert("time ls | grep x"),
+            "timeit { ls | lines | where $it =~ x }"
+        );
+        assert_eq!(convert("time make -j4"), "timeit { make -j4 }");
+        assert_eq!(convert("time (ls; pwd)"), "timeit { ls; pwd }");
+        assert_eq!(convert("time { ls; pwd; }"), "timeit { ls; pwd }");
+    }
+
+    #[test]
+    fn test_convert_random() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        assert_eq!(convert("echo $RANDOM"), "print (random int 0..32767)");
+        assert_eq!(
+            convert("x=$((RANDOM % 6 + 1))"),
+            "let x = ((random int 0..32767) mod 6 + 1)"
+        );
+    }
+
+    #[test]
+    fn test_convert_array_assignments() {
+        let converter = PosixToNuConverter::new();
+        let convert = |name: &str, value: &str, append: bool| {
+            converter
+                .convert_simple_command(&SimpleCommandData {
+                    name: String::new(),
+                    args: vec![],
+                    assignments: vec![Assignment {
+                        name: name.to_string(),
+                        value: value.to_string(),
+                        append,
+                    }],
+                    redirections: vec![],
+                    span: (0, 0),
+                })
+                .unwrap()
+        };
+
+        assert_eq!(convert("arr", "(a b c)", false), "let arr = [a b c]");
+        assert_eq!(
+            convert("arr", "(one \"two three\" $x)", false),
+            "let arr = [one \"two three\" $x]"
+        );
+        assert_eq!(convert("arr", "(d)", true), "$arr = ($arr | append d)");
+        assert_eq!(
+            convert("arr", "(d e)", true),
+            "$arr = ($arr | append [d e])"
+        );
+    }
+
+    #[test]
+    fn test_convert_case_patterns() {
+        let converter = PosixToNuConverter::new();
+        let case = CompoundCommandKind::Case {
+            word: "$file".to_string(),
+            items: vec![
+                CaseItemData {
+                    patterns: vec!["*.txt".to_string()],
+                    body: vec![assign("kind", "text")],
+                    terminator: CaseTerminator::Break,
+                },
+                CaseItemData {
+                    patterns: vec!["[0-9]*".to_string()],
+                    body: vec![assign("kind", "numbered")],
+                    terminator: CaseTerminator::Break,
+                },
+                CaseItemData {
+                    patterns: vec!["README".to_string(), "'LICENSE'".to_string()],
+                    body: vec![assign("kind", "docs")],
+                    terminator: CaseTerminator::Break,
+                },
+                CaseItemData {
+                    patterns: vec!["*".to_string()],
+                    body: vec![assign("kind", "other")],
+                    terminator: CaseTerminator::Break,
+                },
+            ],
+        };
+
+        let result = converter.convert_compound_kind(&case).unwrap();
+        assert_eq!(
+            result,
+            "match $file {\n  \
+             $value if $value =~ '^.*\\.txt$' => {\n    let kind = \"text\"\n  }\n  \
+             $value if $value =~ '^[0-9].*$' => {\n    let kind = \"numbered\"\n  }\n  \
+             \"README\" | \"LICENSE\" => {\n    let kind = \"docs\"\n  }\n  \
+             _ => {\n    let kind = \"other\"\n  }\n}"
+        );
+
+        // Fallthrough repeats the next body after a note
+        let case = CompoundCommandKind::Case {
+            word: "$x".to_string(),
+            items: vec![
+                CaseItemData {
+                    patterns: vec!["a".to_string()],
+                    body: vec![assign("first", "1")],
+                    terminator: CaseTerminator::Fallthrough,
+                },
+                CaseItemData {
+                    patterns: vec!["b".to_string()],
+                    body: vec![assign("second", "2")],
+                    terminator: CaseTerminator::Continue,
+                },
+            ],
+        };
+
+        let result = converter.convert_compound_kind(&case).unwrap();
+        assert_eq!(
+            result,
+            "match $x {\n  \
+             \"a\" => {\n    let first = 1\n    \
+             # fallthrough (;&) into the next case body\n    let second = 2\n  }\n  \
+             \"b\" => {\n    let second = 2\n    \
+             # ;;& would test the remaining patterns; match stops at the first arm\n  }\n}"
+        );
+
+        // Mixed literal and glob labels share one regex guard
+        assert_eq!(
+            converter.convert_case_patterns(&["a.md".to_string(), "*.txt".to_string()]),
+            "$value if $value =~ '^(?:a\\.md|.*\\.txt)$'"
+        );
+    }
+
+    #[test]
+    fn test_convert_to_writer_matches_convert() {
+        let converter = PosixToNuConverter::new();
+        let script =
+            parse_posix_script("echo hello\nls -lt\nFOO=bar\nfor i in 1 2 3 do echo $i done")
+                .unwrap();
+
+        let mut written = String::new();
+        converter.convert_to_writer(&script, &mut written).unwrap();
+        assert_eq!(written, converter.convert(&script).unwrap());
+
+        // Commands are written as they convert, before a later one fails
+        let script = parse_posix_script("echo start\nfor ((; i<3; i++)) do echo $i done").unwrap();
+        let mut written = String::new();
+        assert!(converter.convert_to_writer(&script, &mut written).is_err());
+        assert_eq!(written, "print start");
+    }
+
+    #[test]
+    fn test_convert_partial() {
+        let converter = PosixToNuConverter::new();
+        let source = "echo start\nfor ((; i<3; i++)) do echo $i done\nls | grep test";
+        let script = parse_posix_script(source).unwrap();
+
+        // Strict conversion stops at the first failure
+        assert!(converter.convert(&script).is_err());
+
+        let result = converter.convert_partial(&script, source);
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "print start");
+        assert_eq!(
+            lines[1],
+            "# UNCONVERTED: for ((; i<3; i++)) do echo $i done"
+        );
+        assert!(lines[2].starts_with("ls |"));
+    }
+
+    #[test]
+    fn test_convert_path_updates() {
+        let converter = PosixToNuConverter::new();
+        let export = |arg: &str| SimpleCommandData {
+            name: "export".to_string(),
+            args: vec![arg.to_string()],
+            assignments: vec![],
+            redirections: vec![],
+            span: (0, 0),
+        };
+        let PosixCommand::Simple(append) = assign("PATH", "\"$PATH:/opt/bin\"") else {
+            unreachable!()
+        };
+        let PosixCommand::Simple(prepend) = assign("PATH", "$HOME/bin:$PATH") else {
+            unreachable!()
+        };
+
+        assert_eq!(
+            converter.convert_simple_command(&append).unwrap(),
+            "$env.PATH = ($env.PATH | append \"/opt/bin\")"
+        );
+        assert_eq!(
+            converter.convert_simple_command(&prepend).unwrap(),
+            "$env.PATH = ($env.PATH | prepend $\"($HOME)/bin\")"
+        );
+        assert_eq!(
+            converter
+                .convert_simple_command(&export("PATH=\"/a:$PATH:/b:/c\""))
+                .unwrap(),
+            "$env.PATH = ($env.PATH | prepend \"/a\" | append [\"/b\" \"/c\"])"
+        );
+        assert_eq!(
+            converter
+                .convert_simple_command(&export("BACKUP=$name.bak"))
+                .unwrap(),
+            "$env.BACKUP = $\"($name).bak\""
+        );
+        assert_eq!(
+            converter
+                .convert_simple_command(&export("EDITOR=vim"))
+                .unwrap(),
+            "$env.EDITOR = \"vim\""
+        );
+    }
+
+    #[test]
+    fn test_convert_echo_variable_pipeline() {
+        let converter = PosixToNuConverter::new();
+        let pipeline = |echoed: &str| PipelineData {
+            commands: vec![
+                PosixCommand::Simple(SimpleCommandData {
+                    name: "echo".to_string(),
+                    args: vec![echoed.to_string()],
+                    assignments: vec![],
+                    redirections: vec![],
+                    span: (0, 0),
+                }),
+                PosixCommand::Simple(SimpleCommandData {
+                    name: "grep".to_string(),
+                    args: vec!["y".to_string()],
+                    assignments: vec![],
+                    redirections: vec![],
+                    span: (0, 0),
+                }),
+            ],
+            negated: false,
+        };
+
+        let result = converter.convert_pipeline(&pipeline("$x")).unwrap();
+        assert_eq!(result, "$x | lines | where $it =~ y");
+
+        let result = converter.convert_pipeline(&pipeline("\"$x\"")).unwrap();
+        assert_eq!(result, "$x | lines | where $it =~ y");
+
+        // Other text is fed in as a string
+        let result = converter.convert_pipeline(&pipeline("hello")).unwrap();
+        assert_eq!(result, "\"hello\" | lines | where $it =~ y");
+
+        let result = converter
+            .convert_pipeline(&pipeline("\"user: $USER\""))
+            .unwrap();
+        assert_eq!(result, "$\"user: ($USER)\" | lines | where $it =~ y");
+
+        // A plain `echo` writes the same string
+        let script = parse_posix_script("echo \"a $x\"\necho 'a b' \"$x\"").unwrap();
+        assert_eq!(
+            converter.convert(&script).unwrap(),
+            "print $\"a ($x)\"\nprint $\"a b ($x)\""
+        );
+    }
+
+    #[test]
+    fn test_convert_echo_expansions() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        // Expansions among other words are interpolated
+        assert_eq!(
+            convert("echo len ${#x}"),
+            "print $\"len ($x | str length)\""
+        );
+        assert_eq!(
+            convert("echo ${f/a/b} done"),
+            "print $\"($f | str replace \"a\" \"b\") done\""
+        );
+        assert_eq!(convert("echo ${arr[0]} x"), "print $\"($arr | get 0) x\"");
+        assert_eq!(convert("echo pid $$"), "print $\"pid ($nu.pid)\"");
+    }
+
+    #[test]
+    fn test_convert_process_substitution() {
+        let converter = PosixToNuConverter::new();
+        let script = parse_posix_script("diff <(cat a) <(cat b)").unwrap();
+        let cat = |file: &str| {
+            let script = parse_posix_script(&format!("cat {}", file)).unwrap();
+            converter.convert(&script).unwrap()
+        };
+
+        let result = converter.convert(&script).unwrap();
+        assert_eq!(result, format!("diff ({}) ({})", cat("a"), cat("b")));
+    }
+
+    #[test]
+    fn test_convert_tee_process_substitution() {
+        let converter = PosixToNuConverter::new();
+        let script = parse_posix_script("cat f | tee >(wc -l)").unwrap();
+
+        let result = converter.convert(&script).unwrap();
+        assert_eq!(result, "open --raw f | tee { lines | length }");
+    }
+
+    #[test]
+    fn test_convert_pipeline() {
+        let converter = PosixToNuConverter::new();
+        let pipe = PipelineData {
+            commands: vec![
+                PosixCommand::Simple(SimpleCommandData {
+                    name: "ls".to_string(),
+                    args: vec![],
+                    assignments: vec![],
+                    redirections: vec![],
+                    span: (0, 0),
+                }),
+                PosixCommand::Simple(SimpleCommandData {
+                    name: "grep".to_string(),
+                    args: vec!["test".to_string()],
+                    assignments: vec![],
+                    redirections: vec![],
+                    span: (0, 0),
+                }),
+            ],
+            negated: false,
+        };
+
+        let result = converter.convert_pipeline(&pipe).unwrap();
+        assert_eq!(result, "ls | where $it =~ test");
+    }
+
+    #[test]
+    fn test_convert_if_statement() {
+        let converter = PosixToNuConverter::new();
+        let if_cmd = CompoundCommandKind::If {
+            condition: vec![PosixCommand::Simple(SimpleCommandData {
+                name: "true".to_string(),
+                args: vec![],
+                assignments: vec![],
+                redirections: vec![],
+                span: (0, 0),
+            })],
+            then_body: vec![PosixCommand::Simple(SimpleCommandData {
+                name: "echo".to_string(),
+                args: vec!["yes".to_string()],
+                assignments: vec![],
+                redirections: vec![],
+                span: (0, 0),
+            })],
+            elif_parts: vec![],
+            else_body: None,
+        };
+
+        let result = converter.convert_compound_kind(&if_cmd).unwrap();
+        assert!(result.contains("if true"));
+        assert!(result.contains("print yes"));
+    }
+
+    fn assign(name: &str, value: &str) -> PosixCommand {
+        PosixCommand::Simple(SimpleCommandData {
+            name: String::new(),
+            args: vec![],
+            assignments: vec![Assignment {
+                name: name.to_string(),
+                value: value.to_string(),
+                append: false,
+            }],
+            redirections: vec![],
+            span: (0, 0),
+        })
+    }
+
+    #[test]
+    fn test_convert_if_expression() {
+        let converter = PosixToNuConverter::new();
+        let condition = vec![PosixCommand::Simple(SimpleCommandData {
+            name: "true".to_string(),
+            args: vec![],
+            assignments: vec![],
+            redirections: vec![],
+            span: (0, 0),
+        })];
+
+        // Every branch assigns the same variable
+        let if_cmd = CompoundCommandKind::If {
+            condition: condition.clone(),
+            then_body: vec![assign("y", "1")],
+            elif_parts: vec![],
+            else_body: Some(vec![assign("y", "2")]),
+        };
+        let result = converter.convert_compound_kind(&if_cmd).unwrap();
+        assert_eq!(result, "let y = if true { 1 } else { 2 }");
+
+        // Elif branches are chained
+        let if_cmd = CompoundCommandKind::If {
+            condition: condition.clone(),
+            then_body: vec![assign("mode", "fast")],
+            elif_parts: vec![ElifPart {
+                condition: condition.clone(),
+                body: vec![assign("mode", "slow")],
+            }],
+            else_body: Some(vec![assign("mode", "$DEFAULT")]),
+        };
+        let result = converter.convert_compound_kind(&if_cmd).unwrap();
+        assert_eq!(
+            result,
+            "let mode = if true { \"fast\" } else if true { \"slow\" } else { $DEFAULT }"
+        );
+
+        // Different variables keep the statement form
+        let if_cmd = CompoundCommandKind::If {
+            condition: condition.clone(),
+            then_body: vec![assign("y", "1")],
+            elif_parts: vec![],
+            else_body: Some(vec![assign("z", "2")]),
+        };
+        let result = converter.convert_compound_kind(&if_cmd).unwrap();
+        assert!(result.starts_with("if true {\n"));
+
+        // A missing else branch keeps the statement form
+        let if_cmd = CompoundCommandKind::If {
+            condition,
+            then_body: vec![assign("y", "1")],
+            elif_parts: vec![],
+            else_body: None,
+        };
+        let result = converter.convert_compound_kind(&if_cmd).unwrap();
+        assert!(result.starts_with("if true {\n"));
+    }
+
+    #[test]
+    fn test_convert_for_seq_range() {
+        let converter = PosixToNuConverter::new();
+        let for_loop = CompoundCommandKind::For {
+            variable: "i".to_string(),
+            words: vec!["$(seq 1 10)".to_string()],
+            body: vec![PosixCommand::Simple(SimpleCommandData {
+                name: "echo".to_string(),
+                args: vec!["$i".to_string()],
+                assignments: vec![],
+                redirections: vec![],
+                span: (0, 0),
+            })],
+        };
+
+        let result = converter.convert_compound_kind(&for_loop).unwrap();
+        assert_eq!(result, "for i in 1..10 {\n  print $i\n}");
+    }
+
+    #[test]
+    fn test_convert_arithmetic_for_loop() {
+        let converter = PosixToNuConverter::new();
+        let for_loop = |init: &str, condition: &str, update: &str| {
+            let kind = CompoundCommandKind::ArithmeticFor {
+                init: init.to_string(),
+                condition: condition.to_string(),
+                update: update.to_string(),
+                body: vec![PosixCommand::Simple(SimpleCommandData {
+                    name: "echo".to_string(),
+                    args: vec!["$i".to_string()],
+                    assignments: vec![],
+                    redirections: vec![],
+                    span: (0, 0),
+                })],
+            };
+            converter.convert_compound_kind(&kind).unwrap()
+        };
+
+        // Counting up by one is a range
+        assert_eq!(
+            for_loop("i=0", "i<10", "i++"),
+            "for i in 0..9 {\n  print $i\n}"
+        );
+        assert_eq!(
+            for_loop("i=1", "i<=5", "i+=1"),
+            "for i in 1..5 {\n  print $i\n}"
+        );
+
+        // A loop that never runs must not become a descending range
+        assert_eq!(
+            for_loop("i=5", "i<3", "i++"),
+            "mut i = 5\nwhile $i < 3 {\n  print $i\n  $i += 1\n}"
+        );
+
+        // Anything else becomes a mutable counter
+        assert_eq!(
+            for_loop("i=10", "i>0", "i--"),
+            "mut i = 10\nwhile $i > 0 {\n  print $i\n  $i -= 1\n}"
+        );
+        assert_eq!(
+            for_loop("i=0", "i<n", "i+=2"),
+            "mut i = 0\nwhile $i < $n {\n  print $i\n  $i += 2\n}"
+        );
+    }
+
+    #[test]
+    fn test_convert_while_true_loop() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        assert_eq!(
+            convert("while true; do echo x; done"),
+            "loop {\n  print x\n}"
+        );
+        assert_eq!(convert("while :; do echo x; done"), "loop {\n  print x\n}");
+
+        // Other conditions keep the while loop
+        assert_eq!(
+            convert("while false; do echo x; done"),
+            "while false {\n  print x\n}"
+        );
+    }
+
+    #[test]
+    fn test_convert_for_loop_with_break() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        // Loops that break or continue stay native loops
+        assert_eq!(
+            convert("for f in a b c; do if [ -e $f ]; then break; fi; done"),
+            "for f in [a, b, c] {\n  if ($f | path exists) {\n  break\n}\n}"
+        );
+        assert_eq!(
+            convert("for ((i=0; i<3; i++)); do continue; done"),
+            "for i in 0..2 {\n  continue\n}"
+        );
+
+        // Otherwise value-producing loops become each closures
+        assert_eq!(
+            convert("for f in a b; do basename $f; done"),
+            "[a, b] | each { |f| \n  $f | path basename\n}"
+        );
+    }
+
+    #[test]
+    fn test_convert_for_loop_side_effects() {
+        let converter = PosixToNuConverter::new();
+        let convert = |source: &str| {
+            converter
+                .convert(&parse_posix_script(source).unwrap())
+                .unwrap()
+        };
+
+        // Loops run for their effects are statements
+        assert_eq!(
+            convert("for d in a b; do mkdir $d; done"),
+            "for d in [a, b] {\n  mkdir $d\n}"
+        );
+
+        // Loops producing values map over the list
+        assert_eq!(
+            convert("for f in a b; do dirname $f; done"),
+            "[a, b] | each { |f| \n  $f | path dirname\n}"
+        );
+    }
+
+    #[test]
+    fn test_convert_while_read_loop() {
+        let converter = PosixToNuConverter::new();
+        let read_loop = CompoundCommandKind::While {
+            condition: vec![PosixCommand::Simple(SimpleCommandData {
+                name: "read".to_string(),
+                args: vec!["line".to_string()],
+                assignments: vec![],
+                redirections: vec![],
+                span: (0, 0),
+            })],
+            body: vec![PosixCommand::Simple(SimpleCommandData {
+                name: "echo".to_string(),
+                args: vec!["$line".to_string()],
+                assignments: vec![],
+                redirections: vec![],
+                span: (0, 0),
+            })],
+        };
+
+        let result = converter.convert_compound_kind(&read_loop).unwrap();
+        assert_eq!(result, "lines | each { |line|\n  print $line\n}");
+
+        // Input redirected from a file opens it first
+        let redirected = CompoundCommandData {
+            kind: read_loop,
+            redirections: vec![Redirection {
+                fd: None,
+                operator: RedirectionOp::Input,
+                target: "input.txt".to_string(),
+            }],
+            span: (0, 0),
+        };
+        let result = converter.convert_compound_command(&redirected).unwrap();
+        assert_eq!(
+            result,
+            "open input.txt | lines | each { |line|\n  print $line\n}"
+        );
+
+        // A loop that breaks out early needs a native loop
+        let CompoundCommandKind::While {
+            condition,
+            mut body,
+        } = redirected.kind.clone()
+        else {
+            unreachable!()
+        };
+        body.push(PosixCommand::Simple(SimpleCommandData {
+            name: "break".to_string(),
+            args: vec![],
+            assignments: vec![],
+            redirections: vec![],
+            span: (0, 0),
+        }));
+        let breaking = CompoundCommandKind::While { condition, body };
+        assert_eq!(
+            converter.convert_compound_kind(&breaking).unwrap(),
+            "for line in ($in | lines) {\n  print $line\n  break\n}"
+        );
+        let redirected = CompoundCommandData {
+            kind: breaking,
+            ..redirected
+        };
+        assert_eq!(
+            converter.convert_compound_command(&redirected).unwrap(),
+            "for line in (open input.txt | lines) {\n  print $line\n  break\n}"
+        );
+
+        // The redirection after `done` applies to the whole loop
+        let script =
+            parse_posix_script("count=0; while read line; do count=$((count+1)); done < f.txt")
+                .unwrap();
+        assert_eq!(
+            converter.convert(&script).unwrap(),
+            "mut count = 0\nfor line in (open f.txt | lines) {\n  $count = ($count + 1)\n}"
+        );
+        let script = parse_posix_script("while read line; do echo $line; done <f.txt").unwrap();
+        assert_eq!(
+            converter.convert(&script).unwrap(),
+            "open f.txt | lines | each { |line|\n  print $line\n}"
+        );
+    }
+
+    #[test]
+    fn test_quote_arg() {
+        let converter = PosixToNuConverter::new();
+
+        assert_eq!(converter.quote_arg("simple"), "simple");
+        assert_eq!(converter.quote_arg("with space"), "\"with space\"");
+        assert_eq!(converter.quote_arg("with\"quote"), "\"with\\\"quote\"");
+        assert_eq!(converter.quote_arg("$rest"), "$rest");
+    }
+
+    #[test]
+    fn test_convert_function_positional_parameters() {
+        let converter = PosixToNuConverter::new();
+        let function = CompoundCommandKind::Function {
+            name: "forward".to_string(),
+            body: vec![
+                PosixCommand::Simple(SimpleCommandData {
+                    name: "process".to_string(),
+                    args: vec!["\"$@\"".to_string()],
+                    assignments: vec![],
+                    redirections: vec![],
+                    span: (0, 0),
+                }),
+                PosixCommand::Simple(SimpleCommandData {
+                    name: "report".to_string(),
+                    args: vec!["$#".to_string()],
+                    assignments: vec![],
+                    redirections: vec![],
+                    span: (0, 0),
+                }),
+            ],
+        };
+
+        let result = converter.convert_compound_kind(&function).unwrap();
+        assert_eq!(
+            result,
+            "def forward [...rest] {\n  process $rest\n  report ($rest | length)\n}"
+        );
+
+        // Functions without positional parameters keep an empty signature
+        let function = CompoundCommandKind::Function {
+            name: "greet".to_string(),
+            body: vec![PosixCommand::Simple(SimpleCommandData {
+                name: "hello".to_string(),
+                args: vec![],
+                assignments: vec![],
+                redirections: vec![],
+                span: (0, 0),
+            })],
+        };
+        let result = converter.convert_compound_kind(&function).unwrap();
+        assert!(result.starts_with("def greet [] {"));
+
+        // Assigned values count too, and interpolate inside strings
+        let function = CompoundCommandKind::Function {
+            name: "count".to_string(),
+            body: vec![assign("n", "$#"), assign("msg", "\"got $# args: $*\"")],
+        };
+        assert_eq!(
+            converter.convert_compound_kind(&function).unwrap(),
+            "def count [...rest] {\n  let n = ($rest | length)\n  let msg = $\"got ($rest | length) args: ($rest | str join ' ')\"\n}"
+        );
     }
 }
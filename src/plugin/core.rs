@@ -3,7 +3,11 @@
     Category, Example, LabeledError, Record, Signature, Span, SyntaxShape, Type, Value,
 };
 
-use super::{converter::PosixToNuConverter, parser_posix::parse_posix_script};
+use super::{
+    converter::{ConversionStats, PosixToNuConverter},
+    parser_posix::{parse_posix_script_with_dialect, Dialect},
+    sus::EchoStyle,
+};
 
 pub struct PosixPlugin;
 
@@ -41,6 +45,8 @@ fn signature(&self) -> Signature {
             .input_output_types(vec![
                 (Type::String, Type::String),
                 (Type::Nothing, Type::String),
+                (Type::String, Type::record()),
+                (Type::Nothing, Type::record()),
             ])
             .named(
                 "pretty",
@@ -54,6 +60,64 @@ fn signature(&self) -> Signature {
                 "Read POSIX script from file",
                 Some('f'),
             )
+            .named(
+                "dialect",
+                SyntaxShape::String,
+                "Shell dialect to parse: posix, bash or dash (default: from the shebang)",
+                None,
+            )
+            .named(
+                "output",
+                SyntaxShape::Filepath,
+                "Also write the converted script to a file",
+                Some('o'),
+            )
+            .switch(
+                "shebang",
+                "Prepend #!/usr/bin/env nu; with --output, mark the file executable",
+                None,
+            )
+            .switch(
+                "echo-command",
+                "Convert echo to Nushell's echo instead of print",
+                None,
+            )
+            .switch(
+                "keep-nushell",
+                "Return input that already looks like Nushell unchanged",
+                None,
+            )
+            .switch(
+                "preserve-exit-semantics",
+                "Convert && and || between commands into exit-code checks",
+                None,
+            )
+            .switch(
+                "string-capture",
+                "Capture $(...) assignments as trimmed strings",
+                None,
+            )
+            .switch(
+                "exit-as-return",
+                "Convert exit inside functions to return",
+                None,
+            )
+            .switch(
+                "external-prefix",
+                "Prefix unrecognized commands with ^ to run them as externals",
+                None,
+            )
+            .switch("no-notes", "Leave out advisory # Note: comments", None)
+            .switch(
+                "partial",
+                "Keep converting after failures, commenting out unconverted commands",
+                None,
+            )
+            .switch(
+                "stats",
+                "Return counts of converted and passed-through commands instead of the script",
+                None,
+            )
             .category(Category::Conversions)
     }
 
@@ -85,6 +149,14 @@ fn run(
         input: &Value,
     ) -> Result<Value, LabeledError> {
         let pretty = call.has_flag("pretty")?;
+        let partial = call.has_flag("partial")?;
+        let shebang = call.has_flag("shebang")?;
+        let echo_style = if call.has_flag("echo-command")? {
+            EchoStyle::Echo
+        } else {
+            EchoStyle::Print
+        };
+        let output_path = call.get_flag::<String>("output")?;
         let file_path = call.get_flag::<String>("file")?;
 
         let posix_script = if let Some(file_path) = file_path {
@@ -108,18 +180,38 @@ fn run(
             }
         };
 
+        // Input that is already Nushell is flagged, or passed through untouched
+        let warning = nushell_warning(&posix_script);
+        if warning.is_some() && call.has_flag("keep-nushell")? {
+            return Ok(Value::string(posix_script, call.head));
+        }
+
         // Parse the POSIX script
-        let parsed_script = parse_posix_script(&posix_script).map_err(|e| {
-            LabeledError::new(format!("Failed to parse POSIX script: {}", e))
-                .with_label("parse error", call.head)
-        })?;
+        let parsed_script = parse_posix_script_with_dialect(&posix_script, dialect_flag(call)?)
+            .map_err(|e| {
+                LabeledError::new(format!("Failed to parse POSIX script: {}", e))
+                    .with_label("parse error", call.head)
+            })?;
 
         // Convert to Nushell syntax
-        let converter = PosixToNuConverter::new();
-        let nu_script = converter.convert(&parsed_script).map_err(|e| {
-            LabeledError::new(format!("Failed to convert to Nushell: {}", e))
-                .with_label("conversion error", call.head)
-        })?;
+        let converter = PosixToNuConverter::new()
+            .with_echo_style(echo_style)
+            .with_preserve_exit_semantics(call.has_flag("preserve-exit-semantics")?)
+            .with_string_capture(call.has_flag("string-capture")?)
+            .with_exit_as_return(call.has_flag("exit-as-return")?)
+            .with_external_prefix(call.has_flag("external-prefix")?)
+            .with_notes(!call.has_flag("no-notes")?);
+        let nu_script = if partial {
+            converter.convert_partial(&parsed_script, &posix_script)
+        } else {
+            converter.convert(&parsed_script).map_err(|e| {
+                LabeledError::new(format!("Failed to convert to Nushell: {}", e))
+                    .with_label("conversion error", call.head)
+            })?
+        };
+        for note in converter.suppressed_notes() {
+            log::debug!("Suppressed note: {}", note);
+        }
 
         // Format if requested
         let output = if pretty {
@@ -127,11 +219,45 @@ fn run(
         } else {
             nu_script
         };
+        let output = match warning {
+            Some(warning) => format!("{}\n{}", warning, output),
+            None => output,
+        };
+        let output = if shebang {
+            add_shebang(&output)
+        } else {
+            output
+        };
+
+        if let Some(output_path) = output_path {
+            write_output(&output_path, &output, shebang).map_err(|e| {
+                LabeledError::new(format!("Failed to write output file: {}", e))
+                    .with_label("file write error", call.head)
+            })?;
+        }
+
+        if call.has_flag("stats")? {
+            return Ok(stats_record(&converter.stats(), call.head));
+        }
 
         Ok(Value::string(output, call.head))
     }
 }
 
+/// Report conversion counts as a `{commands_total, converted, passthrough, warnings}` record
+fn stats_record(stats: &ConversionStats, span: Span) -> Value {
+    let mut record = Record::new();
+    for (name, count) in [
+        ("commands_total", stats.commands_total),
+        ("converted", stats.converted),
+        ("passthrough", stats.passthrough),
+        ("warnings", stats.warnings),
+    ] {
+        record.insert(name.to_string(), Value::int(count as i64, span));
+    }
+    Value::record(record, span)
+}
+
 pub struct ToPosix;
 
 impl SimplePluginCommand for ToPosix {
@@ -202,6 +328,12 @@ fn description(&self) -> &str {
     fn signature(&self) -> Signature {
         Signature::build("parse posix")
             .input_output_types(vec![(Type::String, Type::Record(vec![].into()))])
+            .named(
+                "dialect",
+                SyntaxShape::String,
+                "Shell dialect to parse: posix, bash or dash (default: from the shebang)",
+                None,
+            )
             .category(Category::Conversions)
     }
 
@@ -234,10 +366,11 @@ fn run(
         };
 
         // Parse the POSIX script
-        let parsed_script = parse_posix_script(&posix_script).map_err(|e| {
-            LabeledError::new(format!("Failed to parse POSIX script: {}", e))
-                .with_label("parse error", call.head)
-        })?;
+        let parsed_script = parse_posix_script_with_dialect(&posix_script, dialect_flag(call)?)
+            .map_err(|e| {
+                LabeledError::new(format!("Failed to parse POSIX script: {}", e))
+                    .with_label("parse error", call.head)
+            })?;
 
         // Convert to Nushell Value
         let ast_value = convert_ast_to_value(&parsed_script, call.head);
@@ -246,6 +379,62 @@ fn run(
     }
 }
 
+/// Read the `--dialect` flag, if given
+fn dialect_flag(call: &EvaluatedCall) -> Result<Option<Dialect>, LabeledError> {
+    call.get_flag::<String>("dialect")?
+        .map(|name| {
+            name.parse::<Dialect>().map_err(|e| {
+                LabeledError::new(e.to_string()).with_label("invalid dialect", call.head)
+            })
+        })
+        .transpose()
+}
+
+/// Warn when the input already looks like Nushell rather than POSIX shell
+fn nushell_warning(script: &str) -> Option<String> {
+    let marker = script.lines().map(str::trim).find_map(|line| {
+        if line.contains("| where ") {
+            Some("| where")
+        } else if line.match_indices("$in").any(|(pos, _)| {
+            // `$input` or `$index` are ordinary shell variables
+            !line[pos + 3..].starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+        }) {
+            Some("$in")
+        } else if line.starts_with("def ") && line.contains('[') {
+            Some("def ... []")
+        } else {
+            None
+        }
+    })?;
+
+    Some(format!(
+        "# warning: input already looks like Nushell (found `{}`)",
+        marker
+    ))
+}
+
+/// Prepend a Nushell shebang line to a converted script
+fn add_shebang(script: &str) -> String {
+    format!("#!/usr/bin/env nu\n{}", script)
+}
+
+/// Write a converted script to `path`, marking it executable on Unix if requested
+fn write_output(path: &str, contents: &str, executable: bool) -> std::io::Result<()> {
+    std::fs::write(path, contents)?;
+
+    #[cfg(unix)]
+    if executable {
+        use std::os::unix::fs::PermissionsExt;
+        let mut permissions = std::fs::metadata(path)?.permissions();
+        permissions.set_mode(permissions.mode() | 0o111);
+        std::fs::set_permissions(path, permissions)?;
+    }
+    #[cfg(not(unix))]
+    let _ = executable;
+
+    Ok(())
+}
+
 fn format_nu_script(script: &str) -> String {
     let lines: Vec<&str> = script.lines().collect();
     let mut formatted = String::new();
@@ -296,10 +485,25 @@ fn convert_ast_to_value(script: &super::parser_posix::PosixScript, span: Span) -
             span,
         ),
     );
+    record.insert(
+        "interpreter".to_string(),
+        match &script.interpreter {
+            Some(interpreter) => Value::string(interpreter, span),
+            None => Value::nothing(span),
+        },
+    );
 
     Value::record(record, span)
 }
 
+/// Convert a source byte range into a `{start, end}` record
+fn convert_span_to_value(range: (usize, usize), span: Span) -> Value {
+    let mut record = Record::new();
+    record.insert("start".to_string(), Value::int(range.0 as i64, span));
+    record.insert("end".to_string(), Value::int(range.1 as i64, span));
+    Value::record(record, span)
+}
+
 fn convert_command_to_value(command: &super::parser_posix::PosixCommand, span: Span) -> Value {
     let mut record = Record::new();
 
@@ -317,6 +521,7 @@ fn convert_command_to_value(command: &super::parser_posix::PosixCommand, span: S
                     span,
                 ),
             );
+            record.insert("span".to_string(), convert_span_to_value(cmd.span, span));
         }
         super::parser_posix::PosixCommand::Pipeline(pipe) => {
             record.insert("type".to_string(), Value::string("pipeline", span));
@@ -332,9 +537,10 @@ fn convert_command_to_value(command: &super::parser_posix::PosixCommand, span: S
             );
             record.insert("negated".to_string(), Value::bool(pipe.negated, span));
         }
-        super::parser_posix::PosixCommand::Compound(_comp) => {
+        super::parser_posix::PosixCommand::Compound(comp) => {
             record.insert("type".to_string(), Value::string("compound", span));
             record.insert("kind".to_string(), Value::string("compound", span)); // Simplified
+            record.insert("span".to_string(), convert_span_to_value(comp.span, span));
         }
         super::parser_posix::PosixCommand::AndOr(and_or) => {
             record.insert("type".to_string(), Value::string("andor", span));
@@ -366,3 +572,64 @@ fn convert_command_to_value(command: &super::parser_posix::PosixCommand, span: S
 
     Value::record(record, span)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_shebang() {
+        let output = add_shebang("print hello");
+        assert!(output.starts_with("#!/usr/bin/env nu\n"));
+        assert_eq!(output, "#!/usr/bin/env nu\nprint hello");
+    }
+
+    #[test]
+    fn test_nushell_warning() {
+        assert_eq!(
+            nushell_warning("ls | where size > 1kb").unwrap(),
+            "# warning: input already looks like Nushell (found `| where`)"
+        );
+        assert!(nushell_warning("$in | length").unwrap().contains("`$in`"));
+        assert!(nushell_warning("def greet [name] {\n  print $name\n}")
+            .unwrap()
+            .contains("`def ... []`"));
+
+        // POSIX input is not flagged
+        assert!(nushell_warning("ls | grep test\necho \"$HOME\"").is_none());
+        assert!(nushell_warning("echo \"$input\" \"$index\"").is_none());
+    }
+
+    #[test]
+    fn test_stats_record() {
+        let script = "echo hi\nx=1\nmytool --fast\nls | grep foo\nuniq -i names.txt";
+        let parsed = parse_posix_script_with_dialect(script, None).unwrap();
+        let converter = PosixToNuConverter::new();
+        converter.convert(&parsed).unwrap();
+
+        let record = stats_record(&converter.stats(), Span::test_data());
+        let count = |name: &str| record.get_data_by_key(name).unwrap().as_int().unwrap();
+        assert_eq!(count("commands_total"), 6);
+        assert_eq!(count("converted"), 5);
+        assert_eq!(count("passthrough"), 1);
+        // `uniq -i` notes that case folding isn't supported
+        assert_eq!(count("warnings"), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_output_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("script.nu");
+        let path_str = path.to_str().unwrap();
+        write_output(path_str, &add_shebang("print hello"), true).unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_ne!(mode & 0o111, 0);
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .starts_with("#!/usr/bin/env nu"));
+    }
+}
@@ -0,0 +1,781 @@
+//! Shell parameter expansion converter
+//!
+//! Converts POSIX parameter expansions that appear inside command words
+//! (`$@`, `$*`, `$#`, `${#var}`, `${var%pat}`, `${var:-default}`,
+//! `${var/pat/repl}`, `${arr[0]}`, `$((a + 1))`, ...) to their Nushell equivalents.
+
+/// Convert the parameter expansions in a single shell word to Nushell syntax
+///
+/// A word that consists of exactly one expansion (optionally wrapped in double
+/// quotes) is replaced by the bare Nushell expression. Expansions embedded in a
+/// larger word are substituted in place. Single-quoted words are left untouched
+/// since the shell performs no expansion inside them.
+pub fn expand_word(word: &str) -> String {
+    if word.starts_with('\'') || !word.contains('$') {
+        return word.to_string();
+    }
+
+    let inner = strip_double_quotes(word);
+    if let Some((expr, consumed)) = parse_expansion(inner) {
+        if consumed == inner.len() {
+            return expr;
+        }
+    }
+
+    let mut result = String::new();
+    let mut i = 0;
+    while i < word.len() {
+        let rest = &word[i..];
+        if rest.starts_with('$') {
+            if let Some((expr, consumed)) = parse_expansion(rest) {
+                result.push_str(&expr);
+                i += consumed;
+                continue;
+            }
+        }
+        let ch = rest.chars().next().unwrap_or_default();
+        result.push(ch);
+        i += ch.len_utf8();
+    }
+
+    result
+}
+
+/// Convert the text inside a double-quoted shell word into the body of a
+/// Nushell `$"..."` string, interpolating each expansion as a `(...)` expression
+///
+/// Positional parameter lists are joined with spaces, as the shell does, and
+/// backslash escapes are kept as they are.
+pub fn interpolate_word(text: &str) -> String {
+    let mut result = String::new();
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        if let Some(escaped) = rest.strip_prefix('\\') {
+            let len = 1 + escaped.chars().next().map_or(0, char::len_utf8);
+            result.push_str(&rest[..len]);
+            i += len;
+            continue;
+        }
+        if rest.starts_with('$') {
+            if let Some((expr, consumed)) = parse_expansion(rest) {
+                if expr == "$rest" {
+                    result.push_str("($rest | str join ' ')");
+                } else if expr.starts_with('(') && is_nu_expression(&expr) {
+                    result.push_str(&expr);
+                } else {
+                    result.push_str(&format!("({})", expr));
+                }
+                i += consumed;
+                continue;
+            }
+        }
+        let ch = rest.chars().next().unwrap_or_default();
+        if ch == '"' {
+            result.push_str("\\\"");
+        } else {
+            result.push(ch);
+        }
+        i += ch.len_utf8();
+    }
+
+    result
+}
+
+/// Escape the parentheses of text bound for a `$"..."` string so
+/// interpolation keeps them literal; `$(cmd)` substitutions become
+/// interpolated `(cmd)` and arithmetic expansions stay as they are
+pub fn escape_literal_parens(text: &str) -> String {
+    let mut result = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '$' if chars.peek() == Some(&'(') => {
+                // Arithmetic `$((expr))` is left for `interpolate_word` to convert
+                if chars.clone().nth(1) == Some('(') {
+                    result.push('$');
+                }
+                // Copy the substitution with its own parentheses balanced
+                let mut depth = 0;
+                for inner in chars.by_ref() {
+                    result.push(inner);
+                    match inner {
+                        '(' => depth += 1,
+                        ')' => depth -= 1,
+                        _ => {}
+                    }
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            }
+            '(' | ')' => {
+                result.push('\\');
+                result.push(ch);
+            }
+            ch => result.push(ch),
+        }
+    }
+    result
+}
+
+/// Check whether a word references the positional parameters (`$@`, `$*`, `$#`)
+pub fn references_positional_params(word: &str) -> bool {
+    !word.starts_with('\'') && (word.contains("$@") || word.contains("$*") || word.contains("$#"))
+}
+
+/// Check whether an argument is already a Nushell expression that must not be quoted
+///
+/// Variable references (`$name`, `$env.NAME`) and parenthesized subexpressions
+/// are emitted bare so Nushell evaluates them instead of treating them as text.
+pub fn is_nu_expression(arg: &str) -> bool {
+    if let Some(name) = arg.strip_prefix('$') {
+        // Only `$env.NAME` and `$nu.NAME` are cell paths; `$file.txt` is text after a variable
+        let name = name
+            .strip_prefix("env.")
+            .or_else(|| name.strip_prefix("nu."))
+            .unwrap_or(name);
+        return !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    }
+
+    if arg.starts_with('(') && arg.ends_with(')') {
+        let mut depth = 0i32;
+        for (i, ch) in arg.char_indices() {
+            match ch {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    // The outer parentheses must enclose the whole argument
+                    if depth == 0 && i != arg.len() - 1 {
+                        return false;
+                    }
+                }
+                _ => {}
+            }
+        }
+        return depth == 0;
+    }
+
+    false
+}
+
+/// Strip one level of surrounding double quotes from a word
+fn strip_double_quotes(word: &str) -> &str {
+    if word.len() >= 2 && word.starts_with('"') && word.ends_with('"') {
+        &word[1..word.len() - 1]
+    } else {
+        word
+    }
+}
+
+/// Parse an expansion at the start of `input` (which begins with `$`)
+///
+/// Returns the converted Nushell text and the number of bytes consumed, or
+/// `None` if the text is not an expansion this module handles.
+fn parse_expansion(input: &str) -> Option<(String, usize)> {
+    let rest = input.strip_prefix('$')?;
+    let next = rest.chars().next()?;
+
+    // $((expr)) - arithmetic expansion
+    if rest.starts_with("((") {
+        let close = find_closing_paren(rest)?;
+        let inner = rest[..=close].strip_prefix("((")?.strip_suffix("))")?;
+        return Some((format!("({})", arithmetic_expression(inner)), close + 2));
+    }
+
+    if next == '{' {
+        let close = find_closing_brace(rest)?;
+        return convert_braced(&rest[1..close]).map(|expr| (expr, close + 2));
+    }
+
+    // $name - plain variable reference
+    if next.is_ascii_alphabetic() || next == '_' {
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let name = &rest[..len];
+        return Some((convert_variable(name), 1 + len));
+    }
+
+    convert_special_parameter(next).map(|expr| (expr, 1 + next.len_utf8()))
+}
+
+/// Find the index of the `)` matching the `(` at the start of `input`
+fn find_closing_paren(input: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, ch) in input.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Convert a variable reference, mapping shell-maintained variables such as
+/// `RANDOM` to the Nushell expressions that produce their values
+fn convert_variable(name: &str) -> String {
+    match name {
+        "RANDOM" => "(random int 0..32767)".to_string(),
+        _ => format!("${}", name),
+    }
+}
+
+/// Convert a bash arithmetic expression such as `i>0&&i<n` into `$i > 0 and $i < $n`
+///
+/// Names are variables, whether or not they carry a `$`; `%` becomes `mod` and
+/// `/` integer division.
+pub fn arithmetic_expression(expression: &str) -> String {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut chars = expression.chars().peekable();
+    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+    while let Some(ch) = chars.next() {
+        if ch.is_whitespace() {
+            continue;
+        }
+        let mut token = ch.to_string();
+        // Parentheses are tokens of their own; other characters group into
+        // words or operators
+        if !matches!(ch, '(' | ')') {
+            while let Some(&next) = chars.peek() {
+                if next.is_whitespace() || matches!(next, '(' | ')') || is_word(ch) != is_word(next)
+                {
+                    break;
+                }
+                token.push(next);
+                chars.next();
+            }
+        }
+        tokens.push(match token.as_str() {
+            "&&" => "and".to_string(),
+            "||" => "or".to_string(),
+            "%" => "mod".to_string(),
+            "/" => "//".to_string(),
+            word if word.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') => {
+                convert_variable(word)
+            }
+            word if word.starts_with('$') && word.len() > 1 => convert_variable(&word[1..]),
+            _ => token,
+        });
+    }
+    tokens.join(" ").replace("( ", "(").replace(" )", ")")
+}
+
+/// Find the index of the `}` matching the `{` at the start of `input`
+fn find_closing_brace(input: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, ch) in input.char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Convert the contents of a `${...}` expansion
+fn convert_braced(inner: &str) -> Option<String> {
+    // ${#var} - string length, ${#arr[@]} - array length
+    if let Some(name) = inner.strip_prefix('#') {
+        if is_name(name) {
+            return Some(format!("(${} | str length)", name));
+        }
+        if let Some((array, "@" | "*")) = split_subscript(name) {
+            return Some(format!("(${} | length)", array));
+        }
+    }
+
+    // ${arr[@]} - all elements, ${arr[N]} - a single element
+    if let Some((array, index)) = split_subscript(inner) {
+        return match index {
+            "@" | "*" => Some(format!("${}", array)),
+            _ if index.parse::<usize>().is_ok() || is_nu_expression(&expand_word(index)) => {
+                Some(format!("(${} | get {})", array, expand_word(index)))
+            }
+            _ => None,
+        };
+    }
+
+    let name_len = inner
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(inner.len());
+    let (name, operation) = inner.split_at(name_len);
+    if name.is_empty() {
+        return None;
+    }
+
+    if operation.is_empty() {
+        return Some(format!("${}", name));
+    }
+
+    // Pattern substitution
+    if let Some(substitution) = operation.strip_prefix('/') {
+        return Some(convert_substitution(name, substitution));
+    }
+
+    // Default values; the colon forms also apply to empty values, which is
+    // what Nushell's `default` (null-only) approximates for unset variables.
+    // The assigning forms expand the same way, see [`default_assignments`]
+    let operation_body = operation.strip_prefix(':').unwrap_or(operation);
+    if let Some(word) = operation_body
+        .strip_prefix('-')
+        .or_else(|| operation_body.strip_prefix('='))
+    {
+        return Some(format!("(${} | default {})", name, convert_value(word)));
+    }
+    if let Some(message) = operation_body.strip_prefix('?') {
+        let message = if message.is_empty() {
+            "parameter null or not set".to_string()
+        } else {
+            strip_double_quotes(message).replace('"', "\\\"")
+        };
+        return Some(format!(
+            "(if (${} | is-empty) {{ error make {{ msg: \"{}: {}\" }} }} else {{ ${} }})",
+            name, name, message, name
+        ));
+    }
+
+    // Prefix and suffix removal, longest forms first
+    if let Some(pattern) = operation.strip_prefix("##") {
+        return Some(convert_prefix_removal(name, pattern, false));
+    }
+    if let Some(pattern) = operation.strip_prefix('#') {
+        return Some(convert_prefix_removal(name, pattern, true));
+    }
+    if let Some(pattern) = operation.strip_prefix("%%") {
+        return Some(convert_suffix_removal(name, pattern, false));
+    }
+    if let Some(pattern) = operation.strip_prefix('%') {
+        return Some(convert_suffix_removal(name, pattern, true));
+    }
+
+    None
+}
+
+/// Find the `${NAME:=WORD}` and `${NAME=WORD}` expansions in `word`, giving
+/// each variable they assign with its defaulted value
+///
+/// Nushell expressions can't assign, so the caller assigns these before
+/// the command the word belongs to.
+pub fn default_assignments(word: &str) -> Vec<(String, String)> {
+    let mut assignments = Vec::new();
+    let mut rest = word;
+    while let Some(start) = rest.find("${") {
+        let braced = &rest[start + 1..];
+        let Some(end) = find_closing_brace(braced) else {
+            break;
+        };
+        let inner = &braced[1..end];
+        let name_len = inner
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(inner.len());
+        let (name, operation) = inner.split_at(name_len);
+        let operation = operation.strip_prefix(':').unwrap_or(operation);
+        if let (false, Some(value)) = (name.is_empty(), operation.strip_prefix('=')) {
+            assignments.push((
+                name.to_string(),
+                format!("(${} | default {})", name, convert_value(value)),
+            ));
+        }
+        rest = &braced[end + 1..];
+    }
+    assignments
+}
+
+/// Convert the word of a default-value expansion to a Nushell value
+fn convert_value(word: &str) -> String {
+    let expanded = expand_word(word);
+    if is_nu_expression(&expanded) {
+        expanded
+    } else {
+        format!(
+            "\"{}\"",
+            strip_double_quotes(&expanded).replace('"', "\\\"")
+        )
+    }
+}
+
+/// Convert `${var/pat/repl}` substitution (the text after the first `/`)
+///
+/// A leading `/` replaces every match, while `#` and `%` anchor the pattern
+/// to the start or end of the value.
+fn convert_substitution(name: &str, substitution: &str) -> String {
+    let (global, substitution) = match substitution.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, substitution),
+    };
+    let (anchor, substitution) = match substitution.chars().next() {
+        Some(anchor @ ('#' | '%')) => (Some(anchor), &substitution[1..]),
+        _ => (None, substitution),
+    };
+
+    // The pattern ends at the first unescaped `/`
+    let mut split = None;
+    let mut escaped = false;
+    for (i, ch) in substitution.char_indices() {
+        match ch {
+            '\\' if !escaped => escaped = true,
+            '/' if !escaped => {
+                split = Some(i);
+                break;
+            }
+            _ => escaped = false,
+        }
+    }
+    let (pattern, replacement) = match split {
+        Some(i) => (&substitution[..i], &substitution[i + 1..]),
+        None => (substitution, ""),
+    };
+
+    let flags = if global { " --all" } else { "" };
+    let replacement = convert_value(replacement);
+
+    if anchor.is_none() && !pattern.contains(['*', '?', '[']) {
+        return format!(
+            "(${} | str replace{} \"{}\" {})",
+            name,
+            flags,
+            pattern.replace('\\', "").replace('"', "\\\""),
+            replacement
+        );
+    }
+
+    let regex = match anchor {
+        Some('#') => format!("^{}", glob_to_regex(pattern, false)),
+        Some('%') => format!("{}$", glob_to_regex(pattern, false)),
+        _ => glob_to_regex(pattern, false),
+    };
+    format!(
+        "(${} | str replace{} --regex '{}' {})",
+        name, flags, regex, replacement
+    )
+}
+
+/// Convert `${var#pat}` (shortest) and `${var##pat}` (longest) prefix removal
+fn convert_prefix_removal(name: &str, pattern: &str, shortest: bool) -> String {
+    if !shortest && pattern == "*/" {
+        // ${path##*/} is the shell idiom for basename
+        return format!("(${} | path basename)", name);
+    }
+
+    format!(
+        "(${} | str replace --regex '^{}' \"\")",
+        name,
+        glob_to_regex(pattern, shortest)
+    )
+}
+
+/// Convert `${var%pat}` (shortest) and `${var%%pat}` (longest) suffix removal
+fn convert_suffix_removal(name: &str, pattern: &str, shortest: bool) -> String {
+    if shortest && pattern == "/*" {
+        // ${path%/*} is the shell idiom for dirname
+        return format!("(${} | path dirname)", name);
+    }
+
+    // Regex matching is leftmost, so an anchored suffix match is always the
+    // longest one. For the shortest form, a `*` following a literal character
+    // is restricted so it cannot span another occurrence of that character.
+    let regex = match (shortest, pattern.chars().next()) {
+        (true, Some(first)) if pattern.len() == first.len_utf8() + 1 && pattern.ends_with('*') => {
+            format!(
+                "{}[^{}]*",
+                glob_to_regex(&first.to_string(), false),
+                glob_to_regex(&first.to_string(), false)
+            )
+        }
+        _ => glob_to_regex(pattern, shortest),
+    };
+
+    format!("(${} | str replace --regex '{}$' \"\")", name, regex)
+}
+
+/// Translate a shell glob pattern to an equivalent regular expression
+///
+/// When `lazy` is set, `*` becomes a non-greedy `.*?` so the pattern matches
+/// as little text as possible.
+pub fn glob_to_regex(pattern: &str, lazy: bool) -> String {
+    let mut regex = String::new();
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '*' => regex.push_str(if lazy { ".*?" } else { ".*" }),
+            '?' => regex.push('.'),
+            '[' => {
+                // Bracket expressions carry over, with `!` negation mapped to `^`
+                regex.push('[');
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    regex.push('^');
+                }
+                for inner in chars.by_ref() {
+                    regex.push(inner);
+                    if inner == ']' {
+                        break;
+                    }
+                }
+            }
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    regex.push_str(&regex_escape(escaped));
+                }
+            }
+            _ => regex.push_str(&regex_escape(ch)),
+        }
+    }
+
+    regex
+}
+
+/// Escape a character that has special meaning in a regular expression
+fn regex_escape(ch: char) -> String {
+    if ".+()|^$\\{}[]*?".contains(ch) {
+        format!("\\{}", ch)
+    } else {
+        ch.to_string()
+    }
+}
+
+/// Split `arr[index]` into the array name and its subscript
+fn split_subscript(text: &str) -> Option<(&str, &str)> {
+    let (array, index) = text.strip_suffix(']')?.split_once('[')?;
+    is_name(array).then_some((array, index))
+}
+
+/// Check whether `name` is a valid shell variable name
+fn is_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Convert a single-character special parameter
+fn convert_special_parameter(name: char) -> Option<String> {
+    match name {
+        // All positional parameters - bound to the rest parameter of the enclosing def
+        '@' | '*' => Some("$rest".to_string()),
+        // Number of positional parameters
+        '#' => Some("($rest | length)".to_string()),
+        // Exit status of the last command
+        '?' => Some("$env.LAST_EXIT_CODE".to_string()),
+        // Process ID of the shell
+        '$' => Some("$nu.pid".to_string()),
+        // ID of the most recent background job
+        '!' => Some("(job list | get id | last)".to_string()),
+        // Name of the script
+        '0' => Some("$env.CURRENT_FILE".to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_positional_parameters() {
+        // All positional parameters
+        assert_eq!(expand_word("$@"), "$rest");
+        assert_eq!(expand_word("\"$@\""), "$rest");
+        assert_eq!(expand_word("$*"), "$rest");
+
+        // Parameter count
+        assert_eq!(expand_word("$#"), "($rest | length)");
+        assert_eq!(expand_word("\"$#\""), "($rest | length)");
+
+        // Embedded in a larger word
+        assert_eq!(expand_word("count=$#"), "count=($rest | length)");
+
+        // Single quotes suppress expansion
+        assert_eq!(expand_word("'$@'"), "'$@'");
+
+        // Ordinary words are untouched
+        assert_eq!(expand_word("hello"), "hello");
+        assert_eq!(expand_word("$HOME"), "$HOME");
+    }
+
+    #[test]
+    fn test_expand_exit_status() {
+        assert_eq!(expand_word("$?"), "$env.LAST_EXIT_CODE");
+        assert_eq!(expand_word("\"$?\""), "$env.LAST_EXIT_CODE");
+        assert_eq!(expand_word("code=$?"), "code=$env.LAST_EXIT_CODE");
+    }
+
+    #[test]
+    fn test_expand_special_parameters() {
+        assert_eq!(expand_word("$$"), "$nu.pid");
+        assert_eq!(expand_word("$!"), "(job list | get id | last)");
+        assert_eq!(expand_word("$0"), "$env.CURRENT_FILE");
+        assert_eq!(expand_word("/tmp/lock.$$"), "/tmp/lock.$nu.pid");
+    }
+
+    #[test]
+    fn test_expand_string_length() {
+        assert_eq!(expand_word("${#name}"), "($name | str length)");
+        assert_eq!(expand_word("\"${#name}\""), "($name | str length)");
+
+        // Plain references
+        assert_eq!(expand_word("${name}"), "$name");
+        assert_eq!(expand_word("\"$name\""), "$name");
+    }
+
+    #[test]
+    fn test_expand_array_access() {
+        assert_eq!(expand_word("${arr[0]}"), "($arr | get 0)");
+        assert_eq!(expand_word("${arr[$i]}"), "($arr | get $i)");
+        assert_eq!(expand_word("${arr[@]}"), "$arr");
+        assert_eq!(expand_word("\"${arr[*]}\""), "$arr");
+        assert_eq!(expand_word("${#arr[@]}"), "($arr | length)");
+        assert_eq!(expand_word("count: ${#arr[*]}"), "count: ($arr | length)");
+    }
+
+    #[test]
+    fn test_expand_suffix_removal() {
+        assert_eq!(
+            expand_word("${file%.txt}"),
+            "($file | str replace --regex '\\.txt$' \"\")"
+        );
+        assert_eq!(
+            expand_word("${file%.*}"),
+            "($file | str replace --regex '\\.[^\\.]*$' \"\")"
+        );
+        assert_eq!(
+            expand_word("${file%%.*}"),
+            "($file | str replace --regex '\\..*$' \"\")"
+        );
+        assert_eq!(expand_word("${path%/*}"), "($path | path dirname)");
+    }
+
+    #[test]
+    fn test_expand_prefix_removal() {
+        assert_eq!(expand_word("${path##*/}"), "($path | path basename)");
+        assert_eq!(
+            expand_word("${path#*/}"),
+            "($path | str replace --regex '^.*?/' \"\")"
+        );
+        assert_eq!(
+            expand_word("${version#v}"),
+            "($version | str replace --regex '^v' \"\")"
+        );
+    }
+
+    #[test]
+    fn test_interpolate_word() {
+        assert_eq!(interpolate_word("home is $HOME"), "home is ($HOME)");
+        // Converted expressions are interpolated once
+        assert_eq!(interpolate_word("$# args"), "($rest | length) args");
+        assert_eq!(
+            interpolate_word("${PORT:-8080}/api"),
+            "($PORT | default \"8080\")/api"
+        );
+        assert_eq!(interpolate_word("all: $@"), "all: ($rest | str join ' ')");
+        // Literal quotes are escaped, escapes are kept
+        assert_eq!(interpolate_word("say \"hi\" \\$5"), "say \\\"hi\\\" \\$5");
+    }
+
+    #[test]
+    fn test_expand_default_values() {
+        // Use a default value
+        assert_eq!(expand_word("${PORT:-8080}"), "($PORT | default \"8080\")");
+        assert_eq!(
+            expand_word("\"${EDITOR:-$VISUAL}\""),
+            "($EDITOR | default $VISUAL)"
+        );
+
+        // Assign a default value; the assignment itself is separate
+        assert_eq!(expand_word("${MODE:=fast}"), "($MODE | default \"fast\")");
+        assert_eq!(
+            default_assignments("${MODE:=fast}-${LEVEL=3}${PORT:-80}"),
+            vec![
+                ("MODE".to_string(), "($MODE | default \"fast\")".to_string()),
+                ("LEVEL".to_string(), "($LEVEL | default \"3\")".to_string()),
+            ]
+        );
+        assert!(default_assignments("${#MODE} $MODE").is_empty());
+
+        // Error when unset
+        assert_eq!(
+            expand_word("${TARGET:?missing target}"),
+            "(if ($TARGET | is-empty) { error make { msg: \"TARGET: missing target\" } } else { $TARGET })"
+        );
+        assert_eq!(
+            expand_word("${TARGET:?}"),
+            "(if ($TARGET | is-empty) { error make { msg: \"TARGET: parameter null or not set\" } } else { $TARGET })"
+        );
+    }
+
+    #[test]
+    fn test_expand_substitution() {
+        // First occurrence
+        assert_eq!(
+            expand_word("${file/old/new}"),
+            "($file | str replace \"old\" \"new\")"
+        );
+
+        // Every occurrence
+        assert_eq!(
+            expand_word("${file//old/new}"),
+            "($file | str replace --all \"old\" \"new\")"
+        );
+
+        // Deletion when the replacement is omitted
+        assert_eq!(
+            expand_word("${file// /}"),
+            "($file | str replace --all \" \" \"\")"
+        );
+
+        // Glob patterns and anchors use a regex
+        assert_eq!(
+            expand_word("${file//[0-9]/#}"),
+            "($file | str replace --all --regex '[0-9]' \"#\")"
+        );
+        assert_eq!(
+            expand_word("${file/#./_}"),
+            "($file | str replace --regex '^\\.' \"_\")"
+        );
+    }
+
+    #[test]
+    fn test_expand_random_and_arithmetic() {
+        assert_eq!(expand_word("$RANDOM"), "(random int 0..32767)");
+        assert_eq!(
+            expand_word("$((RANDOM % 6 + 1))"),
+            "((random int 0..32767) mod 6 + 1)"
+        );
+        assert_eq!(expand_word("$(($count * 2))"), "($count * 2)");
+        assert_eq!(arithmetic_expression("(i+1)/2"), "($i + 1) // 2");
+    }
+
+    #[test]
+    fn test_glob_to_regex() {
+        assert_eq!(glob_to_regex("*.txt", false), ".*\\.txt");
+        assert_eq!(glob_to_regex("file?", false), "file.");
+        assert_eq!(glob_to_regex("[!0-9]*", true), "[^0-9].*?");
+    }
+
+    #[test]
+    fn test_is_nu_expression() {
+        assert!(is_nu_expression("$rest"));
+        assert!(is_nu_expression("$env.PATH"));
+        assert!(is_nu_expression("$nu.pid"));
+        assert!(is_nu_expression("($rest | length)"));
+
+        assert!(!is_nu_expression("$"));
+        assert!(!is_nu_expression("$file.txt"));
+        assert!(!is_nu_expression("file$var*.txt"));
+        assert!(!is_nu_expression("(a) (b)"));
+        assert!(!is_nu_expression("plain"));
+    }
+}
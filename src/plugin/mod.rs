@@ -1,6 +1,7 @@
 pub mod builtin;
 pub mod converter;
 pub mod core;
+pub mod expansion;
 pub mod parser_heuristic;
 pub mod parser_posix;
 pub mod sus;
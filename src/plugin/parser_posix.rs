@@ -5,6 +5,80 @@
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PosixScript {
     pub commands: Vec<PosixCommand>,
+    /// Interpreter named by the `#!` line, e.g. `sh` or `bash`
+    pub interpreter: Option<String>,
+}
+
+impl PosixScript {
+    /// Whether bash extensions such as `[[ ... ]]` are accepted
+    ///
+    /// Scripts without a shebang are parsed leniently.
+    pub fn allows_bash_extensions(&self) -> bool {
+        match self
+            .interpreter
+            .as_deref()
+            .and_then(Dialect::from_interpreter)
+        {
+            Some(dialect) => dialect.allows_bash_extensions(),
+            None => true,
+        }
+    }
+}
+
+/// Shell dialect whose syntax the parser accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Dialect {
+    /// Strict POSIX `sh`
+    Posix,
+    /// Bash, including `[[ ]]`, arrays and `local`
+    Bash,
+    /// Debian's `dash`: POSIX plus `local`
+    Dash,
+}
+
+impl Dialect {
+    /// Dialect implied by an interpreter name from a shebang line
+    pub fn from_interpreter(interpreter: &str) -> Option<Self> {
+        match interpreter {
+            "sh" | "ash" | "posh" => Some(Dialect::Posix),
+            "dash" => Some(Dialect::Dash),
+            "bash" | "zsh" | "ksh" => Some(Dialect::Bash),
+            _ => None,
+        }
+    }
+
+    /// Whether bash extensions such as `[[ ... ]]` are accepted
+    pub fn allows_bash_extensions(self) -> bool {
+        self == Dialect::Bash
+    }
+
+    /// Whether function-local variables declared with `local` are accepted
+    pub fn allows_local(self) -> bool {
+        self != Dialect::Posix
+    }
+}
+
+impl std::str::FromStr for Dialect {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        match name {
+            "posix" | "sh" => Ok(Dialect::Posix),
+            "bash" => Ok(Dialect::Bash),
+            "dash" => Ok(Dialect::Dash),
+            _ => anyhow::bail!("unknown dialect `{}`; expected posix, bash or dash", name),
+        }
+    }
+}
+
+impl std::fmt::Display for Dialect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Dialect::Posix => "posix",
+            Dialect::Bash => "bash",
+            Dialect::Dash => "dash",
+        })
+    }
 }
 
 /// Represents different types of POSIX commands
@@ -23,6 +97,8 @@ pub struct SimpleCommandData {
     pub args: Vec<String>,
     pub assignments: Vec<Assignment>,
     pub redirections: Vec<Redirection>,
+    /// Byte range of the command in the parsed source
+    pub span: (usize, usize),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +111,8 @@ pub struct PipelineData {
 pub struct CompoundCommandData {
     pub kind: CompoundCommandKind,
     pub redirections: Vec<Redirection>,
+    /// Byte range of the command in the parsed source
+    pub span: (usize, usize),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +124,13 @@ pub enum CompoundCommandKind {
         words: Vec<String>,
         body: Vec<PosixCommand>,
     },
+    /// Bash `for ((init; condition; update))` loop
+    ArithmeticFor {
+        init: String,
+        condition: String,
+        update: String,
+        body: Vec<PosixCommand>,
+    },
     While {
         condition: Vec<PosixCommand>,
         body: Vec<PosixCommand>,
@@ -83,6 +168,15 @@ pub struct ElifPart {
 pub struct CaseItemData {
     pub patterns: Vec<String>,
     pub body: Vec<PosixCommand>,
+    pub terminator: CaseTerminator,
+}
+
+/// How a case item ends: `;;`, `;&` (fall through) or `;;&` (test next pattern)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CaseTerminator {
+    Break,
+    Fallthrough,
+    Continue,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -114,6 +208,8 @@ pub enum ListSeparator {
 pub struct Assignment {
     pub name: String,
     pub value: String,
+    /// Set for `NAME+=VALUE` appends
+    pub append: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -130,7 +226,10 @@ pub enum RedirectionOp {
     Append,
     InputOutput,
     Clobber,
-    InputHereDoc,
+    /// `<<WORD`; a quoted `WORD` makes the body `literal`, without expansions
+    InputHereDoc {
+        literal: bool,
+    },
     InputHereString,
     OutputDup,
     InputDup,
@@ -139,6 +238,14 @@ pub enum RedirectionOp {
 /// Parse a POSIX shell script string into a structured representation
 /// This function will attempt to use yash-syntax for parsing, but fall back to simple parsing if needed
 pub fn parse_posix_script(input: &str) -> Result<PosixScript> {
+    parse_posix_script_with_dialect(input, None)
+}
+
+/// Parse a script as the given dialect, or as its shebang implies when `None`
+pub fn parse_posix_script_with_dialect(
+    input: &str,
+    dialect: Option<Dialect>,
+) -> Result<PosixScript> {
     // Try yash-syntax first
     match parse_with_yash_syntax(input) {
         Ok(script) => {
@@ -151,7 +258,7 @@ pub fn parse_posix_script(input: &str) -> Result<PosixScript> {
                 e
             );
             // Fall back to heuristic parser
-            parse_with_heuristic_parser(input)
+            parse_with_heuristic_parser(input, dialect)
         }
     }
 }
@@ -219,23 +326,451 @@ fn convert_yash_redirection(redir: &yash_syntax::syntax::Redirection) -> Result<
 */
 
 /// Heuristic parser implementation as fallback
-fn parse_with_heuristic_parser(input: &str) -> Result<PosixScript> {
-    let mut commands = Vec::new();
-
-    // Heuristic line-by-line parsing
-    for line in input.lines() {
+fn parse_with_heuristic_parser(input: &str, dialect: Option<Dialect>) -> Result<PosixScript> {
+    let mut script = PosixScript {
+        commands: Vec::new(),
+        interpreter: input.lines().next().and_then(parse_shebang),
+    };
+    // An explicit dialect overrides the shebang
+    let allows_bash = dialect.map_or(
+        script.allows_bash_extensions(),
+        Dialect::allows_bash_extensions,
+    );
+    let allows_local = dialect
+        .or_else(|| {
+            script
+                .interpreter
+                .as_deref()
+                .and_then(Dialect::from_interpreter)
+        })
+        .is_none_or(Dialect::allows_local);
+    let shell = match dialect {
+        Some(dialect) => dialect.to_string(),
+        None => script
+            .interpreter
+            .clone()
+            .unwrap_or_else(|| "sh".to_string()),
+    };
+
+    // Heuristic line-by-line parsing; lines stay slices of `input` so
+    // commands can record their byte offsets
+    let mut lines = input.lines().enumerate();
+    while let Some((number, line)) = lines.next() {
         let trimmed = line.trim();
         if !trimmed.is_empty() && !trimmed.starts_with('#') {
-            commands.push(parse_heuristic_command(trimmed));
+            if !allows_bash && trimmed.split_whitespace().any(|word| word == "[[") {
+                anyhow::bail!(
+                    "line {}: `[[` is a bash extension not supported by {}",
+                    number + 1,
+                    shell
+                );
+            }
+            for command_str in split_commands(trimmed) {
+                if !allows_local && command_str.split_whitespace().next() == Some("local") {
+                    anyhow::bail!(
+                        "line {}: `local` is not POSIX and not supported by {}",
+                        number + 1,
+                        shell
+                    );
+                }
+                let mut command = parse_heuristic_command(input, command_str);
+
+                // A here-document's body runs up to its delimiter line
+                if let PosixCommand::Simple(cmd) = &mut command {
+                    if let Some((delimiter, strip_tabs, literal)) = heredoc_delimiter(&cmd.args) {
+                        let body: Vec<&str> = lines
+                            .by_ref()
+                            .map(|(_, line)| {
+                                if strip_tabs {
+                                    line.trim_start_matches('\t')
+                                } else {
+                                    line
+                                }
+                            })
+                            .take_while(|line| *line != delimiter)
+                            .collect();
+                        attach_heredoc(cmd, body.join("\n"), literal);
+                    }
+                }
+                script.commands.push(command);
+            }
+        }
+    }
+
+    Ok(script)
+}
+
+/// Find the delimiter of a `<<WORD` or `<<-WORD` here-document in `args`
+///
+/// Returns the unquoted delimiter, whether leading tabs are stripped and
+/// whether the delimiter was quoted, which keeps the body literal.
+fn heredoc_delimiter(args: &[String]) -> Option<(String, bool, bool)> {
+    let position = args
+        .iter()
+        .position(|arg| arg.starts_with("<<") && !arg.starts_with("<<<"))?;
+    let operator = &args[position][2..];
+    let (word, strip_tabs) = match operator.strip_prefix('-') {
+        Some(word) => (word, true),
+        None => (operator, false),
+    };
+    let word = if word.is_empty() {
+        args.get(position + 1)?.as_str()
+    } else {
+        word
+    };
+    let unquoted = word.trim_matches(|c| c == '\'' || c == '"' || c == '\\');
+    Some((unquoted.to_string(), strip_tabs, unquoted != word))
+}
+
+/// Move the here-document operator and output redirections out of `cmd.args`
+fn attach_heredoc(cmd: &mut SimpleCommandData, body: String, literal: bool) {
+    let mut args = std::mem::take(&mut cmd.args).into_iter();
+    cmd.redirections.push(Redirection {
+        fd: None,
+        operator: RedirectionOp::InputHereDoc { literal },
+        target: body,
+    });
+
+    while let Some(arg) = args.next() {
+        let (operator, target) = if let Some(rest) = arg.strip_prefix("<<") {
+            // The delimiter word is separate when written `<< EOF`
+            if rest.is_empty() || rest == "-" {
+                args.next();
+            }
+            continue;
+        } else if let Some(target) = arg.strip_prefix(">>") {
+            (RedirectionOp::Append, target.to_string())
+        } else if let Some(target) = arg.strip_prefix('>') {
+            (RedirectionOp::Output, target.to_string())
+        } else {
+            cmd.args.push(arg);
+            continue;
+        };
+
+        let target = if target.is_empty() {
+            args.next().unwrap_or_default()
+        } else {
+            target
+        };
+        cmd.redirections.push(Redirection {
+            fd: None,
+            operator,
+            target,
+        });
+    }
+}
+
+/// Extract the interpreter name from a `#!` line
+///
+/// Handles both `#!/bin/bash` and `#!/usr/bin/env bash` forms.
+fn parse_shebang(line: &str) -> Option<String> {
+    let mut words = line.strip_prefix("#!")?.split_whitespace();
+    let program = words.next()?.rsplit('/').next()?;
+    let interpreter = if program == "env" {
+        words.find(|word| !word.starts_with('-'))?
+    } else {
+        program
+    };
+    Some(interpreter.to_string())
+}
+
+/// Byte range of `part`, which must be a slice of `source`
+fn span_of(source: &str, part: &str) -> (usize, usize) {
+    let start = part.as_ptr() as usize - source.as_ptr() as usize;
+    debug_assert!(start + part.len() <= source.len());
+    (start, start + part.len())
+}
+
+/// Split `input` on whitespace, keeping quoted strings and `$(...)` or
+/// backtick substitutions together as single words
+pub(crate) fn split_words(input: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut depth = 0usize;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if quote != Some('\'') => {
+                current.push(c);
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+                continue;
+            }
+            '\'' | '"' | '`' if quote.is_none() => quote = Some(c),
+            _ if quote == Some(c) => quote = None,
+            '(' if quote.is_none() && current.ends_with('$') => depth += 1,
+            '(' if quote.is_none() && depth > 0 => depth += 1,
+            ')' if quote.is_none() && depth > 0 => depth -= 1,
+            c if c.is_whitespace() && quote.is_none() && depth == 0 => {
+                if !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+/// Parse the `pattern) body ;;` items of a single-line case statement
+fn parse_case_items(source: &str, input: &str) -> Vec<CaseItemData> {
+    let mut items = Vec::new();
+    let mut rest = input.trim();
+
+    while !rest.is_empty() && rest != "esac" {
+        let Some(paren) = rest.find(')') else {
+            break;
+        };
+        let patterns = rest[..paren]
+            .trim()
+            .trim_start_matches('(')
+            .split('|')
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+        rest = &rest[paren + 1..];
+
+        // The body runs up to the earliest terminator, or `esac` for the last item
+        let terminator = [
+            (";;&", CaseTerminator::Continue),
+            (";;", CaseTerminator::Break),
+            (";&", CaseTerminator::Fallthrough),
+        ]
+        .into_iter()
+        .filter_map(|(token, kind)| rest.find(token).map(|pos| (pos, token, kind)))
+        .min_by_key(|(pos, token, _)| (*pos, std::cmp::Reverse(token.len())));
+        let (body, terminator) = match terminator {
+            Some((pos, token, kind)) => {
+                let body = &rest[..pos];
+                rest = rest[pos + token.len()..].trim();
+                (body, kind)
+            }
+            None => {
+                let body = rest.trim().strip_suffix("esac").unwrap_or(rest);
+                rest = "";
+                (body, CaseTerminator::Break)
+            }
+        };
+
+        let body = body.trim();
+        items.push(CaseItemData {
+            patterns,
+            body: if body.is_empty() {
+                vec![]
+            } else {
+                vec![parse_heuristic_command(source, body)]
+            },
+            terminator,
+        });
+    }
+
+    items
+}
+
+/// Rejoin whitespace-split `parts` of `input` so that a `$(...)` or backtick
+/// substitution containing spaces stays one word, with its original spacing
+fn join_substitutions<'a>(input: &'a str, parts: Vec<&'a str>) -> Vec<&'a str> {
+    let mut joined = Vec::new();
+    let mut start: Option<&str> = None;
+    let mut in_backticks = false;
+    let mut depth = 0usize;
+
+    for part in parts {
+        let mut chars = part.chars().peekable();
+        let mut previous = ' ';
+        while let Some(c) = chars.next() {
+            match c {
+                // Escaped characters, including nested `\``, don't open or close anything
+                '\\' => {
+                    chars.next();
+                }
+                '`' => in_backticks = !in_backticks,
+                '(' if previous == '$' || depth > 0 => depth += 1,
+                ')' if depth > 0 => depth -= 1,
+                _ => {}
+            }
+            previous = c;
+        }
+
+        let first = *start.get_or_insert(part);
+        if !in_backticks && depth == 0 {
+            let (from, _) = span_of(input, first);
+            let (_, to) = span_of(input, part);
+            joined.push(&input[from..to]);
+            start = None;
         }
     }
 
-    Ok(PosixScript { commands })
+    // An unterminated substitution keeps its remaining words together
+    if let Some(first) = start {
+        let (from, _) = span_of(input, first);
+        joined.push(&input[from..]);
+    }
+
+    joined
+}
+
+/// Find the `;`, `|`, `||` and `&&` operators of `input` that sit outside
+/// quoted text, substitutions and nested compound commands such as
+/// `if ...; then ...; fi`, with their byte offsets
+fn top_level_operators(input: &str) -> Vec<(usize, &'static str)> {
+    let mut operators = Vec::new();
+    let mut quote: Option<char> = None;
+    let mut depth = 0usize;
+    let mut nesting = 0usize;
+    let mut command_start = true;
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if quote != Some('\'') => {
+                chars.next();
+            }
+            '\'' | '"' | '`' if quote.is_none() => quote = Some(c),
+            _ if quote == Some(c) => quote = None,
+            _ if quote.is_some() => {}
+            '(' => depth += 1,
+            ')' => {
+                depth = depth.saturating_sub(1);
+                // As in `name() { ...; }`
+                command_start = true;
+            }
+            ';' | '|' | '&' => {
+                let operator = match (c, chars.peek().map(|&(_, next)| next)) {
+                    // `;;` and `;&` end case items rather than commands
+                    (';', Some(';' | '&')) => None,
+                    (';', _) => Some(";"),
+                    ('|', Some('|')) => Some("||"),
+                    ('|', _) => Some("|"),
+                    ('&', Some('&')) => Some("&&"),
+                    _ => None,
+                };
+                if let Some(operator) = operator {
+                    if depth == 0 && nesting == 0 {
+                        operators.push((i, operator));
+                    }
+                    if operator.len() == 2 {
+                        chars.next();
+                    }
+                }
+                command_start = true;
+            }
+            c if c.is_whitespace() => {}
+            _ => {
+                // Reserved words only open or close a block where a command starts
+                let mut end = i + c.len_utf8();
+                while let Some(&(j, next)) = chars.peek() {
+                    if next.is_whitespace() || ";|&()'\"`\\".contains(next) {
+                        break;
+                    }
+                    end = j + next.len_utf8();
+                    chars.next();
+                }
+                let word = &input[i..end];
+                if command_start {
+                    match word {
+                        "if" | "for" | "while" | "until" | "case" | "{" => nesting += 1,
+                        "fi" | "done" | "esac" | "}" => nesting = nesting.saturating_sub(1),
+                        _ => {}
+                    }
+                }
+                command_start = matches!(
+                    word,
+                    "if" | "then" | "else" | "elif" | "do" | "while" | "until" | "{" | "!" | "time"
+                );
+            }
+        }
+    }
+
+    operators
+}
+
+/// Split `input` at each of its top-level `operator`s, see [`top_level_operators`]
+fn split_top_level<'a>(input: &'a str, operator: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for (position, _) in top_level_operators(input)
+        .into_iter()
+        .filter(|&(_, found)| found == operator)
+    {
+        parts.push(input[start..position].trim());
+        start = position + operator.len();
+    }
+    parts.push(input[start..].trim());
+    parts
+}
+
+/// Split a `;`-separated command list, keeping quoted text, substitutions
+/// and nested compound commands together
+fn split_commands(input: &str) -> Vec<&str> {
+    split_top_level(input, ";")
+        .into_iter()
+        .filter(|command| !command.is_empty())
+        .collect()
+}
+
+/// Split the `BODY; done REDIRECTIONS` after a loop's `do` into its body and
+/// the redirections applying to the whole loop, as in `done < file`
+fn split_loop_end(rest: &str) -> (&str, Vec<Redirection>) {
+    let end = rest.rmatch_indices("done").map(|(i, _)| i).find(|&i| {
+        let before = rest[..i].chars().next_back();
+        let after = rest[i + 4..].chars().next();
+        before.is_none_or(|c| c.is_whitespace() || c == ';')
+            && after.is_none_or(|c| c.is_whitespace() || matches!(c, ';' | '<' | '>'))
+    });
+    let Some(end) = end else {
+        return (rest.trim().trim_end_matches(';'), vec![]);
+    };
+
+    let mut redirections = Vec::new();
+    let mut words = split_words(&rest[end + 4..]).into_iter();
+    while let Some(word) = words.next() {
+        let digits = word.chars().take_while(char::is_ascii_digit).count();
+        let (fd, redirection) = word.split_at(digits);
+        let (operator, target) = if let Some(target) = redirection.strip_prefix(">>") {
+            (RedirectionOp::Append, target)
+        } else if let Some(target) = redirection.strip_prefix('>') {
+            (RedirectionOp::Output, target)
+        } else if let Some(target) = redirection.strip_prefix('<') {
+            (RedirectionOp::Input, target)
+        } else {
+            continue;
+        };
+        let target = match target {
+            "" => words.next().unwrap_or_default(),
+            target => target.to_string(),
+        };
+        redirections.push(Redirection {
+            fd: fd.parse().ok(),
+            operator,
+            target,
+        });
+    }
+
+    (rest[..end].trim().trim_end_matches(';'), redirections)
+}
+
+/// Parse a `;`-separated command list, see [`split_commands`]
+fn parse_command_list(source: &str, input: &str) -> Vec<PosixCommand> {
+    split_commands(input)
+        .into_iter()
+        .map(|command| parse_heuristic_command(source, command))
+        .collect()
 }
 
-fn parse_heuristic_command(command_str: &str) -> PosixCommand {
+fn parse_heuristic_command(source: &str, command_str: &str) -> PosixCommand {
     // Heuristic command parsing
-    let parts: Vec<&str> = command_str.split_whitespace().collect();
+    let parts = join_substitutions(command_str, command_str.split_whitespace().collect());
 
     if parts.is_empty() {
         return PosixCommand::Simple(SimpleCommandData {
@@ -243,16 +778,36 @@ fn parse_heuristic_command(command_str: &str) -> PosixCommand {
             args: vec![],
             assignments: vec![],
             redirections: vec![],
+            span: span_of(source, command_str),
         });
     }
 
-    // Check for pipelines
-    if command_str.contains('|') && !command_str.contains("||") {
-        let pipeline_parts: Vec<&str> = command_str.split('|').collect();
+    // Case labels may contain `|`, so check for case before pipelines
+    if command_str.starts_with("case ") {
+        // Very basic case parsing
+        if let Some(in_pos) = command_str.find(" in") {
+            let word = command_str[5..in_pos].trim();
+
+            return PosixCommand::Compound(CompoundCommandData {
+                kind: CompoundCommandKind::Case {
+                    word: word.to_string(),
+                    items: parse_case_items(source, &command_str[in_pos + 3..]),
+                },
+                redirections: vec![],
+                span: span_of(source, command_str),
+            });
+        }
+    }
+
+    // Check for pipelines; a `|` within quotes or a substitution, as in
+    // `x=$(ls | wc -l)`, belongs to its word
+    let operators = top_level_operators(command_str);
+    let has_operator = |operator: &str| operators.iter().any(|&(_, found)| found == operator);
+    if has_operator("|") && !has_operator("||") {
         let mut commands = Vec::new();
 
-        for part in pipeline_parts {
-            commands.push(parse_heuristic_command(part.trim()));
+        for part in split_top_level(command_str, "|") {
+            commands.push(parse_heuristic_command(source, part));
         }
 
         return PosixCommand::Pipeline(PipelineData {
@@ -262,27 +817,25 @@ fn parse_heuristic_command(command_str: &str) -> PosixCommand {
     }
 
     // Check for && or ||
-    if command_str.contains("&&") || command_str.contains("||") {
-        let (left, op, right) = if command_str.contains("&&") {
-            let parts: Vec<&str> = command_str.splitn(2, "&&").collect();
-            (
-                parts[0].trim(),
-                AndOrOperator::And,
-                parts.get(1).unwrap_or(&"").trim(),
-            )
-        } else {
-            let parts: Vec<&str> = command_str.splitn(2, "||").collect();
-            (
-                parts[0].trim(),
-                AndOrOperator::Or,
-                parts.get(1).unwrap_or(&"").trim(),
-            )
-        };
+    let find = |operator: &str| {
+        operators
+            .iter()
+            .find(|&&(_, found)| found == operator)
+            .map(|&(position, _)| position)
+    };
+    let and_or = find("&&")
+        .map(|position| (position, AndOrOperator::And))
+        .or_else(|| find("||").map(|position| (position, AndOrOperator::Or)));
+    if let Some((position, op)) = and_or {
+        let (left, right) = (
+            command_str[..position].trim(),
+            command_str[position + 2..].trim(),
+        );
 
         return PosixCommand::AndOr(AndOrData {
-            left: Box::new(parse_heuristic_command(left)),
+            left: Box::new(parse_heuristic_command(source, left)),
             operator: op,
-            right: Box::new(parse_heuristic_command(right)),
+            right: Box::new(parse_heuristic_command(source, right)),
         });
     }
 
@@ -291,43 +844,65 @@ fn parse_heuristic_command(command_str: &str) -> PosixCommand {
         // Very basic if parsing
         let condition_and_body: Vec<&str> = command_str.splitn(2, " then ").collect();
         if condition_and_body.len() == 2 {
-            let condition = condition_and_body[0].strip_prefix("if ").unwrap_or("");
+            let condition = condition_and_body[0]
+                .strip_prefix("if ")
+                .unwrap_or("")
+                .trim_end_matches(';');
             let then_body = condition_and_body[1]
                 .strip_suffix(" fi")
-                .unwrap_or(condition_and_body[1]);
+                .unwrap_or(condition_and_body[1])
+                .trim()
+                .trim_end_matches(';');
 
             return PosixCommand::Compound(CompoundCommandData {
                 kind: CompoundCommandKind::If {
-                    condition: vec![parse_heuristic_command(condition)],
-                    then_body: vec![parse_heuristic_command(then_body)],
+                    condition: vec![parse_heuristic_command(source, condition)],
+                    then_body: parse_command_list(source, then_body),
                     elif_parts: vec![],
                     else_body: None,
                 },
                 redirections: vec![],
+                span: span_of(source, command_str),
             });
         }
     }
 
+    if let Some(header) = command_str.strip_prefix("for ((") {
+        if let (Some(close), Some(do_pos)) = (header.find("))"), header.find(" do ")) {
+            let clauses: Vec<&str> = header[..close].split(';').map(str::trim).collect();
+            if let [init, condition, update] = clauses.as_slice() {
+                let (body_part, redirections) = split_loop_end(&header[do_pos + 4..]);
+
+                return PosixCommand::Compound(CompoundCommandData {
+                    kind: CompoundCommandKind::ArithmeticFor {
+                        init: init.to_string(),
+                        condition: condition.to_string(),
+                        update: update.to_string(),
+                        body: parse_command_list(source, body_part),
+                    },
+                    redirections,
+                    span: span_of(source, command_str),
+                });
+            }
+        }
+    }
+
     if command_str.starts_with("for ") {
         // Very basic for loop parsing
         if let Some(in_pos) = command_str.find(" in ") {
             if let Some(do_pos) = command_str.find(" do ") {
                 let var_part = &command_str[4..in_pos];
-                let words_part = &command_str[in_pos + 4..do_pos];
-                let body_part = command_str[do_pos + 4..]
-                    .strip_suffix(" done")
-                    .unwrap_or(&command_str[do_pos + 4..]);
+                let words_part = command_str[in_pos + 4..do_pos].trim_end_matches(';');
+                let (body_part, redirections) = split_loop_end(&command_str[do_pos + 4..]);
 
                 return PosixCommand::Compound(CompoundCommandData {
                     kind: CompoundCommandKind::For {
                         variable: var_part.to_string(),
-                        words: words_part
-                            .split_whitespace()
-                            .map(|s| s.to_string())
-                            .collect(),
-                        body: vec![parse_heuristic_command(body_part)],
+                        words: split_words(words_part),
+                        body: parse_command_list(source, body_part),
                     },
-                    redirections: vec![],
+                    redirections,
+                    span: span_of(source, command_str),
                 });
             }
         }
@@ -336,17 +911,16 @@ fn parse_heuristic_command(command_str: &str) -> PosixCommand {
     if command_str.starts_with("while ") {
         // Very basic while loop parsing
         if let Some(do_pos) = command_str.find(" do ") {
-            let condition = command_str[6..do_pos].trim();
-            let body_part = command_str[do_pos + 4..]
-                .strip_suffix(" done")
-                .unwrap_or(&command_str[do_pos + 4..]);
+            let condition = command_str[6..do_pos].trim().trim_end_matches(';');
+            let (body_part, redirections) = split_loop_end(&command_str[do_pos + 4..]);
 
             return PosixCommand::Compound(CompoundCommandData {
                 kind: CompoundCommandKind::While {
-                    condition: vec![parse_heuristic_command(condition)],
-                    body: vec![parse_heuristic_command(body_part)],
+                    condition: vec![parse_heuristic_command(source, condition)],
+                    body: parse_command_list(source, body_part),
                 },
-                redirections: vec![],
+                redirections,
+                span: span_of(source, command_str),
             });
         }
     }
@@ -354,32 +928,16 @@ fn parse_heuristic_command(command_str: &str) -> PosixCommand {
     if command_str.starts_with("until ") {
         // Very basic until loop parsing
         if let Some(do_pos) = command_str.find(" do ") {
-            let condition = command_str[6..do_pos].trim();
-            let body_part = command_str[do_pos + 4..]
-                .strip_suffix(" done")
-                .unwrap_or(&command_str[do_pos + 4..]);
+            let condition = command_str[6..do_pos].trim().trim_end_matches(';');
+            let (body_part, redirections) = split_loop_end(&command_str[do_pos + 4..]);
 
             return PosixCommand::Compound(CompoundCommandData {
                 kind: CompoundCommandKind::Until {
-                    condition: vec![parse_heuristic_command(condition)],
-                    body: vec![parse_heuristic_command(body_part)],
+                    condition: vec![parse_heuristic_command(source, condition)],
+                    body: parse_command_list(source, body_part),
                 },
-                redirections: vec![],
-            });
-        }
-    }
-
-    if command_str.starts_with("case ") {
-        // Very basic case parsing
-        if let Some(in_pos) = command_str.find(" in") {
-            let word = command_str[5..in_pos].trim();
-
-            return PosixCommand::Compound(CompoundCommandData {
-                kind: CompoundCommandKind::Case {
-                    word: word.to_string(),
-                    items: vec![], // Simplified for now
-                },
-                redirections: vec![],
+                redirections,
+                span: span_of(source, command_str),
             });
         }
     }
@@ -388,8 +946,9 @@ fn parse_heuristic_command(command_str: &str) -> PosixCommand {
         // Basic brace group parsing
         let inner = &command_str[2..command_str.len() - 2];
         return PosixCommand::Compound(CompoundCommandData {
-            kind: CompoundCommandKind::BraceGroup(vec![parse_heuristic_command(inner)]),
+            kind: CompoundCommandKind::BraceGroup(parse_command_list(source, inner)),
             redirections: vec![],
+            span: span_of(source, command_str),
         });
     }
 
@@ -397,8 +956,9 @@ fn parse_heuristic_command(command_str: &str) -> PosixCommand {
         // Basic subshell parsing
         let inner = &command_str[2..command_str.len() - 2];
         return PosixCommand::Compound(CompoundCommandData {
-            kind: CompoundCommandKind::Subshell(vec![parse_heuristic_command(inner)]),
+            kind: CompoundCommandKind::Subshell(parse_command_list(source, inner)),
             redirections: vec![],
+            span: span_of(source, command_str),
         });
     }
 
@@ -410,42 +970,104 @@ fn parse_heuristic_command(command_str: &str) -> PosixCommand {
                 expression: expression.to_string(),
             },
             redirections: vec![],
+            span: span_of(source, command_str),
         });
     }
 
     // Parse variable assignments
     let mut assignments = Vec::new();
     let mut command_parts = Vec::new();
+    let mut redirections = Vec::new();
     let mut found_command = false;
 
-    for part in parts {
+    let mut parts = parts.into_iter();
+    while let Some(part) = parts.next() {
         if !found_command && part.contains('=') && !part.starts_with('-') {
             let assignment_parts: Vec<&str> = part.splitn(2, '=').collect();
             if assignment_parts.len() == 2 {
+                let (name, append) = match assignment_parts[0].strip_suffix('+') {
+                    Some(name) => (name, true),
+                    None => (assignment_parts[0], false),
+                };
+                let mut value = assignment_parts[1].to_string();
+
+                // Array literals `arr=(a b c)` span several words
+                if value.starts_with('(') {
+                    while !value.ends_with(')') {
+                        match parts.next() {
+                            Some(next) => {
+                                value.push(' ');
+                                value.push_str(next);
+                            }
+                            None => break,
+                        }
+                    }
+                }
+
                 assignments.push(Assignment {
-                    name: assignment_parts[0].to_string(),
-                    value: assignment_parts[1].to_string(),
+                    name: name.to_string(),
+                    value,
+                    append,
                 });
                 continue;
             }
         }
         found_command = true;
-        command_parts.push(part);
+
+        // Here-strings `<<< word` feed the word to the command's input
+        if let Some(target) = part.strip_prefix("<<<") {
+            let mut target = match target {
+                "" => parts.next().unwrap_or_default().to_string(),
+                target => target.to_string(),
+            };
+            // A quoted here-string may span several words
+            for quote in ['"', '\''] {
+                if target.starts_with(quote) {
+                    while target.len() < 2 || !target.ends_with(quote) {
+                        match parts.next() {
+                            Some(next) => {
+                                target.push(' ');
+                                target.push_str(next);
+                            }
+                            None => break,
+                        }
+                    }
+                }
+            }
+            redirections.push(Redirection {
+                fd: None,
+                operator: RedirectionOp::InputHereString,
+                target,
+            });
+            continue;
+        }
+
+        // Process substitutions `<(cmd args)` span several words
+        let mut word = part.to_string();
+        if word.starts_with("<(") || word.starts_with(">(") {
+            while word.matches('(').count() > word.matches(')').count() {
+                match parts.next() {
+                    Some(next) => {
+                        word.push(' ');
+                        word.push_str(next);
+                    }
+                    None => break,
+                }
+            }
+        }
+        command_parts.push(word);
     }
 
     // Heuristic command
-    let name = command_parts.first().unwrap_or(&"").to_string();
-    let args = command_parts
-        .iter()
-        .skip(1)
-        .map(|s| s.to_string())
-        .collect();
+    let name = command_parts.first().cloned().unwrap_or_default();
+    let args = command_parts.into_iter().skip(1).collect();
 
     PosixCommand::Simple(SimpleCommandData {
         name,
         args,
         assignments,
-        redirections: vec![],
+        redirections,
+        span: span_of(source, command_str),
     })
 }
 
@@ -481,6 +1103,16 @@ fn test_parse_pipeline() {
             }
             _ => panic!("Expected pipeline command"),
         }
+
+        // Pipes within substitutions and quotes don't split the command
+        for input in ["x=$(ls | wc -l)", "count=`ls | wc -l`", "echo \"a|b\""] {
+            let result = parse_posix_script(input).unwrap();
+            assert!(
+                matches!(result.commands[0], PosixCommand::Simple(_)),
+                "{}",
+                input
+            );
+        }
     }
 
     #[test]
@@ -515,6 +1147,173 @@ fn test_parse_assignment() {
         }
     }
 
+    #[test]
+    fn test_parse_array_assignment() {
+        let result = parse_posix_script("arr=(a b c)\narr+=(d)").unwrap();
+        assert_eq!(result.commands.len(), 2);
+
+        match &result.commands[0] {
+            PosixCommand::Simple(cmd) => {
+                assert_eq!(cmd.name, "");
+                assert_eq!(cmd.assignments[0].name, "arr");
+                assert_eq!(cmd.assignments[0].value, "(a b c)");
+                assert!(!cmd.assignments[0].append);
+            }
+            _ => panic!("Expected simple command"),
+        }
+
+        match &result.commands[1] {
+            PosixCommand::Simple(cmd) => {
+                assert_eq!(cmd.assignments[0].name, "arr");
+                assert_eq!(cmd.assignments[0].value, "(d)");
+                assert!(cmd.assignments[0].append);
+            }
+            _ => panic!("Expected simple command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_case_terminators() {
+        let input = "case $x in a|b) echo ab ;; c) echo c ;& d) echo d ;;& *) echo other ;; esac";
+        let result = parse_posix_script(input).unwrap();
+
+        match &result.commands[0] {
+            PosixCommand::Compound(cmd) => match &cmd.kind {
+                CompoundCommandKind::Case { word, items } => {
+                    assert_eq!(word, "$x");
+                    assert_eq!(items.len(), 4);
+                    assert_eq!(items[0].patterns, vec!["a", "b"]);
+                    assert_eq!(items[0].terminator, CaseTerminator::Break);
+                    assert_eq!(items[1].terminator, CaseTerminator::Fallthrough);
+                    assert_eq!(items[2].terminator, CaseTerminator::Continue);
+                    assert_eq!(items[3].patterns, vec!["*"]);
+                    assert!(!items[3].body.is_empty());
+                }
+                _ => panic!("Expected case command"),
+            },
+            _ => panic!("Expected compound command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_command_spans() {
+        let input = "echo a\n  echo b";
+        let result = parse_posix_script(input).unwrap();
+
+        match &result.commands[1] {
+            PosixCommand::Simple(cmd) => {
+                assert_eq!(cmd.span, (9, 15));
+                assert_eq!(&input[cmd.span.0..cmd.span.1], "echo b");
+            }
+            _ => panic!("Expected simple command"),
+        }
+
+        // Nested commands point into the same source
+        let input = "ls\nls | grep test";
+        let result = parse_posix_script(input).unwrap();
+        match &result.commands[1] {
+            PosixCommand::Pipeline(pipe) => match &pipe.commands[1] {
+                PosixCommand::Simple(cmd) => {
+                    assert_eq!(&input[cmd.span.0..cmd.span.1], "grep test");
+                }
+                _ => panic!("Expected simple command"),
+            },
+            _ => panic!("Expected pipeline command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_process_substitution() {
+        let result = parse_posix_script("diff <(sort a) <(sort -r b)").unwrap();
+
+        match &result.commands[0] {
+            PosixCommand::Simple(cmd) => {
+                assert_eq!(cmd.name, "diff");
+                assert_eq!(cmd.args, vec!["<(sort a)", "<(sort -r b)"]);
+            }
+            _ => panic!("Expected simple command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_shebang() {
+        let result = parse_posix_script("#!/bin/bash\necho hi").unwrap();
+        assert_eq!(result.interpreter.as_deref(), Some("bash"));
+        assert_eq!(result.commands.len(), 1);
+
+        let result = parse_posix_script("#!/usr/bin/env sh\necho hi").unwrap();
+        assert_eq!(result.interpreter.as_deref(), Some("sh"));
+
+        let result = parse_posix_script("echo hi").unwrap();
+        assert_eq!(result.interpreter, None);
+    }
+
+    #[test]
+    fn test_shebang_controls_bash_extensions() {
+        let body = "[[ -f file ]] && echo yes";
+
+        assert!(parse_posix_script(&format!("#!/bin/bash\n{}", body)).is_ok());
+        assert!(parse_posix_script(body).is_ok());
+
+        let err = parse_posix_script(&format!("#!/bin/sh\n{}", body)).unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn test_dialect_controls_bash_extensions() {
+        let body = "[[ -f file ]] && echo yes";
+
+        assert!(parse_posix_script_with_dialect(body, Some(Dialect::Bash)).is_ok());
+
+        let err = parse_posix_script_with_dialect(body, Some(Dialect::Posix)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1: `[[` is a bash extension not supported by posix"
+        );
+        assert!(parse_posix_script_with_dialect(body, Some(Dialect::Dash)).is_err());
+
+        // Dash adds `local` to POSIX
+        let body = "local count=0";
+        assert!(parse_posix_script_with_dialect(body, Some(Dialect::Dash)).is_ok());
+        let err = parse_posix_script_with_dialect(body, Some(Dialect::Posix)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1: `local` is not POSIX and not supported by posix"
+        );
+        let err = parse_posix_script(&format!("#!/bin/sh\n{}", body)).unwrap_err();
+        assert!(err.to_string().ends_with("supported by sh"));
+        assert!(parse_posix_script(&format!("#!/bin/dash\n{}", body)).is_ok());
+
+        // An explicit dialect overrides the shebang
+        let script = format!("#!/bin/sh\n{}", body);
+        assert!(parse_posix_script_with_dialect(&script, Some(Dialect::Bash)).is_ok());
+
+        assert_eq!("bash".parse::<Dialect>().unwrap(), Dialect::Bash);
+        assert!("fish".parse::<Dialect>().is_err());
+    }
+
+    #[test]
+    fn test_parse_command_lists() {
+        let result = parse_posix_script("x=1; x=2; echo $x").unwrap();
+        assert_eq!(result.commands.len(), 3);
+
+        // Compound commands and quoted `;` stay whole, their bodies split
+        let result =
+            parse_posix_script("if true; then x=1; x=2; fi; echo \"a; b\"; time { ls; }").unwrap();
+        assert_eq!(result.commands.len(), 3);
+        match &result.commands[0] {
+            PosixCommand::Compound(CompoundCommandData {
+                kind: CompoundCommandKind::If { then_body, .. },
+                ..
+            }) => assert_eq!(then_body.len(), 2),
+            _ => panic!("Expected if command"),
+        }
+        match &result.commands[1] {
+            PosixCommand::Simple(cmd) => assert_eq!(cmd.args.join(" "), "\"a; b\""),
+            _ => panic!("Expected simple command"),
+        }
+    }
+
     #[test]
     fn test_parse_empty_input() {
         let input = "";
@@ -582,6 +1381,99 @@ fn test_parse_for_loop() {
         }
     }
 
+    #[test]
+    fn test_parse_arithmetic_for_loop() {
+        let input = "for ((i=0; i<10; i++)); do echo $i; done";
+        let result = parse_posix_script(input).unwrap();
+
+        match &result.commands[0] {
+            PosixCommand::Compound(cmd) => match &cmd.kind {
+                CompoundCommandKind::ArithmeticFor {
+                    init,
+                    condition,
+                    update,
+                    body,
+                } => {
+                    assert_eq!(init, "i=0");
+                    assert_eq!(condition, "i<10");
+                    assert_eq!(update, "i++");
+                    assert_eq!(body.len(), 1);
+                }
+                _ => panic!("Expected arithmetic for command"),
+            },
+            _ => panic!("Expected compound command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_for_loop_command_substitution() {
+        let input = "for i in $(seq 1 10); do echo $i; done";
+        let result = parse_posix_script(input).unwrap();
+
+        match &result.commands[0] {
+            PosixCommand::Compound(cmd) => match &cmd.kind {
+                CompoundCommandKind::For { words, .. } => {
+                    assert_eq!(words, &vec!["$(seq 1 10)"]);
+                }
+                _ => panic!("Expected for command"),
+            },
+            _ => panic!("Expected compound command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_backtick_substitution() {
+        let result = parse_posix_script("echo `date +%s` done").unwrap();
+        match &result.commands[0] {
+            PosixCommand::Simple(cmd) => assert_eq!(cmd.args, vec!["`date +%s`", "done"]),
+            _ => panic!("Expected simple command"),
+        }
+
+        // Escaped backticks nest inside the outer substitution
+        let result = parse_posix_script("echo `echo \\`date\\``").unwrap();
+        match &result.commands[0] {
+            PosixCommand::Simple(cmd) => assert_eq!(cmd.args, vec!["`echo \\`date\\``"]),
+            _ => panic!("Expected simple command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_here_string() {
+        let result = parse_posix_script("grep foo <<< \"$bar\"").unwrap();
+        match &result.commands[0] {
+            PosixCommand::Simple(cmd) => {
+                assert_eq!(cmd.args, vec!["foo"]);
+                assert_eq!(cmd.redirections.len(), 1);
+                assert!(matches!(
+                    cmd.redirections[0].operator,
+                    RedirectionOp::InputHereString
+                ));
+                assert_eq!(cmd.redirections[0].target, "\"$bar\"");
+            }
+            _ => panic!("Expected simple command"),
+        }
+
+        // Quoted here-strings keep their spaces
+        let result = parse_posix_script("wc -w <<<'a b c'").unwrap();
+        match &result.commands[0] {
+            PosixCommand::Simple(cmd) => {
+                assert_eq!(cmd.args, vec!["-w"]);
+                assert_eq!(cmd.redirections[0].target, "'a b c'");
+            }
+            _ => panic!("Expected simple command"),
+        }
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(split_words("a b  c"), vec!["a", "b", "c"]);
+        assert_eq!(split_words("\"a b\" 'c d'"), vec!["\"a b\"", "'c d'"]);
+        assert_eq!(
+            split_words("$(ls $(pwd)) `date +%s` x"),
+            vec!["$(ls $(pwd))", "`date +%s`", "x"]
+        );
+    }
+
     #[test]
     fn test_parse_while_loop() {
         let input = "while true do echo running done";
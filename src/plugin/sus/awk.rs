@@ -1,6 +1,11 @@
 //! AWK command converter
 //!
 //! Converts POSIX `awk` commands to Nushell external command calls
+//!
+//! Simple `pattern { print ... }` programs that filter rows by a regex, a
+//! field comparison or the line number (`NR`) are translated to Nushell
+//! pipelines instead, as is `{print NF}`. A `BEGIN` block may set the input
+//! and output field separators `FS` and `OFS`.
 
 use super::{BaseConverter, CommandConverter};
 use anyhow::Result;
@@ -16,6 +21,10 @@ fn convert(&self, args: &[String]) -> Result<String> {
             return Ok("^awk".to_string());
         }
 
+        if let Some(pipeline) = convert_filter(args) {
+            return Ok(pipeline);
+        }
+
         // AWK is complex enough that we'll just run it as an external command
         // with proper argument handling
         let mut result = String::from("^awk");
@@ -38,6 +47,285 @@ fn description(&self) -> &'static str {
     }
 }
 
+/// Which lines a `pattern { action }` program selects
+enum Pattern {
+    /// `/regex/`
+    Regex(String),
+    /// `NR op N`, comparing the 1-based line number
+    Record {
+        operator: &'static str,
+        value: usize,
+    },
+    /// `$N op value`
+    Field {
+        field: usize,
+        operator: &'static str,
+        value: String,
+    },
+}
+
+/// What a program prints for each selected line
+enum Action {
+    /// `print` or `print $0`
+    Line,
+    /// `print $N, $M`
+    Fields(Vec<usize>),
+    /// `print NF`
+    FieldCount,
+    /// `$1=$1; print`, which rejoins the fields with `OFS`
+    Rebuild,
+}
+
+/// Convert a filtering program such as `$3 > 100 {print $1}` to a `where` pipeline
+///
+/// Returns `None` for anything else, which then runs as external awk.
+fn convert_filter(args: &[String]) -> Option<String> {
+    let mut separator = None;
+    let mut operands = Vec::new();
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-F" => {
+                separator = Some(args.get(i + 1)?.clone());
+                i += 2;
+            }
+            arg if arg.starts_with("-F") => {
+                separator = Some(arg[2..].to_string());
+                i += 1;
+            }
+            arg if arg.starts_with('-') => return None,
+            arg => {
+                operands.push(arg);
+                i += 1;
+            }
+        }
+    }
+
+    // A quoted program with spaces arrives split into words
+    let mut operands = operands.into_iter();
+    let mut program = operands.next()?.to_string();
+    if program.starts_with('\'') {
+        while program.len() < 2 || !program.ends_with('\'') {
+            program.push(' ');
+            program.push_str(operands.next()?);
+        }
+    }
+    let files: Vec<&str> = operands.collect();
+    let program = program
+        .strip_prefix('\'')
+        .and_then(|p| p.strip_suffix('\''))
+        .unwrap_or(&program)
+        .trim();
+
+    let (program, begin) = match program.strip_prefix("BEGIN") {
+        Some(rest) => {
+            let rest = rest.trim_start().strip_prefix('{')?;
+            let end = rest.find('}')?;
+            let separators = parse_begin(&rest[..end])?;
+            (rest[end + 1..].trim(), Some(separators))
+        }
+        None => (program, None),
+    };
+    let (input_separator, output_separator) = begin.clone().unwrap_or_default();
+    let separator = separator.or(input_separator);
+    let output_separator = output_separator.unwrap_or_else(|| " ".to_string());
+    let (pattern, action) = parse_program(program)?;
+
+    let mut stages = match files.as_slice() {
+        [] => vec!["lines".to_string()],
+        [file] => vec![
+            format!("open --raw {}", BaseConverter.quote_arg(file)),
+            "lines".to_string(),
+        ],
+        _ => return None,
+    };
+    let (split_column, split_row) = match &separator {
+        Some(separator) => (
+            format!("split column {}", nu_string(separator)),
+            format!("split row {}", nu_string(separator)),
+        ),
+        None => (
+            "str trim | split column -r '\\s+'".to_string(),
+            "str trim | split row -r '\\s+'".to_string(),
+        ),
+    };
+
+    let mut split = false;
+    match pattern {
+        // Without a pattern only per-line counts, or programs reformatting
+        // fields as set up in BEGIN, are worth translating
+        None if begin.is_none() && !matches!(action, Action::FieldCount) => return None,
+        None => {}
+        Some(Pattern::Regex(regex)) => stages.push(format!("where $it =~ '{}'", regex)),
+        Some(Pattern::Record { operator, value }) => {
+            stages.push(record_filter(operator, value));
+        }
+        Some(Pattern::Field {
+            field,
+            operator,
+            value,
+        }) => {
+            // Printing whole lines needs them unsplit
+            if let Action::Line | Action::Rebuild = action {
+                return None;
+            }
+            stages.push(split_column.clone());
+            split = true;
+            let column = format!("$it.column{}", field);
+            stages.push(if value.parse::<f64>().is_ok() {
+                format!("where ({} | into float) {} {}", column, operator, value)
+            } else {
+                format!(
+                    "where {} {} '{}'",
+                    column,
+                    operator,
+                    value.trim_matches('"')
+                )
+            });
+        }
+    }
+
+    match action {
+        Action::Line => {}
+        Action::Fields(fields) => {
+            if !split {
+                stages.push(split_column);
+            }
+            if let [field] = fields.as_slice() {
+                stages.push(format!("get column{}", field));
+            } else {
+                let columns: Vec<String> = fields
+                    .iter()
+                    .map(|field| format!("($row.column{})", field))
+                    .collect();
+                stages.push(format!(
+                    "each {{ |row| $\"{}\" }}",
+                    columns.join(&output_separator.replace('(', "\\("))
+                ));
+            }
+        }
+        Action::FieldCount if split => {
+            stages.push("each { |row| $row | values | length }".to_string())
+        }
+        Action::FieldCount => {
+            stages.push(format!("each {{ |line| $line | {} | length }}", split_row))
+        }
+        Action::Rebuild => stages.push(format!(
+            "each {{ |line| $line | {} | str join {} }}",
+            split_row,
+            nu_string(&output_separator)
+        )),
+    }
+
+    Some(stages.join(" | "))
+}
+
+/// Split a program into its pattern, if any, and its action
+fn parse_program(program: &str) -> Option<(Option<Pattern>, Action)> {
+    let (pattern, action) = match program.find('{') {
+        Some(brace) => (
+            program[..brace].trim(),
+            program[brace + 1..].strip_suffix('}')?.trim(),
+        ),
+        None => (program, "print"),
+    };
+
+    let pattern = if pattern.is_empty() {
+        None
+    } else if let Some(regex) = pattern.strip_prefix('/').and_then(|p| p.strip_suffix('/')) {
+        Some(Pattern::Regex(regex.to_string()))
+    } else if let Some(rest) = pattern.strip_prefix("NR") {
+        let (operator, value) = split_comparison(rest)?;
+        Some(Pattern::Record {
+            operator,
+            value: value.parse().ok()?,
+        })
+    } else {
+        let field: String = pattern
+            .strip_prefix('$')?
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        let (operator, value) = split_comparison(&pattern[1 + field.len()..])?;
+        Some(Pattern::Field {
+            field: field.parse().ok().filter(|&field| field > 0)?,
+            operator,
+            value: value.to_string(),
+        })
+    };
+
+    let action = match action {
+        "" | "print" | "print $0" => Action::Line,
+        "print NF" => Action::FieldCount,
+        "$1=$1; print" | "$1=$1;print" | "$1 = $1; print" => Action::Rebuild,
+        _ => Action::Fields(
+            action
+                .strip_prefix("print ")?
+                .split(',')
+                .map(|field| field.trim().strip_prefix('$')?.parse().ok())
+                .collect::<Option<Vec<usize>>>()?,
+        ),
+    };
+
+    Some((pattern, action))
+}
+
+/// Read the `FS` and `OFS` assignments of a `BEGIN` block such as `FS=OFS=","`
+///
+/// Returns `None` if the block does anything else.
+fn parse_begin(block: &str) -> Option<(Option<String>, Option<String>)> {
+    let mut input = None;
+    let mut output = None;
+    for statement in block.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+        let mut parts: Vec<&str> = statement.split('=').map(str::trim).collect();
+        let value = parts.pop()?;
+        let value = value.strip_prefix('"')?.strip_suffix('"')?.to_string();
+        if parts.is_empty() {
+            return None;
+        }
+        for name in parts {
+            match name {
+                "FS" => input = Some(value.clone()),
+                "OFS" => output = Some(value.clone()),
+                _ => return None,
+            }
+        }
+    }
+    Some((input, output))
+}
+
+/// Quote a separator as a Nushell string, keeping escapes such as `\t`
+fn nu_string(value: &str) -> String {
+    if value.contains('\\') {
+        format!("\"{}\"", value)
+    } else {
+        format!("'{}'", value)
+    }
+}
+
+/// Split ` > 100` into its comparison operator and operand
+fn split_comparison(comparison: &str) -> Option<(&'static str, &str)> {
+    let comparison = comparison.trim_start();
+    let operator = ["==", "!=", ">=", "<=", ">", "<"]
+        .into_iter()
+        .find(|op| comparison.starts_with(op))?;
+    Some((operator, comparison[operator.len()..].trim()))
+}
+
+/// Select lines by their 1-based number, as `NR op N` does
+fn record_filter(operator: &str, value: usize) -> String {
+    let index = value.saturating_sub(1);
+    match operator {
+        "==" if index == 0 => "first 1".to_string(),
+        "==" => format!("skip {} | first 1", index),
+        ">" => format!("skip {}", value),
+        ">=" => format!("skip {}", index),
+        "<" => format!("first {}", index),
+        "<=" => format!("first {}", value),
+        _ => format!("enumerate | where $it.index != {} | get item", index),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,7 +418,7 @@ fn test_awk_complex_patterns() {
             converter
                 .convert(&["/pattern/ { print $0 }".to_string()])
                 .unwrap(),
-            "^awk \"/pattern/ { print $0 }\""
+            "lines | where $it =~ 'pattern'"
         );
 
         // Multiple patterns
@@ -141,7 +429,7 @@ fn test_awk_complex_patterns() {
                     "/etc/passwd".to_string()
                 ])
                 .unwrap(),
-            "^awk \"BEGIN { FS=\\\":\\\" } /root/ { print $1 }\" /etc/passwd"
+            "open --raw /etc/passwd | lines | where $it =~ 'root' | split column ':' | get column1"
         );
 
         // AWK with regex containing special characters
@@ -149,7 +437,122 @@ fn test_awk_complex_patterns() {
             converter
                 .convert(&["/^[a-z]+$/ { print }".to_string()])
                 .unwrap(),
-            "^awk \"/^[a-z]+$/ { print }\""
+            "lines | where $it =~ '^[a-z]+$'"
+        );
+    }
+
+    #[test]
+    fn test_awk_filters() {
+        let converter = AwkConverter;
+
+        // Numeric field condition printing a field
+        assert_eq!(
+            converter
+                .convert(&["$3 > 100 {print $1}".to_string()])
+                .unwrap(),
+            "lines | str trim | split column -r '\\s+' | where ($it.column3 | into float) > 100 | get column1"
+        );
+
+        // Regex pattern printing whole lines, from a file
+        assert_eq!(
+            converter
+                .convert(&["/foo/ {print}".to_string(), "log.txt".to_string()])
+                .unwrap(),
+            "open --raw log.txt | lines | where $it =~ 'foo'"
+        );
+
+        // String comparison with a field separator, printing several fields
+        assert_eq!(
+            converter
+                .convert(&[
+                    "-F:".to_string(),
+                    "$7 == \"/bin/sh\" {print $1, $6}".to_string()
+                ])
+                .unwrap(),
+            "lines | split column ':' | where $it.column7 == '/bin/sh' | each { |row| $\"($row.column1) ($row.column6)\" }"
+        );
+
+        // Programs without a pattern otherwise stay external
+        assert!(converter
+            .convert(&["{print $1}".to_string()])
+            .unwrap()
+            .starts_with("^awk"));
+
+        // Printing whole lines selected by a field stays external
+        assert!(converter
+            .convert(&["$3 > 100".to_string()])
+            .unwrap()
+            .starts_with("^awk"));
+    }
+
+    #[test]
+    fn test_awk_begin_separators() {
+        let converter = AwkConverter;
+        let args = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            converter
+                .convert(&args(&["BEGIN{FS=OFS=\",\"} {print $2, $1}", "data.csv"]))
+                .unwrap(),
+            "open --raw data.csv | lines | split column ',' | each { |row| $\"($row.column2),($row.column1)\" }"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&["BEGIN { FS=\",\"; OFS=\"\\t\" } { $1=$1; print }"]))
+                .unwrap(),
+            "lines | each { |line| $line | split row ',' | str join \"\\t\" }"
+        );
+        // `print` alone leaves the line as it was read
+        assert_eq!(
+            converter
+                .convert(&args(&["BEGIN{FS=OFS=\",\"} {print}"]))
+                .unwrap(),
+            "lines"
+        );
+        // A quoted program split into words is rejoined
+        assert_eq!(
+            converter
+                .convert(&args(&[
+                    "'BEGIN{FS=\":\"}",
+                    "{print",
+                    "$1}'",
+                    "/etc/passwd"
+                ]))
+                .unwrap(),
+            "open --raw /etc/passwd | lines | split column ':' | get column1"
+        );
+        // Other BEGIN statements stay external
+        assert!(converter
+            .convert(&args(&["BEGIN { n = 0 } { print $1 }"]))
+            .unwrap()
+            .starts_with("^awk"));
+    }
+
+    #[test]
+    fn test_awk_record_and_field_counts() {
+        let converter = AwkConverter;
+
+        assert_eq!(
+            converter.convert(&["NR==1".to_string()]).unwrap(),
+            "lines | first 1"
+        );
+        assert_eq!(
+            converter.convert(&["NR>2".to_string()]).unwrap(),
+            "lines | skip 2"
+        );
+        assert_eq!(
+            converter.convert(&["NR != 3".to_string()]).unwrap(),
+            "lines | enumerate | where $it.index != 2 | get item"
+        );
+        assert_eq!(
+            converter.convert(&["{print NF}".to_string()]).unwrap(),
+            "lines | each { |line| $line | str trim | split row -r '\\s+' | length }"
+        );
+        assert_eq!(
+            converter
+                .convert(&["NR>1 {print $2}".to_string(), "data.txt".to_string()])
+                .unwrap(),
+            "open --raw data.txt | lines | skip 1 | str trim | split column -r '\\s+' | get column2"
         );
     }
 }
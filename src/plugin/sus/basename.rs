@@ -65,7 +65,7 @@ fn convert(&self, args: &[String]) -> Result<String> {
         // Handle single path case
         if paths.len() == 1 && !multiple {
             let path = &paths[0];
-            let mut result = format!("{} | path basename", base.quote_arg(path));
+            let mut result = base.path_pipeline(path, "path basename");
 
             // Handle suffix removal
             if !suffix.is_empty() {
@@ -82,7 +82,7 @@ fn convert(&self, args: &[String]) -> Result<String> {
         if paths.len() == 1 && multiple {
             // Single path with -a flag
             let path = &paths[0];
-            let mut result = format!("{} | path basename", base.quote_arg(path));
+            let mut result = base.path_pipeline(path, "path basename");
 
             if !suffix.is_empty() {
                 result.push_str(&format!(
@@ -112,7 +112,7 @@ fn convert(&self, args: &[String]) -> Result<String> {
         }
 
         // Add note about external command
-        result.push_str(" # Note: uses external chmod command");
+        result.push_str(&base.note("uses external chmod command"));
 
         Ok(result)
     }
@@ -130,7 +130,7 @@ fn convert(&self, args: &[String]) -> Result<String> {
         }
 
         // Add note about external command
-        result.push_str(" # Note: uses external chown command");
+        result.push_str(&base.note("uses external chown command"));
 
         Ok(result)
     }
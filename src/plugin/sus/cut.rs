@@ -24,8 +24,7 @@ fn convert(&self, args: &[String]) -> Result<String> {
         let mut files = Vec::new();
         let mut output_delimiter = None;
         let mut only_delimited = false;
-        // TODO: complement variable is not used in current implementation
-        let mut _complement = false;
+        let mut complement = false;
 
         let mut i = 0;
         while i < args.len() {
@@ -75,7 +74,24 @@ fn convert(&self, args: &[String]) -> Result<String> {
                     i += 1;
                 }
                 "--complement" => {
-                    _complement = true;
+                    complement = true;
+                    i += 1;
+                }
+                // Option values may be attached, as in `-d:` or `-f1,3`
+                arg if arg.starts_with("-d") && arg.len() > 2 => {
+                    delimiter = arg[2..].to_string();
+                    i += 1;
+                }
+                arg if arg.starts_with("-f") && arg.len() > 2 => {
+                    fields = parse_range_list(&arg[2..]);
+                    i += 1;
+                }
+                arg if arg.starts_with("-c") && arg.len() > 2 => {
+                    characters = parse_range_list(&arg[2..]);
+                    i += 1;
+                }
+                arg if arg.starts_with("-b") && arg.len() > 2 => {
+                    bytes = parse_range_list(&arg[2..]);
                     i += 1;
                 }
                 arg if !arg.starts_with('-') => {
@@ -115,17 +131,13 @@ fn convert(&self, args: &[String]) -> Result<String> {
         // Handle field extraction
         if !fields.is_empty() {
             // Field-based cutting
-            let split_cmd = if delimiter == "\t" {
-                "split row \"\\t\"".to_string()
-            } else if delimiter == " " {
-                "split row \" \"".to_string()
-            } else {
-                format!("split row {}", base.quote_arg(&delimiter))
-            };
+            let split_cmd = format!("split row {}", quote_delimiter(&delimiter));
+            // The total field count is unknown, so the complement rejects the listed fields
+            let selector = if complement { "reject" } else { "select" };
 
             result.push_str(&format!(
-                " | each {{ |line| $line | {} | select ",
-                split_cmd
+                " | each {{ |line| $line | {} | {} ",
+                split_cmd, selector
             ));
 
             // Convert field numbers to Nu column indices (1-based to 0-based)
@@ -143,13 +155,9 @@ fn convert(&self, args: &[String]) -> Result<String> {
             result.push_str(&field_indices.join(" "));
 
             // Handle output delimiter
-            if let Some(out_delim) = output_delimiter {
-                result.push_str(&format!(" | str join {}", base.quote_arg(&out_delim)));
-            } else if delimiter != "\t" {
-                result.push_str(&format!(" | str join {}", base.quote_arg(&delimiter)));
-            } else {
-                result.push_str(" | str join \"\\t\"");
-            }
+            // Fields are rejoined with the input delimiter unless told otherwise
+            let out_delim = output_delimiter.as_deref().unwrap_or(&delimiter);
+            result.push_str(&format!(" | str join {}", quote_delimiter(out_delim)));
 
             result.push_str(" }");
 
@@ -222,6 +230,15 @@ fn description(&self) -> &'static str {
     }
 }
 
+/// Quote a delimiter as a Nushell string, spelling out a tab
+fn quote_delimiter(delimiter: &str) -> String {
+    if delimiter == "\t" {
+        "\"\\t\"".to_string()
+    } else {
+        format!("\"{}\"", delimiter.replace('"', "\\\""))
+    }
+}
+
 /// Parse range list like "1,3,5-7" into individual positions
 fn parse_range_list(range_str: &str) -> Vec<usize> {
     let mut positions = Vec::new();
@@ -312,6 +329,46 @@ fn test_cut_converter() {
         );
     }
 
+    #[test]
+    fn test_cut_multiple_fields() {
+        let converter = CutConverter;
+
+        assert_eq!(
+            converter
+                .convert(&["-d:".to_string(), "-f1,3".to_string()])
+                .unwrap(),
+            "lines | each { |line| $line | split row \":\" | select 0 2 | str join \":\" }"
+        );
+        assert_eq!(
+            converter
+                .convert(&["-d,".to_string(), "-f2-4".to_string()])
+                .unwrap(),
+            "lines | each { |line| $line | split row \",\" | select 1 2 3 | str join \",\" }"
+        );
+    }
+
+    #[test]
+    fn test_cut_complement() {
+        let converter = CutConverter;
+
+        assert_eq!(
+            converter
+                .convert(&["--complement".to_string(), "-f2".to_string()])
+                .unwrap(),
+            "lines | each { |line| $line | split row \"\\t\" | reject 1 | str join \"\\t\" }"
+        );
+        assert_eq!(
+            converter
+                .convert(&[
+                    "-d,".to_string(),
+                    "--complement".to_string(),
+                    "-f1,3".to_string()
+                ])
+                .unwrap(),
+            "lines | each { |line| $line | split row \",\" | reject 0 2 | str join \",\" }"
+        );
+    }
+
     #[test]
     fn test_parse_range_list() {
         assert_eq!(parse_range_list("1,3,5"), vec![1, 3, 5]);
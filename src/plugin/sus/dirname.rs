@@ -51,8 +51,7 @@ fn convert(&self, args: &[String]) -> Result<String> {
         // Handle single path case
         if paths.len() == 1 {
             let path = &paths[0];
-            let result = format!("{} | path dirname", base.quote_arg(path));
-            return Ok(result);
+            return Ok(base.path_pipeline(path, "path dirname"));
         }
 
         // Handle multiple paths
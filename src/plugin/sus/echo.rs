@@ -3,20 +3,54 @@
 //! Converts POSIX `echo` commands to Nushell `print` commands
 
 use super::{BaseConverter, CommandConverter};
+use crate::plugin::expansion::{
+    escape_literal_parens, expand_word, interpolate_word, is_nu_expression,
+};
 use anyhow::Result;
 
+/// Which Nushell command `echo` converts to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EchoStyle {
+    /// Write to stdout with `print`
+    #[default]
+    Print,
+    /// Keep Nushell's own `echo`, which returns its arguments as a value
+    Echo,
+}
+
+impl EchoStyle {
+    /// The Nushell command name for this style
+    pub fn command(self) -> &'static str {
+        match self {
+            EchoStyle::Print => "print",
+            EchoStyle::Echo => "echo",
+        }
+    }
+}
+
 /// Converter for the `echo` command
-pub struct EchoConverter;
+#[derive(Default)]
+pub struct EchoConverter {
+    pub style: EchoStyle,
+}
+
+impl EchoConverter {
+    /// Create an echo converter emitting the given command style
+    pub fn new(style: EchoStyle) -> Self {
+        Self { style }
+    }
+}
 
 impl CommandConverter for EchoConverter {
     fn convert(&self, args: &[String]) -> Result<String> {
-        let base = BaseConverter;
+        let command = self.style.command();
 
         if args.is_empty() {
-            Ok("print".to_string())
+            Ok(command.to_string())
         } else {
             // Handle common echo flags
             let mut filtered_args = Vec::new();
+            let mut escapes = false;
             let mut i = 0;
 
             while i < args.len() {
@@ -28,12 +62,12 @@ fn convert(&self, args: &[String]) -> Result<String> {
                     }
                     "-e" => {
                         // -e enables interpretation of backslash escapes
-                        // Nu handles this by default, so skip
+                        escapes = true;
                         i += 1;
                     }
                     "-E" => {
                         // -E disables interpretation of backslash escapes
-                        // Nu handles this contextually, so skip
+                        escapes = false;
                         i += 1;
                     }
                     arg => {
@@ -43,14 +77,19 @@ fn convert(&self, args: &[String]) -> Result<String> {
                 }
             }
 
+            let expands = |arg: &String| {
+                is_nu_expression(arg) || (arg.contains('$') && !arg.starts_with('\''))
+            };
             if filtered_args.is_empty() {
-                Ok("print".to_string())
-            } else if filtered_args.len() == 1 {
-                Ok(format!("print {}", base.quote_arg(&filtered_args[0])))
+                Ok(command.to_string())
+            } else if escapes && !filtered_args.iter().any(expands) {
+                let text: Vec<String> = filtered_args
+                    .iter()
+                    .map(|arg| interpret_escapes(unquote(arg)))
+                    .collect();
+                Ok(format!("{} \"{}\"", command, text.join(" ")))
             } else {
-                // Multiple arguments - join them with spaces
-                let joined = filtered_args.join(" ");
-                Ok(format!("print {}", base.quote_arg(&joined)))
+                Ok(format!("{} {}", command, echo_string(&filtered_args)))
             }
         }
     }
@@ -64,13 +103,137 @@ fn description(&self) -> &'static str {
     }
 }
 
+/// Convert the words `echo` writes into one Nushell value
+///
+/// A lone variable or expression is its bare value. Otherwise the words
+/// become one string: single-quoted words stay literal, and the others
+/// interpolate their expansions into a `$"..."` string.
+pub(crate) fn echo_string(args: &[String]) -> String {
+    let args = BaseConverter.join_quoted_words(args);
+    if let [arg] = args.as_slice() {
+        let value = expand_word(arg);
+        if is_nu_expression(&value) {
+            return value;
+        }
+    }
+
+    // Single-quoted words are literal; the others may interpolate, and an
+    // unquoted expression is a substitution converted already
+    let words: Vec<(&str, bool, bool)> = args
+        .iter()
+        .map(|arg| {
+            if let Some(literal) = arg.strip_prefix('\'').and_then(|a| a.strip_suffix('\'')) {
+                (literal, true, false)
+            } else if let Some(quoted) = arg.strip_prefix('"').and_then(|a| a.strip_suffix('"')) {
+                (quoted, false, false)
+            } else {
+                (arg.as_str(), false, is_nu_expression(arg))
+            }
+        })
+        .collect();
+    let interpolated = words
+        .iter()
+        .any(|&(word, literal, expression)| expression || (!literal && word.contains('$')));
+    let text: Vec<String> = words
+        .into_iter()
+        .map(
+            |(word, literal, expression)| match (literal, interpolated) {
+                (true, _) => {
+                    let escaped = word.replace('\\', "\\\\").replace('"', "\\\"");
+                    if interpolated {
+                        escaped.replace('(', "\\(")
+                    } else {
+                        escaped
+                    }
+                }
+                (false, true) if expression && word.starts_with('(') => word.to_string(),
+                (false, true) if expression => format!("({})", word),
+                (false, true) => interpolate_word(&escape_literal_parens(word)),
+                (false, false) => word.replace('\\', "\\\\").replace('"', "\\\""),
+            },
+        )
+        .collect();
+    let text = text.join(" ");
+
+    if interpolated {
+        format!("$\"{}\"", text)
+    } else if !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:,=+@%".contains(c))
+    {
+        text
+    } else {
+        format!("\"{}\"", text)
+    }
+}
+
+/// Strip one pair of surrounding shell quotes
+pub(super) fn unquote(arg: &str) -> &str {
+    ['\'', '"']
+        .into_iter()
+        .find_map(|quote| arg.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(arg)
+}
+
+/// Interpret `echo -e` backslash escapes, returning the text as the body of
+/// a Nushell double-quoted string, which writes control characters as
+/// `\t`, `\n` or `\u{HH}`
+pub(super) fn interpret_escapes(text: &str) -> String {
+    let mut result = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => match chars.next() {
+                Some('t') => result.push_str("\\t"),
+                Some('n') => result.push_str("\\n"),
+                Some('r') => result.push_str("\\r"),
+                Some('\\') => result.push_str("\\\\"),
+                Some('a') => result.push_str("\\u{7}"),
+                Some('b') => result.push_str("\\u{8}"),
+                Some('e') => result.push_str("\\u{1b}"),
+                Some('f') => result.push_str("\\u{c}"),
+                Some('v') => result.push_str("\\u{b}"),
+                // `\c` ends the output
+                Some('c') => break,
+                // `\0NNN` is an octal byte, `\xHH` a hexadecimal one
+                Some(base @ ('0' | 'x')) => {
+                    let (radix, max) = if base == '0' { (8, 3) } else { (16, 2) };
+                    let mut digits = String::new();
+                    while let Some(&digit) = chars.peek() {
+                        if digits.len() == max || !digit.is_digit(radix) {
+                            break;
+                        }
+                        digits.push(digit);
+                        chars.next();
+                    }
+                    if base == 'x' && digits.is_empty() {
+                        result.push_str("\\\\x");
+                    } else {
+                        let value = u32::from_str_radix(&digits, radix).unwrap_or(0);
+                        result.push_str(&format!("\\u{{{:x}}}", value));
+                    }
+                }
+                Some(other) => {
+                    result.push_str("\\\\");
+                    result.push(other);
+                }
+                None => result.push_str("\\\\"),
+            },
+            _ => result.push(ch),
+        }
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_echo_converter() {
-        let converter = EchoConverter;
+        let converter = EchoConverter::default();
 
         // Empty echo
         assert_eq!(converter.convert(&[]).unwrap(), "print");
@@ -111,4 +274,79 @@ fn test_echo_converter() {
             "print \"hello\\nworld\""
         );
     }
+
+    #[test]
+    fn test_echo_quoted_words() {
+        let converter = EchoConverter::default();
+        let args = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+
+        // Quotes are removed and expansions interpolated
+        assert_eq!(
+            converter.convert(&args(&["\"a", "$x\""])).unwrap(),
+            "print $\"a ($x)\""
+        );
+        assert_eq!(
+            converter.convert(&args(&["'a b'", "c"])).unwrap(),
+            "print \"a b c\""
+        );
+        assert_eq!(converter.convert(&args(&["\"$x\""])).unwrap(), "print $x");
+        // Single-quoted words and parentheses stay literal
+        assert_eq!(
+            converter
+                .convert(&args(&["'$HOME'", "\"(x)\"", "$y"]))
+                .unwrap(),
+            "print $\"$HOME \\(x\\) ($y)\""
+        );
+    }
+
+    #[test]
+    fn test_echo_escape_sequences() {
+        let converter = EchoConverter::default();
+        let args = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+
+        // Tab and newline
+        assert_eq!(
+            converter.convert(&args(&["-e", "\"a\\tb\\nc\""])).unwrap(),
+            "print \"a\\tb\\nc\""
+        );
+        // Hexadecimal and octal bytes
+        assert_eq!(
+            converter.convert(&args(&["-e", "'x\\x41y'"])).unwrap(),
+            "print \"x\\u{41}y\""
+        );
+        assert_eq!(
+            converter.convert(&args(&["-e", "a\\0101\\0"])).unwrap(),
+            "print \"a\\u{41}\\u{0}\""
+        );
+        // Backslashes and quotes stay literal
+        assert_eq!(
+            converter
+                .convert(&args(&["-e", "'C:\\\\dir \"x\"\\q'"]))
+                .unwrap(),
+            "print \"C:\\\\dir \\\"x\\\"\\\\q\""
+        );
+        // \c stops the output
+        assert_eq!(
+            converter.convert(&args(&["-e", "one\\ctwo"])).unwrap(),
+            "print \"one\""
+        );
+    }
+
+    #[test]
+    fn test_echo_styles() {
+        let args = ["hello world".to_string()];
+
+        assert_eq!(
+            EchoConverter::new(EchoStyle::Print).convert(&args).unwrap(),
+            "print \"hello world\""
+        );
+        assert_eq!(
+            EchoConverter::new(EchoStyle::Echo).convert(&args).unwrap(),
+            "echo \"hello world\""
+        );
+        assert_eq!(
+            EchoConverter::new(EchoStyle::Echo).convert(&[]).unwrap(),
+            "echo"
+        );
+    }
 }
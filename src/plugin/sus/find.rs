@@ -2,7 +2,9 @@
 //!
 //! Converts POSIX `find` commands to Nushell `ls` and filtering operations
 
+use super::echo::unquote;
 use super::{BaseConverter, CommandConverter};
+use crate::plugin::expansion::{interpolate_word, is_nu_expression};
 use anyhow::Result;
 
 /// Converter for the `find` command
@@ -21,6 +23,8 @@ fn convert(&self, args: &[String]) -> Result<String> {
         let mut name_pattern = String::new();
         let mut file_type = String::new();
         let mut exec_command = String::new();
+        let mut exec_batch = false;
+        let mut printf_format = None;
         let mut print_action = true;
         let mut max_depth: Option<usize> = None;
         // TODO: min_depth variable is not used in current implementation
@@ -28,6 +32,7 @@ fn convert(&self, args: &[String]) -> Result<String> {
         let mut size_filter = String::new();
         let mut time_filter = String::new();
         let mut permission_filter = String::new();
+        let mut empty = false;
 
         let mut i = 0;
         while i < args.len() {
@@ -49,10 +54,14 @@ fn convert(&self, args: &[String]) -> Result<String> {
                     }
                 }
                 "-exec" => {
-                    // Handle -exec command {} \;
+                    // Handle -exec command {} \; and the batched -exec command {} +
                     let mut exec_parts = Vec::new();
                     i += 1;
                     while i < args.len() && args[i] != ";" && args[i] != "\\;" {
+                        if args[i] == "+" && exec_parts.last().is_some_and(|p| p == "{}") {
+                            exec_batch = true;
+                            break;
+                        }
                         exec_parts.push(args[i].clone());
                         i += 1;
                     }
@@ -61,13 +70,34 @@ fn convert(&self, args: &[String]) -> Result<String> {
                         print_action = false;
                     }
                     if i < args.len() {
-                        i += 1; // Skip the semicolon
+                        i += 1; // Skip the terminator
                     }
                 }
                 "-print" => {
                     print_action = true;
                     i += 1;
                 }
+                "-printf" => {
+                    if i + 1 < args.len() {
+                        // A quoted format with spaces arrives split into words
+                        i += 1;
+                        let mut format = args[i].clone();
+                        if let Some(quote) =
+                            format.chars().next().filter(|c| *c == '\'' || *c == '"')
+                        {
+                            while (format.len() < 2 || !format.ends_with(quote))
+                                && i + 1 < args.len()
+                            {
+                                i += 1;
+                                format.push(' ');
+                                format.push_str(&args[i]);
+                            }
+                        }
+                        printf_format = Some(printf_interpolation(&format));
+                        print_action = false;
+                    }
+                    i += 1;
+                }
                 "-print0" => {
                     print_action = true;
                     i += 1;
@@ -112,6 +142,10 @@ fn convert(&self, args: &[String]) -> Result<String> {
                         i += 1;
                     }
                 }
+                "-empty" => {
+                    empty = true;
+                    i += 1;
+                }
                 "-delete" => {
                     exec_command = "rm".to_string();
                     print_action = false;
@@ -132,25 +166,17 @@ fn convert(&self, args: &[String]) -> Result<String> {
         // Build the Nu command
         let mut result = String::new();
 
-        // Start with ls command
-        if path == "." {
-            result.push_str("ls");
-        } else {
-            result.push_str(&format!("ls {}", base.quote_arg(&path)));
-        }
-
-        // Add recursive flag if needed (default for find)
-        if max_depth.is_none() || max_depth.unwrap() > 1 {
-            result.push_str("/**/*");
-        }
+        // Start with ls over a glob below the path, recursive unless limited
+        let pattern = match max_depth {
+            Some(0) => None,
+            Some(1) => Some("*"),
+            _ => Some("**/*"),
+        };
+        result.push_str(&format!("ls {}", ls_glob(&base, &path, pattern)));
 
         // Add specific depth pattern if specified
-        if let Some(max) = max_depth {
-            if max == 1 {
-                result = result.replace("/**/*", "/*");
-            } else if max > 1 {
-                result.push_str(&format!(" # max depth {}", max));
-            }
+        if let Some(max) = max_depth.filter(|&max| max > 1) {
+            result.push_str(&format!(" # max depth {}", max));
         }
 
         // Add filters
@@ -158,15 +184,13 @@ fn convert(&self, args: &[String]) -> Result<String> {
 
         // Name pattern filter
         if !name_pattern.is_empty() {
+            let name_pattern = unquote(&name_pattern);
             let pattern = if name_pattern.contains('*') || name_pattern.contains('?') {
                 // It's a glob pattern
-                format!(
-                    "name =~ {}",
-                    base.quote_arg(&name_pattern.replace('*', ".*").replace('?', "."))
-                )
+                format!("name =~ '{}'", glob_regex(name_pattern))
             } else {
                 // Exact match
-                format!("name == {}", base.quote_arg(&name_pattern))
+                format!("name == \"{}\"", name_pattern.replace('"', "\\\""))
             };
             filters.push(pattern);
         }
@@ -200,6 +224,16 @@ fn convert(&self, args: &[String]) -> Result<String> {
             filters.push(size_condition);
         }
 
+        // Empty files have no bytes; empty directories have no entries
+        if empty {
+            let empty_condition = match file_type.as_str() {
+                "f" => "size == 0b",
+                "d" => "(ls $it.name | is-empty)",
+                _ => "((type == \"file\" and size == 0b) or (type == \"dir\" and (ls $it.name | is-empty)))",
+            };
+            filters.push(empty_condition.to_string());
+        }
+
         // Time filter (simplified)
         if !time_filter.is_empty() {
             filters.push(format!("# time filter: {}", time_filter));
@@ -218,13 +252,19 @@ fn convert(&self, args: &[String]) -> Result<String> {
 
         // Handle exec command
         if !exec_command.is_empty() {
-            if exec_command == "rm" {
+            if exec_batch {
+                // `+` runs the command once with every match as arguments
+                let cmd = exec_command.replace("{}", "...$in");
+                result.push_str(&format!(" | get name | {}", cmd));
+            } else if exec_command == "rm" {
                 result.push_str(" | each { |file| rm $file.name }");
             } else {
                 // Generic exec command
                 let cmd = exec_command.replace("{}", "$file.name");
                 result.push_str(&format!(" | each {{ |file| {} }}", cmd));
             }
+        } else if let Some(format) = printf_format {
+            result.push_str(&format!(" | each {{ |file| {} }}", format));
         } else if print_action {
             // Default action is to print the names
             result.push_str(" | get name");
@@ -242,6 +282,78 @@ fn description(&self) -> &'static str {
     }
 }
 
+/// Format the glob `ls` expands below `path`, or `path` itself without a pattern
+///
+/// Nushell only expands unquoted globs, so a path that needs quoting
+/// becomes a string converted with `into glob`.
+fn ls_glob(base: &BaseConverter, path: &str, pattern: Option<&str>) -> String {
+    let path = unquote(path);
+    let Some(pattern) = pattern else {
+        return base.quote_arg(path);
+    };
+    let glob = format!("{}/{}", path.trim_end_matches('/'), pattern);
+    if is_nu_expression(path) || base.quote_arg(path) != path {
+        format!("($\"{}\" | into glob)", interpolate_word(&glob))
+    } else {
+        glob
+    }
+}
+
+/// Convert a `-name` glob to a regex, escaping the characters a regex
+/// would otherwise treat specially
+fn glob_regex(pattern: &str) -> String {
+    let mut regex = String::new();
+    for c in pattern.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            '.' | '+' | '(' | ')' | '|' | '^' | '$' | '{' | '}' | '\\' => {
+                regex.push('\\');
+                regex.push(c);
+            }
+            c => regex.push(c),
+        }
+    }
+    regex
+}
+
+/// Convert a `-printf` format to a Nushell string interpolation over `$file`
+///
+/// Each result is printed on its own line, so a trailing `\n` is dropped.
+fn printf_interpolation(format: &str) -> String {
+    let format = format.trim_matches(|c| c == '\'' || c == '"');
+    let format = format.strip_suffix("\\n").unwrap_or(format);
+
+    let mut result = String::from("$\"");
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '%' => match chars.next() {
+                Some('p') => result.push_str("($file.name)"),
+                Some('f') => result.push_str("($file.name | path basename)"),
+                Some('s') => result.push_str("($file.size | into int)"),
+                Some('y') => result.push_str(
+                    "(match $file.type { \"dir\" => \"d\", \"symlink\" => \"l\", _ => \"f\" })",
+                ),
+                Some('%') => result.push('%'),
+                Some(other) => {
+                    result.push('%');
+                    result.push(other);
+                }
+                None => result.push('%'),
+            },
+            // Parentheses would start an interpolation
+            '(' | ')' | '"' => {
+                result.push('\\');
+                result.push(c);
+            }
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
 /// Parse size value from find command (e.g., "1M", "500k", "2G")
 fn parse_size_value(size_str: &str) -> String {
     let size_str = size_str.trim();
@@ -291,14 +403,17 @@ fn test_find_converter() {
         assert_eq!(converter.convert(&[]).unwrap(), "find");
 
         // Simple find
-        assert_eq!(converter.convert(&[".".to_string()]).unwrap(), "ls/**/*");
+        assert_eq!(
+            converter.convert(&[".".to_string()]).unwrap(),
+            "ls ./**/* | get name"
+        );
 
         // Find with name pattern
         assert_eq!(
             converter
                 .convert(&[".".to_string(), "-name".to_string(), "*.txt".to_string()])
                 .unwrap(),
-            "ls/**/* | where name =~ \".*\\.txt\" | get name"
+            "ls ./**/* | where name =~ '.*\\.txt' | get name"
         );
 
         // Find with type filter
@@ -306,7 +421,7 @@ fn test_find_converter() {
             converter
                 .convert(&[".".to_string(), "-type".to_string(), "f".to_string()])
                 .unwrap(),
-            "ls/**/* | where type == \"file\" | get name"
+            "ls ./**/* | where type == \"file\" | get name"
         );
 
         // Find with name and type
@@ -320,7 +435,7 @@ fn test_find_converter() {
                     "f".to_string()
                 ])
                 .unwrap(),
-            "ls/**/* | where name =~ \".*\\.rs\" and type == \"file\" | get name"
+            "ls ./**/* | where name =~ '.*\\.rs' and type == \"file\" | get name"
         );
 
         // Find with exec
@@ -336,7 +451,7 @@ fn test_find_converter() {
                     ";".to_string()
                 ])
                 .unwrap(),
-            "ls/**/* | where name =~ \".*\\.tmp\" | each { |file| rm $file.name }"
+            "ls ./**/* | where name =~ '.*\\.tmp' | each { |file| rm $file.name }"
         );
 
         // Find with specific path
@@ -344,7 +459,103 @@ fn test_find_converter() {
             converter
                 .convert(&["/tmp".to_string(), "-name".to_string(), "test".to_string()])
                 .unwrap(),
-            "ls \"/tmp\"/**/* | where name == \"test\" | get name"
+            "ls /tmp/**/* | where name == \"test\" | get name"
+        );
+    }
+
+    #[test]
+    fn test_find_start_path() {
+        let converter = FindConverter;
+        let args = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            converter.convert(&args(&[".", "-maxdepth", "1"])).unwrap(),
+            "ls ./* | get name"
+        );
+        // Quoted globs are literal, so a path with spaces converts into a glob
+        assert_eq!(
+            converter
+                .convert(&args(&["'my dir'", "-name", "'*.log'"]))
+                .unwrap(),
+            "ls ($\"my dir/**/*\" | into glob) | where name =~ '.*\\.log' | get name"
+        );
+    }
+
+    #[test]
+    fn test_find_exec_batch() {
+        let converter = FindConverter;
+        let args = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            converter
+                .convert(&args(&[".", "-name", "*.o", "-exec", "rm", "{}", "+"]))
+                .unwrap(),
+            "ls ./**/* | where name =~ '.*\\.o' | get name | rm ...$in"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&[
+                    ".", "-type", "f", "-exec", "chmod", "644", "{}", "+"
+                ]))
+                .unwrap(),
+            "ls ./**/* | where type == \"file\" | get name | chmod 644 ...$in"
+        );
+    }
+
+    #[test]
+    fn test_find_printf() {
+        let converter = FindConverter;
+        let args = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            converter
+                .convert(&args(&[".", "-printf", "'%p %s\\n'"]))
+                .unwrap(),
+            "ls ./**/* | each { |file| $\"($file.name) ($file.size | into int)\" }"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&[".", "-type", "f", "-printf", "%f\\t(%y)\\n"]))
+                .unwrap(),
+            "ls ./**/* | where type == \"file\" | each { |file| $\"($file.name | path basename)\\t\\((match $file.type { \"dir\" => \"d\", \"symlink\" => \"l\", _ => \"f\" })\\)\" }"
+        );
+    }
+
+    #[test]
+    fn test_find_printf_split_format() {
+        use crate::plugin::parser_posix::{parse_posix_script, PosixCommand};
+
+        // The parser splits a quoted format with spaces into several words
+        let script = parse_posix_script("find . -printf '%p %s\\n'").unwrap();
+        let PosixCommand::Simple(cmd) = &script.commands[0] else {
+            panic!("Expected simple command");
+        };
+        assert_eq!(
+            FindConverter.convert(&cmd.args).unwrap(),
+            "ls ./**/* | each { |file| $\"($file.name) ($file.size | into int)\" }"
+        );
+    }
+
+    #[test]
+    fn test_find_empty() {
+        let converter = FindConverter;
+        let args = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            converter
+                .convert(&args(&[".", "-type", "f", "-empty"]))
+                .unwrap(),
+            "ls ./**/* | where type == \"file\" and size == 0b | get name"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&[".", "-type", "d", "-empty"]))
+                .unwrap(),
+            "ls ./**/* | where type == \"dir\" and (ls $it.name | is-empty) | get name"
+        );
+        assert_eq!(
+            converter.convert(&args(&[".", "-empty"])).unwrap(),
+            "ls ./**/* | where ((type == \"file\" and size == 0b) or (type == \"dir\" and (ls $it.name | is-empty))) | get name"
         );
     }
 
@@ -18,14 +18,15 @@ fn convert(&self, args: &[String]) -> Result<String> {
 
         // Parse grep flags and arguments
         let mut pattern = String::new();
+        let mut patterns: Vec<String> = Vec::new();
+        let mut pattern_files = Vec::new();
         let mut files = Vec::new();
         let mut quiet = false;
         let mut invert = false;
         let mut ignore_case = false;
         let mut count = false;
         let mut line_number = false;
-        // TODO: extended_regex variable is not used in current implementation
-        let mut _extended_regex = false;
+        let mut dialect = RegexDialect::Basic;
         let mut fixed_string = false;
         let mut word_match = false;
         let mut only_matching = false;
@@ -53,8 +54,16 @@ fn convert(&self, args: &[String]) -> Result<String> {
                     line_number = true;
                     i += 1;
                 }
+                "-G" | "--basic-regexp" => {
+                    dialect = RegexDialect::Basic;
+                    i += 1;
+                }
                 "-E" | "--extended-regexp" => {
-                    _extended_regex = true;
+                    dialect = RegexDialect::Extended;
+                    i += 1;
+                }
+                "-P" | "--perl-regexp" => {
+                    dialect = RegexDialect::Perl;
                     i += 1;
                 }
                 "-F" | "--fixed-strings" => {
@@ -69,6 +78,27 @@ fn convert(&self, args: &[String]) -> Result<String> {
                     only_matching = true;
                     i += 1;
                 }
+                "-e" | "--regexp" => {
+                    if i + 1 < args.len() {
+                        patterns.push(args[i + 1].clone());
+                    }
+                    i += 2;
+                }
+                "-f" | "--file" => {
+                    if i + 1 < args.len() {
+                        // Read the patterns now if the file exists relative to the CWD
+                        match std::fs::read_to_string(&args[i + 1]) {
+                            Ok(contents) => patterns.extend(
+                                contents
+                                    .lines()
+                                    .filter(|line| !line.is_empty())
+                                    .map(str::to_string),
+                            ),
+                            Err(_) => pattern_files.push(args[i + 1].clone()),
+                        }
+                    }
+                    i += 2;
+                }
                 "-l" | "--files-with-matches" => {
                     // List only filenames with matches
                     i += 1;
@@ -89,12 +119,25 @@ fn convert(&self, args: &[String]) -> Result<String> {
                     // Hide filename
                     i += 1;
                 }
+                "--color" | "--colour" | "--line-buffered" | "-U" | "--binary" => {
+                    // Display and buffering only; the matches are the same
+                    i += 1;
+                }
+                arg if arg.starts_with("--color=") || arg.starts_with("--colour=") => {
+                    i += 1;
+                }
+                "-s" | "--no-messages" => {
+                    // Suppress errors about unreadable files; Nushell reports
+                    // them as errors of `open` instead
+                    i += 1;
+                }
                 arg if arg.starts_with('-') => {
                     // Unknown flag, skip
                     i += 1;
                 }
                 _ => {
-                    if pattern.is_empty() {
+                    let explicit = !patterns.is_empty() || !pattern_files.is_empty();
+                    if pattern.is_empty() && !explicit {
                         pattern = args[i].clone();
                     } else {
                         files.push(args[i].clone());
@@ -104,6 +147,31 @@ fn convert(&self, args: &[String]) -> Result<String> {
             }
         }
 
+        // Basic regexes swap the meaning of escaped and bare metacharacters
+        if dialect == RegexDialect::Basic && !fixed_string {
+            pattern = basic_to_extended(&pattern);
+            patterns = patterns.iter().map(|p| basic_to_extended(p)).collect();
+        }
+
+        // Patterns given with -e or -f match if any of them does
+        match patterns.len() {
+            0 => {}
+            1 => pattern = patterns.remove(0),
+            _ => pattern = format!("({})", patterns.join("|")),
+        }
+
+        // Pattern files that could not be read are left to the external grep
+        if !pattern_files.is_empty() {
+            return Ok(format!(
+                "^grep {}{}",
+                base.format_args(args),
+                base.note(&format!(
+                    "grep pattern file {} not readable at conversion time",
+                    pattern_files.join(", ")
+                ))
+            ));
+        }
+
         if pattern.is_empty() {
             return Ok("grep".to_string());
         }
@@ -111,24 +179,24 @@ fn convert(&self, args: &[String]) -> Result<String> {
         // Build the where clause based on flags
         let mut where_clause = if fixed_string {
             if invert {
-                format!("where $it !~ {}", base.quote_arg(&pattern))
+                format!("where $it !~ {}", quote_pattern(&pattern))
             } else {
-                format!("where $it =~ {}", base.quote_arg(&pattern))
+                format!("where $it =~ {}", quote_pattern(&pattern))
             }
         } else if word_match {
             // Word matching - pattern should match whole words
             let word_pattern = format!("\\b{}\\b", pattern);
             if invert {
-                format!("where $it !~ {}", base.quote_arg(&word_pattern))
+                format!("where $it !~ {}", quote_pattern(&word_pattern))
             } else {
-                format!("where $it =~ {}", base.quote_arg(&word_pattern))
+                format!("where $it =~ {}", quote_pattern(&word_pattern))
             }
         } else {
             // Regular expression matching
             if invert {
-                format!("where $it !~ {}", base.quote_arg(&pattern))
+                format!("where $it !~ {}", quote_pattern(&pattern))
             } else {
-                format!("where $it =~ {}", base.quote_arg(&pattern))
+                format!("where $it =~ {}", quote_pattern(&pattern))
             }
         };
 
@@ -143,17 +211,17 @@ fn convert(&self, args: &[String]) -> Result<String> {
         if files.is_empty() {
             // No files specified, filter stdin
             if quiet {
-                Ok(format!("lines | {} | length | $in > 0", where_clause))
+                Ok(format!("lines | {} | is-not-empty", where_clause))
             } else if count {
                 Ok(format!("lines | {} | length", where_clause))
             } else if line_number {
-                Ok(format!("lines | enumerate | where ($it.item =~ {}) | each {{ |x| $\"($x.index + 1): ($x.item)\" }}", base.quote_arg(&pattern)))
+                Ok(format!("lines | enumerate | where ($it.item =~ {}) | each {{ |x| $\"($x.index + 1): ($x.item)\" }}", quote_pattern(&pattern)))
             } else if only_matching {
                 // Extract only the matching parts - simplified
                 Ok(format!(
                     "lines | {} | each {{ |line| $line | str extract {}}}",
                     where_clause,
-                    base.quote_arg(&pattern)
+                    quote_pattern(&pattern)
                 ))
             } else {
                 Ok(format!("lines | {}", where_clause))
@@ -163,7 +231,7 @@ fn convert(&self, args: &[String]) -> Result<String> {
             let file = &files[0];
             if quiet {
                 Ok(format!(
-                    "open {} | lines | {} | length | $in > 0",
+                    "open {} | lines | {} | is-not-empty",
                     base.quote_arg(file),
                     where_clause
                 ))
@@ -174,13 +242,13 @@ fn convert(&self, args: &[String]) -> Result<String> {
                     where_clause
                 ))
             } else if line_number {
-                Ok(format!("open {} | lines | enumerate | where ($it.item =~ {}) | each {{ |x| $\"($x.index + 1): ($x.item)\" }}", base.quote_arg(file), base.quote_arg(&pattern)))
+                Ok(format!("open {} | lines | enumerate | where ($it.item =~ {}) | each {{ |x| $\"($x.index + 1): ($x.item)\" }}", base.quote_arg(file), quote_pattern(&pattern)))
             } else if only_matching {
                 Ok(format!(
                     "open {} | lines | {} | each {{ |line| $line | str extract {}}}",
                     base.quote_arg(file),
                     where_clause,
-                    base.quote_arg(&pattern)
+                    quote_pattern(&pattern)
                 ))
             } else {
                 Ok(format!(
@@ -189,6 +257,18 @@ fn convert(&self, args: &[String]) -> Result<String> {
                     where_clause
                 ))
             }
+        } else if count {
+            // Count matches per file; a trailing comment must stay outside the closure
+            let (filter, comment) = match where_clause.split_once(" # ") {
+                Some((filter, comment)) => (filter, format!(" # {}", comment)),
+                None => (where_clause.as_str(), String::new()),
+            };
+            Ok(format!(
+                "[{}] | each {{ |file| {{file: $file, count: (open --raw $file | lines | {} | length)}} }}{}",
+                base.format_args(&files),
+                filter,
+                comment
+            ))
         } else {
             // Multiple files - more complex, fall back to basic grep
             let mut result = "grep".to_string();
@@ -209,6 +289,58 @@ fn description(&self) -> &'static str {
     }
 }
 
+/// Regex syntax selected by `-G`, `-E` and `-P`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RegexDialect {
+    Basic,
+    Extended,
+    Perl,
+}
+
+/// Translate a POSIX basic regex into the extended syntax Nushell uses
+///
+/// In a BRE `\(`, `\)`, `\{`, `\}`, `\|`, `\+` and `\?` are operators while
+/// the bare characters are literals; extended syntax is the other way round.
+fn basic_to_extended(pattern: &str) -> String {
+    const SWAPPED: &[char] = &['(', ')', '{', '}', '|', '+', '?'];
+    let mut result = String::new();
+    let mut chars = pattern.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some(next) if SWAPPED.contains(&next) => result.push(next),
+                Some(next) => {
+                    result.push('\\');
+                    result.push(next);
+                }
+                None => result.push('\\'),
+            },
+            ch if SWAPPED.contains(&ch) => {
+                result.push('\\');
+                result.push(ch);
+            }
+            ch => result.push(ch),
+        }
+    }
+
+    result
+}
+
+/// Quote a regex so alternations and groups aren't read as Nushell syntax
+///
+/// Patterns with backslash escapes are single-quoted so Nushell passes them
+/// to the regex engine unchanged.
+fn quote_pattern(pattern: &str) -> String {
+    if pattern.contains('\\') && !pattern.contains('\'') {
+        format!("'{}'", pattern)
+    } else if pattern.contains(['|', '(', ')']) {
+        format!("\"{}\"", pattern.replace('"', "\\\""))
+    } else {
+        BaseConverter.quote_arg(pattern)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -247,7 +379,7 @@ fn test_grep_converter() {
             converter
                 .convert(&["-q".to_string(), "test".to_string()])
                 .unwrap(),
-            "lines | where $it =~ \"test\" | length | $in > 0"
+            "lines | where $it =~ \"test\" | is-not-empty"
         );
 
         // Count mode
@@ -282,4 +414,133 @@ fn test_grep_converter() {
             "lines | where $it =~ \"test.txt\""
         );
     }
+
+    #[test]
+    fn test_grep_display_flags() {
+        let converter = GrepConverter;
+        let args = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            converter
+                .convert(&args(&["--color=auto", "test", "file.txt"]))
+                .unwrap(),
+            "open file.txt | lines | where $it =~ test"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&["--line-buffered", "-s", "test"]))
+                .unwrap(),
+            "lines | where $it =~ test"
+        );
+    }
+
+    #[test]
+    fn test_grep_count_multiple_files() {
+        let converter = GrepConverter;
+
+        assert_eq!(
+            converter
+                .convert(&[
+                    "-c".to_string(),
+                    "x".to_string(),
+                    "a".to_string(),
+                    "b".to_string()
+                ])
+                .unwrap(),
+            "[a b] | each { |file| {file: $file, count: (open --raw $file | lines | where $it =~ x | length)} }"
+        );
+        assert_eq!(
+            converter
+                .convert(&[
+                    "-c".to_string(),
+                    "-i".to_string(),
+                    "x".to_string(),
+                    "a".to_string(),
+                    "b".to_string()
+                ])
+                .unwrap(),
+            "[a b] | each { |file| {file: $file, count: (open --raw $file | lines | where $it =~ x | length)} } # case-insensitive"
+        );
+    }
+
+    #[test]
+    fn test_grep_multiple_patterns() {
+        let converter = GrepConverter;
+
+        assert_eq!(
+            converter
+                .convert(&[
+                    "-e".to_string(),
+                    "a".to_string(),
+                    "-e".to_string(),
+                    "b".to_string(),
+                ])
+                .unwrap(),
+            "lines | where $it =~ \"(a|b)\""
+        );
+
+        // With -e every positional argument is a file
+        assert_eq!(
+            converter
+                .convert(&["-e".to_string(), "a".to_string(), "file.txt".to_string()])
+                .unwrap(),
+            "open file.txt | lines | where $it =~ a"
+        );
+    }
+
+    #[test]
+    fn test_grep_pattern_file() {
+        let converter = GrepConverter;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("patterns.txt");
+        std::fs::write(&path, "foo\nbar\n").unwrap();
+
+        let result = converter
+            .convert(&["-f".to_string(), path.to_string_lossy().to_string()])
+            .unwrap();
+        assert_eq!(result, "lines | where $it =~ \"(foo|bar)\"");
+
+        // An unreadable pattern file runs the external grep
+        assert_eq!(
+            converter
+                .convert(&[
+                    "-e".to_string(),
+                    "foo".to_string(),
+                    "-f".to_string(),
+                    "missing.txt".to_string()
+                ])
+                .unwrap(),
+            "^grep -e foo -f missing.txt # Note: grep pattern file missing.txt not readable at conversion time"
+        );
+    }
+
+    #[test]
+    fn test_grep_regex_dialects() {
+        let converter = GrepConverter;
+
+        // Perl patterns pass through unchanged
+        assert_eq!(
+            converter
+                .convert(&["-P".to_string(), "\\d+".to_string()])
+                .unwrap(),
+            "lines | where $it =~ '\\d+'"
+        );
+
+        // Basic patterns swap escaped and bare metacharacters
+        assert_eq!(
+            converter
+                .convert(&["-G".to_string(), "\\(ab\\)\\+c?".to_string()])
+                .unwrap(),
+            "lines | where $it =~ '(ab)+c\\?'"
+        );
+        assert_eq!(basic_to_extended("a|b"), "a\\|b");
+
+        // Extended patterns are left alone
+        assert_eq!(
+            converter
+                .convert(&["-E".to_string(), "(ab)+".to_string()])
+                .unwrap(),
+            "lines | where $it =~ \"(ab)+\""
+        );
+    }
 }
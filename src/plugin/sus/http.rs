@@ -0,0 +1,453 @@
+//! HTTP client command converters
+//!
+//! Converts `curl` and `wget` downloads to Nushell's native `http` commands
+
+use super::echo::unquote;
+use super::{BaseConverter, CommandConverter};
+use anyhow::Result;
+
+/// Short curl options that take a value
+const SHORT_VALUE_FLAGS: &str = "ACDEFHKPQTUXYbcdemortuwxyz";
+
+/// Long curl options that take a value, as the next word or after `=`
+const LONG_VALUE_FLAGS: &[&str] = &[
+    "--request",
+    "--data",
+    "--data-raw",
+    "--data-binary",
+    "--data-urlencode",
+    "--header",
+    "--output",
+    "--url",
+    "--user",
+    "--user-agent",
+    "--referer",
+    "--cookie",
+    "--cookie-jar",
+    "--form",
+    "--upload-file",
+    "--write-out",
+    "--proxy",
+    "--proxy-user",
+    "--range",
+    "--config",
+    "--cert",
+    "--key",
+    "--cacert",
+    "--continue-at",
+    "--dump-header",
+    "--max-time",
+    "--connect-timeout",
+    "--retry",
+    "--retry-delay",
+    "--max-redirs",
+    "--limit-rate",
+    "--resolve",
+    "--interface",
+    "--output-dir",
+];
+
+/// Converter for the `curl` command
+pub struct CurlConverter;
+
+impl CommandConverter for CurlConverter {
+    fn convert(&self, args: &[String]) -> Result<String> {
+        let base = BaseConverter;
+        let args = &base.join_quoted_words(args);
+
+        let mut method = None;
+        let mut data = None;
+        let mut headers = Vec::new();
+        let mut output = None;
+        let mut remote_name = false;
+        let mut url = None;
+
+        let mut words = args.iter();
+        while let Some(arg) = words.next() {
+            if !arg.starts_with('-') || arg == "-" {
+                // The first URL is the one requested
+                if url.is_none() {
+                    url = Some(unquote(arg).to_string());
+                }
+                continue;
+            }
+
+            let mut options = Vec::new();
+            if arg.starts_with("--") {
+                let (flag, attached) = match arg.split_once('=') {
+                    Some((flag, value)) => (flag, Some(value.to_string())),
+                    None => (arg.as_str(), None),
+                };
+                let value = if LONG_VALUE_FLAGS.contains(&flag) {
+                    attached.or_else(|| words.next().cloned())
+                } else {
+                    None
+                };
+                options.push((flag.to_string(), value));
+            } else {
+                // Short flags may be combined, e.g. `-fsSLo out`; a flag taking
+                // a value ends the group and uses the rest of it, or the next word
+                for (pos, letter) in arg.char_indices().skip(1) {
+                    let flag = format!("-{}", letter);
+                    if SHORT_VALUE_FLAGS.contains(letter) {
+                        let attached = &arg[pos + letter.len_utf8()..];
+                        let value = if attached.is_empty() {
+                            words.next().cloned()
+                        } else {
+                            Some(attached.to_string())
+                        };
+                        options.push((flag, value));
+                        break;
+                    }
+                    options.push((flag, None));
+                }
+            }
+
+            for (flag, value) in options {
+                match (flag.as_str(), value) {
+                    ("-X" | "--request", Some(m)) => method = Some(unquote(&m).to_lowercase()),
+                    ("-d" | "--data" | "--data-raw" | "--data-binary", Some(d)) => {
+                        data = Some(unquote(&d).to_string())
+                    }
+                    ("-H" | "--header", Some(h)) => {
+                        if let Some((name, value)) = unquote(&h).split_once(':') {
+                            headers.push(format!(
+                                "{} {}",
+                                base.quote_arg(name.trim()),
+                                base.quote_arg(value.trim())
+                            ));
+                        }
+                    }
+                    ("-o" | "--output", Some(o)) => output = Some(unquote(&o).to_string()),
+                    ("-O" | "--remote-name", _) => remote_name = true,
+                    ("--url", Some(u)) if url.is_none() => url = Some(unquote(&u).to_string()),
+                    // `http` is already quiet, follows redirects and fails on
+                    // error statuses; other options are skipped with their values
+                    _ => {}
+                }
+            }
+        }
+
+        let Some(url) = url else {
+            return Ok(format!("curl {}", base.format_args(args))
+                .trim_end()
+                .to_string());
+        };
+
+        // Sending data without a method is a POST
+        let method =
+            method.unwrap_or_else(|| if data.is_some() { "post" } else { "get" }.to_string());
+        if remote_name {
+            output = Some(remote_file_name(&url));
+        }
+
+        Ok(request(
+            &base,
+            &method,
+            &headers,
+            &url,
+            data.as_deref(),
+            output.as_deref(),
+        ))
+    }
+
+    fn command_name(&self) -> &'static str {
+        "curl"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts curl requests to Nushell http commands"
+    }
+}
+
+/// Converter for the `wget` command
+pub struct WgetConverter;
+
+impl CommandConverter for WgetConverter {
+    fn convert(&self, args: &[String]) -> Result<String> {
+        let base = BaseConverter;
+        let args = &base.join_quoted_words(args);
+
+        let mut headers = Vec::new();
+        let mut output = None;
+        let mut url = None;
+
+        let mut i = 0;
+        while i < args.len() {
+            match args[i].as_str() {
+                "-q" | "--quiet" | "-nv" | "--no-verbose" => {}
+                "-O" | "--output-document" => {
+                    i += 1;
+                    output = args.get(i).map(|o| unquote(o).to_string());
+                }
+                arg if arg.starts_with("-O") => output = Some(arg[2..].to_string()),
+                arg if arg.starts_with("--header=") => {
+                    if let Some((name, value)) = unquote(&arg[9..]).split_once(':') {
+                        headers.push(format!(
+                            "{} {}",
+                            base.quote_arg(name.trim()),
+                            base.quote_arg(value.trim())
+                        ));
+                    }
+                }
+                arg if arg.starts_with('-') => {
+                    // Unknown flag, skip
+                }
+                arg => url = Some(unquote(arg).to_string()),
+            }
+            i += 1;
+        }
+
+        let Some(url) = url else {
+            return Ok(format!("wget {}", base.format_args(args))
+                .trim_end()
+                .to_string());
+        };
+
+        // wget saves under the remote name unless told otherwise; `-O -` prints
+        let output = match output.as_deref() {
+            Some("-") => None,
+            Some(file) => Some(file.to_string()),
+            None => Some(remote_file_name(&url)),
+        };
+
+        Ok(request(
+            &base,
+            "get",
+            &headers,
+            &url,
+            None,
+            output.as_deref(),
+        ))
+    }
+
+    fn command_name(&self) -> &'static str {
+        "wget"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts wget downloads to Nushell http get"
+    }
+}
+
+/// Build an `http METHOD [--headers [...]] URL [DATA] [| save FILE]` command
+fn request(
+    base: &BaseConverter,
+    method: &str,
+    headers: &[String],
+    url: &str,
+    data: Option<&str>,
+    output: Option<&str>,
+) -> String {
+    let mut result = format!("http {}", method);
+    if !headers.is_empty() {
+        result.push_str(&format!(" --headers [{}]", headers.join(" ")));
+    }
+    result.push_str(&format!(" {}", base.quote_arg(url)));
+    if let Some(data) = data {
+        result.push_str(&format!(" {}", base.quote_arg(data)));
+    } else if method == "post" || method == "put" || method == "patch" {
+        // These methods take a body even when empty
+        result.push_str(" ''");
+    }
+    if let Some(file) = output.filter(|file| *file != "-") {
+        result.push_str(&format!(" | save {}", base.quote_arg(file)));
+    }
+    result
+}
+
+/// The file name a download is saved under: the last segment of the URL path
+fn remote_file_name(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let path = path.split_once("://").map_or(path, |(_, rest)| rest);
+    match path
+        .split_once('/')
+        .and_then(|(_, path)| path.rsplit('/').next())
+    {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => "index.html".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_curl_get() {
+        let converter = CurlConverter;
+
+        assert_eq!(
+            converter
+                .convert(&args(&["-s", "https://x.org/api"]))
+                .unwrap(),
+            "http get https://x.org/api"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&[
+                    "-H",
+                    "Accept: application/json",
+                    "https://x.org/api"
+                ]))
+                .unwrap(),
+            "http get --headers [Accept application/json] https://x.org/api"
+        );
+    }
+
+    #[test]
+    fn test_split_header() {
+        use crate::plugin::parser_posix::{parse_posix_script, PosixCommand};
+
+        // The parser splits a quoted header with spaces into several words
+        let convert = |converter: &dyn CommandConverter, source: &str| {
+            let script = parse_posix_script(source).unwrap();
+            let PosixCommand::Simple(cmd) = &script.commands[0] else {
+                panic!("Expected simple command");
+            };
+            converter.convert(&cmd.args).unwrap()
+        };
+
+        assert_eq!(
+            convert(
+                &CurlConverter,
+                "curl -H \"Authorization: Bearer abc123\" -H 'Accept: application/json' https://x.org/api"
+            ),
+            "http get --headers [Authorization \"Bearer abc123\" Accept application/json] https://x.org/api"
+        );
+        assert_eq!(
+            convert(
+                &WgetConverter,
+                "wget --header=\"Accept: application/json\" https://x.org/a.json"
+            ),
+            "http get --headers [Accept application/json] https://x.org/a.json | save a.json"
+        );
+    }
+
+    #[test]
+    fn test_curl_download() {
+        let converter = CurlConverter;
+
+        assert_eq!(
+            converter
+                .convert(&args(&["-o", "out.tar.gz", "https://x.org/a.tar.gz"]))
+                .unwrap(),
+            "http get https://x.org/a.tar.gz | save out.tar.gz"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&["-fsSLO", "https://x.org/a.tar.gz"]))
+                .unwrap(),
+            "http get https://x.org/a.tar.gz | save a.tar.gz"
+        );
+        assert_eq!(
+            converter.convert(&args(&["-O", "https://x.org"])).unwrap(),
+            "http get https://x.org | save index.html"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&["-sSLo", "out.tar.gz", "https://x.org/a.tar.gz"]))
+                .unwrap(),
+            "http get https://x.org/a.tar.gz | save out.tar.gz"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&["-sSLoout.tar.gz", "https://x.org/a.tar.gz"]))
+                .unwrap(),
+            "http get https://x.org/a.tar.gz | save out.tar.gz"
+        );
+    }
+
+    #[test]
+    fn test_curl_option_values() {
+        let converter = CurlConverter;
+
+        // Option values are not taken for the URL
+        assert_eq!(
+            converter
+                .convert(&args(&[
+                    "-u",
+                    "me:secret",
+                    "--max-time",
+                    "10",
+                    "-A",
+                    "agent",
+                    "https://x.org/api"
+                ]))
+                .unwrap(),
+            "http get https://x.org/api"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&["--connect-timeout=5", "https://x.org/api"]))
+                .unwrap(),
+            "http get https://x.org/api"
+        );
+
+        // The first URL is requested
+        assert_eq!(
+            converter
+                .convert(&args(&["https://x.org/a", "https://x.org/b"]))
+                .unwrap(),
+            "http get https://x.org/a"
+        );
+    }
+
+    #[test]
+    fn test_curl_post() {
+        let converter = CurlConverter;
+
+        assert_eq!(
+            converter
+                .convert(&args(&["-X", "POST", "-d", "name=x", "https://x.org/api"]))
+                .unwrap(),
+            "http post https://x.org/api name=x"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&["-d", "'a=1'", "https://x.org/api"]))
+                .unwrap(),
+            "http post https://x.org/api a=1"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&["-X", "DELETE", "https://x.org/api/1"]))
+                .unwrap(),
+            "http delete https://x.org/api/1"
+        );
+    }
+
+    #[test]
+    fn test_wget_converter() {
+        let converter = WgetConverter;
+
+        assert_eq!(
+            converter
+                .convert(&args(&["-O-", "https://x.org/a"]))
+                .unwrap(),
+            "http get https://x.org/a"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&["-q", "-O", "-", "https://x.org/a"]))
+                .unwrap(),
+            "http get https://x.org/a"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&["-O", "page.html", "https://x.org/"]))
+                .unwrap(),
+            "http get https://x.org/ | save page.html"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&["https://x.org/file.zip"]))
+                .unwrap(),
+            "http get https://x.org/file.zip | save file.zip"
+        );
+    }
+}
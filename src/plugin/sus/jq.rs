@@ -0,0 +1,139 @@
+//! Jq command converter
+//!
+//! Converts simple `jq` filters to Nushell's native JSON navigation; other
+//! filters keep running the external `jq`
+
+use super::{BaseConverter, CommandConverter};
+use anyhow::Result;
+
+/// Converter for the `jq` command
+pub struct JqConverter;
+
+impl CommandConverter for JqConverter {
+    fn convert(&self, args: &[String]) -> Result<String> {
+        let base = BaseConverter;
+
+        let mut filter = None;
+        let mut files = Vec::new();
+        let mut supported = true;
+
+        for arg in args {
+            match arg.as_str() {
+                // Nushell strings print without JSON quoting already
+                "-r" | "--raw-output" => {}
+                arg if arg.starts_with('-') => supported = false,
+                arg if filter.is_none() => {
+                    filter = Some(arg.trim_matches(|c| c == '\'' || c == '"').to_string())
+                }
+                arg => files.push(arg.to_string()),
+            }
+        }
+
+        let path = filter.as_deref().and_then(cell_path).filter(|_| supported);
+        let Some(path) = path else {
+            let mut result = String::from("^jq");
+            if !args.is_empty() {
+                result.push_str(&format!(" {}", args.join(" ")));
+            }
+            result.push_str(&base.note("jq filter has no direct Nushell equivalent"));
+            return Ok(result);
+        };
+
+        // Files are read raw, since `open` only parses names ending in .json
+        let mut result = match files.as_slice() {
+            [] => "from json".to_string(),
+            [file] => format!("open --raw {} | from json", base.quote_arg(file)),
+            files => format!(
+                "[{}] | each {{ |file| open --raw $file | from json }}",
+                base.format_args(files)
+            ),
+        };
+        if !path.is_empty() {
+            result.push_str(&format!(" | get {}", path));
+        }
+
+        Ok(result)
+    }
+
+    fn command_name(&self) -> &'static str {
+        "jq"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts simple jq filters to Nushell JSON navigation"
+    }
+}
+
+/// Convert a jq path filter such as `.`, `.name`, `.a.b` or `.[]` to a cell
+/// path for `get`; an empty path means the whole value
+///
+/// `.[]` iterates the value, which a Nushell list already does in a pipeline.
+fn cell_path(filter: &str) -> Option<String> {
+    let path = filter.strip_prefix('.')?;
+    let path = path.strip_prefix("[]").unwrap_or(path);
+    let path = path.strip_prefix('.').unwrap_or(path);
+    let fields: Vec<&str> = if path.is_empty() {
+        Vec::new()
+    } else {
+        path.split('.').collect()
+    };
+    fields
+        .iter()
+        .all(|field| {
+            !field.is_empty() && field.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+        .then(|| fields.join("."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_jq_converter() {
+        let converter = JqConverter;
+
+        assert_eq!(converter.convert(&args(&["."])).unwrap(), "from json");
+        assert_eq!(
+            converter.convert(&args(&[".name"])).unwrap(),
+            "from json | get name"
+        );
+        assert_eq!(converter.convert(&args(&["'.[]'"])).unwrap(), "from json");
+        assert_eq!(
+            converter
+                .convert(&args(&["-r", ".[].name", "users.json"]))
+                .unwrap(),
+            "open --raw users.json | from json | get name"
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&[".id", "a.txt", "b.txt"]))
+                .unwrap(),
+            "[a.txt b.txt] | each { |file| open --raw $file | from json } | get id"
+        );
+        assert_eq!(
+            converter.convert(&args(&[".config.port"])).unwrap(),
+            "from json | get config.port"
+        );
+    }
+
+    #[test]
+    fn test_jq_complex_filter() {
+        let converter = JqConverter;
+
+        assert_eq!(
+            converter
+                .convert(&args(&["'map(select(.age > 30))'"]))
+                .unwrap(),
+            "^jq 'map(select(.age > 30))' # Note: jq filter has no direct Nushell equivalent"
+        );
+        assert_eq!(
+            converter.convert(&args(&["-s", "."])).unwrap(),
+            "^jq -s . # Note: jq filter has no direct Nushell equivalent"
+        );
+    }
+}
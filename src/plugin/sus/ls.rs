@@ -18,76 +18,39 @@ fn convert(&self, args: &[String]) -> Result<String> {
 
         let mut nu_args = Vec::new();
         let mut paths = Vec::new();
+        let mut sort_key = None;
+        let mut reverse = false;
+        let mut recursive = false;
+        let mut inode = false;
 
         for arg in args {
             match arg.as_str() {
-                "-l" => nu_args.push("--long".to_string()),
-                "-a" => nu_args.push("--all".to_string()),
-                "-h" => nu_args.push("--help".to_string()),
-                "-la" | "-al" => {
-                    nu_args.push("--long".to_string());
-                    nu_args.push("--all".to_string());
-                }
-                "-lh" | "-hl" => {
-                    nu_args.push("--long".to_string());
-                    nu_args.push("--help".to_string());
-                }
-                "-ah" | "-ha" => {
-                    nu_args.push("--all".to_string());
-                    nu_args.push("--help".to_string());
-                }
-                "-lah" | "-alh" | "-hla" | "-hal" | "-ahl" | "-lha" => {
-                    nu_args.push("--long".to_string());
-                    nu_args.push("--all".to_string());
-                    nu_args.push("--help".to_string());
-                }
-                "-1" => {
-                    // Single column output - Nu's default table format handles this
-                    // Skip this flag
-                }
-                "-d" => {
-                    // List directories themselves, not their contents
-                    // Nu doesn't have a direct equivalent, but we can note it
-                    nu_args.push("--directory".to_string());
-                }
-                "-R" => {
-                    // Recursive listing
-                    nu_args.push("--recursive".to_string());
-                }
-                "-r" => {
-                    // Reverse order
-                    nu_args.push("--reverse".to_string());
-                }
-                "-t" => {
-                    // Sort by modification time
-                    nu_args.push("--sort-by".to_string());
-                    nu_args.push("modified".to_string());
-                }
-                "-S" => {
-                    // Sort by size
-                    nu_args.push("--sort-by".to_string());
-                    nu_args.push("size".to_string());
-                }
-                "-i" => {
-                    // Show inode numbers - Nu doesn't have direct support
-                    // but we can note it in a comment
-                    nu_args.push("# --show-inode".to_string());
-                }
-                "-F" => {
-                    // Append indicator to entries - Nu's type column handles this
-                    // Skip this flag
-                }
-                "-G" => {
-                    // Enable colorized output - Nu handles this by default
-                    // Skip this flag
-                }
-                "--color" | "--color=auto" | "--color=always" => {
+                "--color" | "--color=auto" | "--color=always" | "--color=never" => {
                     // Color output - Nu handles this by default
-                    // Skip this flag
                 }
-                "--color=never" => {
-                    // Disable color - Nu would need specific handling
-                    // Skip for now
+                "--recursive" => recursive = true,
+                arg if arg.starts_with('-') && arg.len() > 1 && !arg.starts_with("--") => {
+                    // Short flags may be combined, e.g. `-ltr`
+                    for flag in arg[1..].chars() {
+                        match flag {
+                            'l' => push_unique(&mut nu_args, "--long"),
+                            'a' | 'A' => push_unique(&mut nu_args, "--all"),
+                            // List directories themselves, not their contents
+                            'd' => push_unique(&mut nu_args, "--directory"),
+                            // Nu lists subdirectories through a recursive glob
+                            'R' => recursive = true,
+                            // Sort by size or modification time, largest/newest first
+                            'S' => sort_key = Some("size"),
+                            't' => sort_key = Some("modified"),
+                            'r' => reverse = true,
+                            // Inode numbers are a column of the long listing
+                            'i' => inode = true,
+                            // Human-readable sizes, single column, type indicators and
+                            // colors are all part of Nu's default table output
+                            'h' | '1' | 'F' | 'G' => {}
+                            _ => nu_args.push(format!("# Unknown flag: -{}", flag)),
+                        }
+                    }
                 }
                 arg if arg.starts_with('-') => {
                     // Unknown flag, pass through with warning comment
@@ -95,11 +58,48 @@ fn convert(&self, args: &[String]) -> Result<String> {
                 }
                 _ => {
                     // This is a path argument
-                    paths.push(base.quote_arg(arg));
+                    paths.push(arg.to_string());
+                }
+            }
+        }
+
+        if recursive {
+            if paths.is_empty() {
+                paths.push("**/*".to_string());
+            } else {
+                for path in &mut paths {
+                    *path = format!("{}/**/*", path.trim_end_matches('/'));
                 }
             }
         }
 
+        // `-i` alone shows just the inode and name; with `-l` it leads the
+        // full listing
+        let long = nu_args.iter().any(|arg| arg == "--long");
+        if inode {
+            push_unique(&mut nu_args, "--long");
+        }
+
+        // A trailing slash on a glob (`*/`) only matches directories
+        let list_directories = nu_args.iter().any(|arg| arg == "--directory");
+        let mut dirs_only = false;
+        let paths: Vec<String> = paths
+            .iter()
+            .map(|path| {
+                if is_glob(path) {
+                    match path.strip_suffix('/') {
+                        Some(stripped) if list_directories => {
+                            dirs_only = true;
+                            stripped.to_string()
+                        }
+                        _ => path.to_string(),
+                    }
+                } else {
+                    base.quote_arg(path)
+                }
+            })
+            .collect();
+
         // Build the final command
         let mut result = "ls".to_string();
 
@@ -115,6 +115,26 @@ fn convert(&self, args: &[String]) -> Result<String> {
             result.push_str(&paths.join(" "));
         }
 
+        if dirs_only {
+            result.push_str(" | where type == dir");
+        }
+
+        // Add sorting; `-r` flips the default largest/newest-first order
+        match (sort_key, reverse) {
+            (Some(key), false) => result.push_str(&format!(" | sort-by {} --reverse", key)),
+            (Some(key), true) => result.push_str(&format!(" | sort-by {}", key)),
+            (None, true) => result.push_str(" | reverse"),
+            (None, false) => {}
+        }
+
+        if inode {
+            if long {
+                result.push_str(" | move inode --before name");
+            } else {
+                result.push_str(" | select inode name");
+            }
+        }
+
         Ok(result)
     }
 
@@ -127,6 +147,18 @@ fn description(&self) -> &'static str {
     }
 }
 
+/// Check whether a path is an unquoted glob that Nu should expand
+fn is_glob(path: &str) -> bool {
+    !path.contains([' ', '$']) && path.contains(['*', '?', '['])
+}
+
+/// Push a flag unless it is already present
+fn push_unique(args: &mut Vec<String>, flag: &str) {
+    if !args.iter().any(|arg| arg == flag) {
+        args.push(flag.to_string());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -170,19 +202,108 @@ fn test_ls_converter() {
         // ls with sort by time
         assert_eq!(
             converter.convert(&["-t".to_string()]).unwrap(),
-            "ls --sort-by modified"
+            "ls | sort-by modified --reverse"
         );
 
         // ls with sort by size
         assert_eq!(
             converter.convert(&["-S".to_string()]).unwrap(),
-            "ls --sort-by size"
+            "ls | sort-by size --reverse"
         );
 
         // ls with reverse order
         assert_eq!(
             converter.convert(&["-r".to_string()]).unwrap(),
-            "ls --reverse"
+            "ls | reverse"
+        );
+
+        // Long listing sorted by size
+        assert_eq!(
+            converter.convert(&["-lS".to_string()]).unwrap(),
+            "ls --long | sort-by size --reverse"
+        );
+
+        // Long listing sorted by time
+        assert_eq!(
+            converter.convert(&["-lt".to_string()]).unwrap(),
+            "ls --long | sort-by modified --reverse"
+        );
+
+        // Long listing sorted by time, oldest first
+        assert_eq!(
+            converter.convert(&["-ltr".to_string()]).unwrap(),
+            "ls --long | sort-by modified"
+        );
+
+        // Directory globs list the matching directories themselves
+        assert_eq!(
+            converter
+                .convert(&["-d".to_string(), "*/".to_string()])
+                .unwrap(),
+            "ls --directory * | where type == dir"
+        );
+
+        // Directory entry itself in long format
+        assert_eq!(
+            converter
+                .convert(&["-ld".to_string(), "dir".to_string()])
+                .unwrap(),
+            "ls --long --directory dir"
+        );
+
+        // Globs stay unquoted so Nu expands them
+        assert_eq!(
+            converter
+                .convert(&["-lt".to_string(), "*.log".to_string()])
+                .unwrap(),
+            "ls --long *.log | sort-by modified --reverse"
+        );
+
+        // Inode numbers come from the long listing
+        assert_eq!(
+            converter.convert(&["-i".to_string()]).unwrap(),
+            "ls --long | select inode name"
+        );
+        assert_eq!(
+            converter.convert(&["-li".to_string()]).unwrap(),
+            "ls --long | move inode --before name"
+        );
+        assert_eq!(
+            converter
+                .convert(&["-ait".to_string(), "src".to_string()])
+                .unwrap(),
+            "ls --all --long src | sort-by modified --reverse | select inode name"
+        );
+
+        // Single column and type indicators need no flags
+        assert_eq!(
+            converter
+                .convert(&["-1F".to_string(), "src".to_string()])
+                .unwrap(),
+            "ls src"
+        );
+    }
+
+    #[test]
+    fn test_ls_recursive() {
+        let converter = LsConverter;
+
+        assert_eq!(converter.convert(&["-R".to_string()]).unwrap(), "ls **/*");
+        assert_eq!(
+            converter
+                .convert(&["-lR".to_string(), "dir".to_string()])
+                .unwrap(),
+            "ls --long dir/**/*"
+        );
+        assert_eq!(
+            converter
+                .convert(&[
+                    "-a".to_string(),
+                    "--recursive".to_string(),
+                    "src/".to_string()
+                ])
+                .unwrap(),
+            "ls --all src/**/*"
         );
     }
 }
@@ -3,7 +3,9 @@
 //! This module contains individual command converters for translating
 //! POSIX/Unix commands to their Nushell equivalents.
 
+use super::expansion::is_nu_expression;
 use anyhow::Result;
+use std::collections::HashMap;
 
 /// Trait for converting POSIX commands to Nushell syntax
 pub trait CommandConverter {
@@ -19,19 +21,79 @@ fn description(&self) -> &'static str {
     }
 }
 
+/// Marker that starts an advisory note appended to a converted command
+pub const NOTE_MARKER: &str = " # Note: ";
+
 /// Base converter that provides common functionality
 pub struct BaseConverter;
 
 impl BaseConverter {
     /// Quote an argument if it contains spaces or special characters
     pub fn quote_arg(&self, arg: &str) -> String {
-        if arg.contains(' ') || arg.contains('$') || arg.contains('*') || arg.contains('?') {
+        // Single-quoted words are already literal strings in Nushell
+        let single_quoted = arg.len() >= 2 && arg.starts_with('\'') && arg.ends_with('\'');
+        if is_nu_expression(arg) || single_quoted {
+            arg.to_string()
+        } else if arg.contains(' ') || arg.contains('$') || arg.contains('*') || arg.contains('?') {
             format!("\"{}\"", arg.replace('"', "\\\""))
         } else {
             arg.to_string()
         }
     }
 
+    /// Format an advisory `# Note:` comment to append to a conversion
+    ///
+    /// Notes go through here and come last, so the converter can move them
+    /// onto their own line above the statement or strip them when asked.
+    pub fn note(&self, message: &str) -> String {
+        format!("{}{}", NOTE_MARKER, message)
+    }
+
+    /// Pipe `path` into a `path` subcommand such as `path dirname`
+    ///
+    /// A path computed by another path command, as in `dirname $(readlink -f x)`,
+    /// extends that pipeline instead of nesting it.
+    pub fn path_pipeline(&self, path: &str, command: &str) -> String {
+        match path.strip_prefix('(').and_then(|p| p.strip_suffix(')')) {
+            Some(inner) if is_nu_expression(path) && inner.contains(" | path ") => {
+                format!("{} | {}", inner, command)
+            }
+            _ => format!("{} | {}", self.quote_arg(path), command),
+        }
+    }
+
+    /// Rejoin quoted arguments the parser split at their spaces, as in
+    /// `'hello %s\n'` arriving as `'hello` and `%s\n'`, or
+    /// `--header="Accept: json"` as `--header="Accept:` and `json"`
+    pub fn join_quoted_words(&self, args: &[String]) -> Vec<String> {
+        // A word is complete once its first kind of quote is balanced
+        let unbalanced = |word: &str| {
+            word.chars()
+                .find(|c| *c == '\'' || *c == '"')
+                .is_some_and(|quote| {
+                    let escaped = word.matches(&format!("\\{}", quote)).count();
+                    (word.matches(quote).count() - escaped) % 2 == 1
+                })
+        };
+
+        let mut joined = Vec::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut word = arg.clone();
+            while unbalanced(&word) {
+                match args.next() {
+                    Some(next) => {
+                        word.push(' ');
+                        word.push_str(next);
+                    }
+                    None => break,
+                }
+            }
+            joined.push(word);
+        }
+        joined
+    }
+
     /// Format a list of arguments, quoting them as needed
     pub fn format_args(&self, args: &[String]) -> String {
         args.iter()
@@ -55,9 +117,12 @@ pub fn format_args(&self, args: &[String]) -> String {
 pub mod find;
 pub mod grep;
 pub mod head;
+pub mod http;
+pub mod jq;
 pub mod ls;
 pub mod mkdir;
 pub mod mv;
+pub mod printf;
 pub mod ps;
 pub mod realpath;
 pub mod rm;
@@ -83,15 +148,19 @@ pub fn format_args(&self, args: &[String]) -> String {
 pub use cut::CutConverter;
 pub use date::DateConverter;
 pub use dirname::DirnameConverter;
-pub use echo::EchoConverter;
+pub(crate) use echo::echo_string;
+pub use echo::{EchoConverter, EchoStyle};
 pub use find::FindConverter;
 pub use grep::GrepConverter;
 pub use head::HeadConverter;
+pub use http::{CurlConverter, WgetConverter};
+pub use jq::JqConverter;
 pub use ls::LsConverter;
 pub use mkdir::MkdirConverter;
 pub use mv::MvConverter;
+pub use printf::PrintfConverter;
 pub use ps::PsConverter;
-pub use realpath::RealpathConverter;
+pub use realpath::{ReadlinkConverter, RealpathConverter};
 pub use rm::RmConverter;
 pub use rmdir::RmdirConverter;
 pub use sed::SedConverter;
@@ -107,14 +176,14 @@ pub fn format_args(&self, args: &[String]) -> String {
 
 /// Registry of all command converters
 pub struct CommandRegistry {
-    converters: Vec<Box<dyn CommandConverter>>,
+    converters: HashMap<&'static str, Box<dyn CommandConverter>>,
 }
 
 impl CommandRegistry {
     /// Create a new command registry with all standard converters
     pub fn new() -> Self {
         let mut registry = Self {
-            converters: Vec::new(),
+            converters: HashMap::new(),
         };
 
         // Register all standard converters
@@ -124,16 +193,20 @@ pub fn new() -> Self {
         registry.register(Box::new(ChmodConverter));
         registry.register(Box::new(ChownConverter));
         registry.register(Box::new(CpConverter));
+        registry.register(Box::new(CurlConverter));
         registry.register(Box::new(CutConverter));
         registry.register(Box::new(DateConverter));
         registry.register(Box::new(DirnameConverter));
-        registry.register(Box::new(EchoConverter));
+        registry.register(Box::new(EchoConverter::default()));
         registry.register(Box::new(FindConverter));
         registry.register(Box::new(GrepConverter));
         registry.register(Box::new(HeadConverter));
+        registry.register(Box::new(JqConverter));
         registry.register(Box::new(LsConverter));
         registry.register(Box::new(MkdirConverter));
         registry.register(Box::new(MvConverter));
+        registry.register(Box::new(PrintfConverter));
+        registry.register(Box::new(ReadlinkConverter));
         registry.register(Box::new(RealpathConverter));
         registry.register(Box::new(RmConverter));
         registry.register(Box::new(RmdirConverter));
@@ -144,6 +217,7 @@ pub fn new() -> Self {
         registry.register(Box::new(TailConverter));
         registry.register(Box::new(TeeConverter));
         registry.register(Box::new(UniqConverter));
+        registry.register(Box::new(WgetConverter));
         registry.register(Box::new(WcConverter));
         registry.register(Box::new(WhichConverter));
         registry.register(Box::new(WhoamiConverter));
@@ -154,23 +228,19 @@ pub fn new() -> Self {
 
     /// Register a new command converter
     pub fn register(&mut self, converter: Box<dyn CommandConverter>) {
-        self.converters.push(converter);
+        self.converters.insert(converter.command_name(), converter);
     }
 
     /// Find a converter for the given command name
     pub fn find_converter(&self, command: &str) -> Option<&dyn CommandConverter> {
-        self.converters
-            .iter()
-            .find(|conv| conv.command_name() == command)
-            .map(|conv| conv.as_ref())
+        self.converters.get(command).map(|conv| conv.as_ref())
     }
 
     /// Get all registered command names
     pub fn get_command_names(&self) -> Vec<&'static str> {
-        self.converters
-            .iter()
-            .map(|conv| conv.command_name())
-            .collect()
+        let mut names: Vec<&'static str> = self.converters.keys().copied().collect();
+        names.sort_unstable();
+        names
     }
 
     /// Convert a command using the appropriate converter
@@ -218,10 +288,32 @@ fn test_command_registry() {
         assert!(registry.find_converter("which").is_some());
         assert!(registry.find_converter("whoami").is_some());
         assert!(registry.find_converter("ps").is_some());
+        assert!(registry.find_converter("jq").is_some());
+        assert!(registry.find_converter("curl").is_some());
+        assert!(registry.find_converter("wget").is_some());
 
         assert!(registry.find_converter("nonexistent").is_none());
     }
 
+    #[test]
+    fn test_command_registry_lookup() {
+        let registry = CommandRegistry::new();
+
+        // Every registered name resolves to the converter for that name
+        let names = registry.get_command_names();
+        assert!(names.windows(2).all(|pair| pair[0] < pair[1]));
+        for name in names {
+            let converter = registry.find_converter(name).unwrap();
+            assert_eq!(converter.command_name(), name);
+        }
+
+        // Registering a converter under an existing name replaces it
+        let mut registry = CommandRegistry::new();
+        let count = registry.get_command_names().len();
+        registry.register(Box::new(LsConverter));
+        assert_eq!(registry.get_command_names().len(), count);
+    }
+
     #[test]
     fn test_base_converter_quoting() {
         let base = BaseConverter;
@@ -230,6 +322,7 @@ fn test_base_converter_quoting() {
         assert_eq!(base.quote_arg("with space"), "\"with space\"");
         assert_eq!(base.quote_arg("with$var"), "\"with$var\"");
         assert_eq!(base.quote_arg("with*glob"), "\"with*glob\"");
+        assert_eq!(base.quote_arg("($rest | length)"), "($rest | length)");
     }
 
     #[test]
@@ -243,4 +336,27 @@ fn test_format_args() {
 
         assert_eq!(base.format_args(&args), "simple \"with space\" normal");
     }
+
+    #[test]
+    fn test_base_converter_note() {
+        let base = BaseConverter;
+
+        assert_eq!(base.note("uses external ls"), " # Note: uses external ls");
+    }
+
+    #[test]
+    fn test_base_converter_path_pipeline() {
+        let base = BaseConverter;
+
+        assert_eq!(base.path_pipeline("x", "path dirname"), "x | path dirname");
+        assert_eq!(
+            base.path_pipeline("(x | path expand)", "path dirname"),
+            "x | path expand | path dirname"
+        );
+        // Other substitutions stay nested
+        assert_eq!(
+            base.path_pipeline("(pwd)", "path dirname"),
+            "(pwd) | path dirname"
+        );
+    }
 }
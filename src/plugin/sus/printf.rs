@@ -0,0 +1,288 @@
+//! Printf command converter
+//!
+//! Converts POSIX `printf` commands to Nushell `print` with string interpolation
+
+use super::echo::{interpret_escapes, unquote};
+use super::{BaseConverter, CommandConverter};
+use crate::plugin::expansion::is_nu_expression;
+use anyhow::Result;
+
+/// Converter for the `printf` command
+pub struct PrintfConverter;
+
+impl CommandConverter for PrintfConverter {
+    fn convert(&self, args: &[String]) -> Result<String> {
+        let base = BaseConverter;
+        let args = base.join_quoted_words(args);
+
+        // `-v NAME` assigns the formatted text instead of printing it
+        let (variable, args) = match args.as_slice() {
+            [flag, name, rest @ ..] if flag == "-v" => (Some(name), rest),
+            args => (None, args),
+        };
+
+        let Some((format, values)) = args.split_first() else {
+            return Ok("printf".to_string());
+        };
+
+        // A trailing newline maps onto print's own newline
+        let format = unquote(format);
+        let (format, newline) = match format.strip_suffix("\\n") {
+            Some(format) => (format, true),
+            None => (format, false),
+        };
+
+        let mut values = values.iter();
+        let mut text = String::new();
+        let mut chars = format.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '%' if chars.peek() == Some(&'%') => {
+                    chars.next();
+                    text.push('%');
+                }
+                '%' => {
+                    let mut spec = String::new();
+                    while let Some(&next) = chars.peek() {
+                        chars.next();
+                        spec.push(next);
+                        if next.is_ascii_alphabetic() {
+                            break;
+                        }
+                    }
+
+                    // `%b` interprets backslash escapes in its argument
+                    if spec == "b" {
+                        match values.next() {
+                            Some(value) if is_nu_expression(value) => text.push_str(&format!(
+                                "({} | str replace --all '\\t' (char tab) | str replace --all '\\n' (char newline))",
+                                value
+                            )),
+                            Some(value) => {
+                                text.push_str(&interpret_escapes(unquote(value)).replace('(', "\\("))
+                            }
+                            None => {}
+                        }
+                        continue;
+                    }
+
+                    // Missing arguments print as empty strings
+                    let value = values
+                        .next()
+                        .map(|v| operand(v))
+                        .unwrap_or_else(|| "''".to_string());
+                    match convert_spec(&spec, &value) {
+                        Some(expr) => text.push_str(&format!("({})", expr)),
+                        None => {
+                            // The external printf still understands the format
+                            let mut result = format!("^printf {}", base.format_args(args));
+                            result.push_str(
+                                &base.note(&format!("unsupported printf format: %{}", spec)),
+                            );
+                            return Ok(result);
+                        }
+                    }
+                }
+                '"' => text.push_str("\\\""),
+                '(' => text.push_str("\\("),
+                _ => text.push(ch),
+            }
+        }
+
+        if let Some(variable) = variable {
+            if newline {
+                text.push_str("\\n");
+            }
+            return Ok(format!("let {} = $\"{}\"", variable, text));
+        }
+
+        let command = if newline { "print" } else { "print -n" };
+        Ok(format!("{} $\"{}\"", command, text))
+    }
+
+    fn command_name(&self) -> &'static str {
+        "printf"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts printf commands to print with string interpolation"
+    }
+}
+
+/// Format a printf argument as a Nushell expression
+fn operand(value: &str) -> String {
+    if is_nu_expression(value) || value.parse::<f64>().is_ok() {
+        value.to_string()
+    } else {
+        format!("'{}'", unquote(value))
+    }
+}
+
+/// Convert one conversion specification such as `5d`, `-10s` or `.2f`
+///
+/// Flags, width and precision map to `fill` and `math round`; returns
+/// `None` for conversions with no Nushell equivalent.
+fn convert_spec(spec: &str, value: &str) -> Option<String> {
+    let (body, conversion) = spec.split_at(spec.len().checked_sub(1)?);
+    let left_align = body.starts_with('-');
+    let body = body.trim_start_matches('-');
+    let zero_pad = body.starts_with('0') && !left_align;
+
+    let (width, precision) = match body.split_once('.') {
+        Some((width, precision)) => (width, Some(precision.parse::<usize>().ok()?)),
+        None => (body, None),
+    };
+    let width: usize = if width.is_empty() {
+        0
+    } else {
+        width.parse().ok()?
+    };
+
+    let mut expr = match (conversion, precision) {
+        ("s", None) => value.to_string(),
+        ("s", Some(precision)) => format!("{} | str substring 0..<{}", value, precision),
+        ("d" | "i", _) => format!("{} | into int", value),
+        ("f", Some(precision)) => format!(
+            "{} | into float | math round --precision {}",
+            value, precision
+        ),
+        ("f", None) => format!("{} | into float", value),
+        _ => return None,
+    };
+
+    if width > 0 {
+        let alignment = if left_align { "left" } else { "right" };
+        let fill = if zero_pad { " -c '0'" } else { "" };
+        expr.push_str(&format!(" | fill -a {}{} -w {}", alignment, fill, width));
+    }
+
+    Some(expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_printf_converter() {
+        let converter = PrintfConverter;
+
+        assert_eq!(converter.convert(&[]).unwrap(), "printf");
+        assert_eq!(
+            converter.convert(&args(&["hello\\n"])).unwrap(),
+            "print $\"hello\""
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&["%s: %s\\n", "$name", "$value"]))
+                .unwrap(),
+            "print $\"($name): ($value)\""
+        );
+        assert_eq!(
+            converter.convert(&args(&["100%%"])).unwrap(),
+            "print -n $\"100%\""
+        );
+    }
+
+    #[test]
+    fn test_printf_width_and_precision() {
+        let converter = PrintfConverter;
+
+        assert_eq!(
+            converter.convert(&args(&["%5d\\n", "$n"])).unwrap(),
+            "print $\"($n | into int | fill -a right -w 5)\""
+        );
+        assert_eq!(
+            converter.convert(&args(&["%-10s|\\n", "$name"])).unwrap(),
+            "print $\"($name | fill -a left -w 10)|\""
+        );
+        assert_eq!(
+            converter.convert(&args(&["%.2f", "$x"])).unwrap(),
+            "print -n $\"($x | into float | math round --precision 2)\""
+        );
+        assert_eq!(
+            converter.convert(&args(&["%03d", "7"])).unwrap(),
+            "print -n $\"(7 | into int | fill -a right -c '0' -w 3)\""
+        );
+    }
+
+    #[test]
+    fn test_printf_assign_to_variable() {
+        let converter = PrintfConverter;
+
+        assert_eq!(
+            converter.convert(&args(&["-v", "x", "%d", "5"])).unwrap(),
+            "let x = $\"(5 | into int)\""
+        );
+        assert_eq!(
+            converter
+                .convert(&args(&["-v", "line", "%s\\n", "$name"]))
+                .unwrap(),
+            "let line = $\"($name)\\n\""
+        );
+    }
+
+    #[test]
+    fn test_printf_backslash_escapes() {
+        let converter = PrintfConverter;
+
+        assert_eq!(
+            converter.convert(&args(&["%b", "'a\\tb'"])).unwrap(),
+            "print -n $\"a\\tb\""
+        );
+        assert_eq!(
+            converter.convert(&args(&["'%b\\n'", "x\\x41"])).unwrap(),
+            "print $\"x\\u{41}\""
+        );
+        assert_eq!(
+            converter.convert(&args(&["%b", "$msg"])).unwrap(),
+            "print -n $\"($msg | str replace --all '\\t' (char tab) | str replace --all '\\n' (char newline))\""
+        );
+    }
+
+    #[test]
+    fn test_printf_split_format() {
+        use crate::plugin::parser_posix::{parse_posix_script, PosixCommand};
+
+        // The parser splits a quoted format with spaces into several words
+        let convert = |source: &str| {
+            let script = parse_posix_script(source).unwrap();
+            let PosixCommand::Simple(cmd) = &script.commands[0] else {
+                panic!("Expected simple command");
+            };
+            PrintfConverter.convert(&cmd.args).unwrap()
+        };
+
+        assert_eq!(
+            convert("printf 'hello %s\\n' $name"),
+            "print $\"hello ($name)\""
+        );
+        assert_eq!(
+            convert("printf \"%5d|%-10s\\n\" 3 ab"),
+            "print $\"(3 | into int | fill -a right -w 5)|('ab' | fill -a left -w 10)\""
+        );
+        assert_eq!(
+            convert("printf 'total: %.2f\\n' 3.14159"),
+            "print $\"total: (3.14159 | into float | math round --precision 2)\""
+        );
+        assert_eq!(
+            convert("printf '%s and %s\\n' 'x y' \"z\""),
+            "print $\"('x y') and ('z')\""
+        );
+    }
+
+    #[test]
+    fn test_printf_unsupported_format() {
+        let converter = PrintfConverter;
+
+        assert_eq!(
+            converter.convert(&args(&["%x", "255"])).unwrap(),
+            "^printf %x 255 # Note: unsupported printf format: %x"
+        );
+    }
+}
@@ -146,10 +146,7 @@ fn convert(&self, args: &[String]) -> Result<String> {
         }
 
         if !notes.is_empty() {
-            result.push_str(&format!(
-                " # Note: {} not fully supported",
-                notes.join(", ")
-            ));
+            result.push_str(&base.note(&format!("{} not fully supported", notes.join(", "))));
         }
 
         Ok(result)
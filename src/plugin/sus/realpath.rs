@@ -96,7 +96,7 @@ fn convert(&self, args: &[String]) -> Result<String> {
         // Handle single path case
         if paths.len() == 1 {
             let path = &paths[0];
-            let mut result = format!("{} | path expand", base.quote_arg(path));
+            let mut result = base.path_pipeline(path, "path expand");
 
             // Handle relative-to option
             if !relative_to.is_empty() {
@@ -148,6 +148,42 @@ fn description(&self) -> &'static str {
     }
 }
 
+/// Converter for the `readlink` command
+///
+/// `readlink -f`, `-e` and `-m` canonicalize like `realpath`; plain `readlink`
+/// prints a link's target and keeps running externally.
+pub struct ReadlinkConverter;
+
+impl CommandConverter for ReadlinkConverter {
+    fn convert(&self, args: &[String]) -> Result<String> {
+        let canonicalize = args.iter().any(|arg| {
+            arg.starts_with("--canonicalize")
+                || (arg.starts_with('-') && !arg.starts_with("--") && arg.contains(['f', 'e', 'm']))
+        });
+        if !canonicalize {
+            let base = BaseConverter;
+            return Ok(format!("readlink {}", base.format_args(args))
+                .trim_end()
+                .to_string());
+        }
+
+        let paths: Vec<String> = args
+            .iter()
+            .filter(|arg| !arg.starts_with('-'))
+            .cloned()
+            .collect();
+        RealpathConverter.convert(&paths)
+    }
+
+    fn command_name(&self) -> &'static str {
+        "readlink"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts canonicalizing readlink commands to Nushell path expand"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -245,4 +281,20 @@ fn test_realpath_converter() {
             "\"/path/to/file.txt\" | path expand"
         );
     }
+
+    #[test]
+    fn test_readlink_converter() {
+        let converter = ReadlinkConverter;
+
+        assert_eq!(
+            converter
+                .convert(&["-f".to_string(), "link".to_string()])
+                .unwrap(),
+            "link | path expand"
+        );
+        assert_eq!(
+            converter.convert(&["link".to_string()]).unwrap(),
+            "readlink link"
+        );
+    }
 }
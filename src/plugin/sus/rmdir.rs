@@ -65,7 +65,7 @@ fn convert(&self, args: &[String]) -> Result<String> {
 
         // Add note about empty directory requirement
         if !ignore_fail_on_non_empty {
-            result.push_str(" # Note: rmdir only removes empty directories");
+            result.push_str(&base.note("rmdir only removes empty directories"));
         }
 
         Ok(result)
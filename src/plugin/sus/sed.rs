@@ -2,6 +2,7 @@
 //!
 //! Converts POSIX `sed` commands to Nushell string operations
 
+use super::echo::unquote;
 use super::{BaseConverter, CommandConverter};
 use anyhow::Result;
 
@@ -27,6 +28,7 @@ fn convert(&self, args: &[String]) -> Result<String> {
         // TODO: line_length variable is not used in current implementation
         let mut _line_length: Option<usize> = None;
         let mut backup_suffix = String::new();
+        let mut script_files = Vec::new();
 
         let mut i = 0;
         while i < args.len() {
@@ -36,7 +38,7 @@ fn convert(&self, args: &[String]) -> Result<String> {
                         if !script.is_empty() {
                             script.push(';');
                         }
-                        script.push_str(&args[i + 1]);
+                        script.push_str(unquote(&args[i + 1]));
                         i += 2;
                     } else {
                         i += 1;
@@ -44,8 +46,21 @@ fn convert(&self, args: &[String]) -> Result<String> {
                 }
                 "-f" | "--file" => {
                     if i + 1 < args.len() {
-                        // Script from file - would need special handling
-                        script.push_str(&format!("# script from file: {}", args[i + 1]));
+                        // Read the script now if it exists relative to the CWD
+                        match std::fs::read_to_string(&args[i + 1]) {
+                            Ok(contents) => {
+                                for line in contents.lines().map(str::trim) {
+                                    if line.is_empty() || line.starts_with('#') {
+                                        continue;
+                                    }
+                                    if !script.is_empty() {
+                                        script.push(';');
+                                    }
+                                    script.push_str(line);
+                                }
+                            }
+                            Err(_) => script_files.push(args[i + 1].clone()),
+                        }
                         i += 2;
                     } else {
                         i += 1;
@@ -82,8 +97,8 @@ fn convert(&self, args: &[String]) -> Result<String> {
                     i += 1;
                 }
                 arg if !arg.starts_with('-') => {
-                    if script.is_empty() {
-                        script = arg.to_string();
+                    if script.is_empty() && script_files.is_empty() {
+                        script = unquote(arg).to_string();
                     } else {
                         files.push(arg.to_string());
                     }
@@ -96,13 +111,37 @@ fn convert(&self, args: &[String]) -> Result<String> {
             }
         }
 
-        if script.is_empty() {
+        if script.is_empty() && script_files.is_empty() {
             return Ok("sed".to_string());
         }
 
+        // Scripts that could not be read are left to the external sed
+        if !script_files.is_empty() {
+            return Ok(format!(
+                "^sed {}{}",
+                base.format_args(args),
+                base.note(&format!(
+                    "sed script {} not readable at conversion time",
+                    script_files.join(", ")
+                ))
+            ));
+        }
+
         // Parse the sed script into individual commands
         let commands = parse_sed_script(&script);
 
+        // Ranges that start or end at a regex match are left to the external sed
+        if commands.iter().any(|command| {
+            address_range(&command.address)
+                .is_some_and(|(start, end)| start.starts_with('/') || end.starts_with('/'))
+        }) {
+            return Ok(format!(
+                "^sed {}{}",
+                base.format_args(args),
+                base.note("sed address ranges with regex ends have no Nushell equivalent")
+            ));
+        }
+
         // Build the Nushell command
         let mut result = String::new();
 
@@ -131,10 +170,18 @@ fn convert(&self, args: &[String]) -> Result<String> {
             }
         }
 
-        // Convert sed commands to Nu operations
+        // Convert sed commands to Nu operations, composing consecutive
+        // per-line edits into a single `each` closure so they apply in order
+        let mut transforms: Vec<String> = Vec::new();
         for command in commands {
+            if let Some(transform) = line_transform(&command, &base) {
+                transforms.push(transform);
+                continue;
+            }
+            push_line_transforms(&mut result, &mut transforms);
             result.push_str(&convert_sed_command_to_nu(&command, &base)?);
         }
+        push_line_transforms(&mut result, &mut transforms);
 
         // Handle quiet mode
         if quiet {
@@ -230,14 +277,19 @@ fn parse_single_sed_command(command_str: &str) -> Option<SedCommand> {
     let mut arguments = String::new();
     let mut found_command = false;
 
-    for (i, ch) in trimmed.chars().enumerate() {
+    // A regex address may contain command letters, so take the address whole
+    let end = address_end(trimmed);
+    address.push_str(&trimmed[..end]);
+    let rest = &trimmed[end..];
+
+    for (i, ch) in rest.chars().enumerate() {
         if !found_command {
             match ch {
                 's' | 'd' | 'p' | 'q' | 'n' | 'N' | 'h' | 'H' | 'g' | 'G' | 'x' | 'l' | '='
                 | 'a' | 'i' | 'c' | 'r' | 'w' | 'y' | 'b' | 't' | 'T' => {
                     command_char = ch;
                     found_command = true;
-                    arguments = trimmed[i + 1..].to_string();
+                    arguments = rest[i + 1..].to_string();
                     break;
                 }
                 _ => {
@@ -258,10 +310,110 @@ fn parse_single_sed_command(command_str: &str) -> Option<SedCommand> {
     }
 }
 
+/// Byte length of a leading address or `ADDR,ADDR` range, including a trailing `!`
+fn address_end(command: &str) -> usize {
+    let mut end = single_address_end(command);
+    if end > 0 && command[end..].starts_with(',') {
+        end += 1 + single_address_end(&command[end + 1..]);
+    }
+    if command[end..].starts_with('!') {
+        end += 1;
+    }
+    end
+}
+
+/// Split an `ADDR,ADDR` range into its start and end addresses
+fn address_range(address: &str) -> Option<(&str, &str)> {
+    let end = single_address_end(address);
+    let rest = address[end..].strip_prefix(',')?;
+    (end > 0).then_some((&address[..end], rest))
+}
+
+/// Byte length of a leading line number, `$` or `/regex/` address
+fn single_address_end(command: &str) -> usize {
+    if command.starts_with('$') {
+        return 1;
+    }
+    let mut chars = command.char_indices();
+    if chars.next().map(|(_, ch)| ch) != Some('/') {
+        return command
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(command.len());
+    }
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '\\' => {
+                chars.next();
+            }
+            '/' => return i + 1,
+            _ => {}
+        }
+    }
+    0
+}
+
+/// Convert an unaddressed `s` or `y` command to a per-line string operation
+fn line_transform(command: &SedCommand, base: &BaseConverter) -> Option<String> {
+    if !command.address.is_empty() {
+        return None;
+    }
+
+    match command.command {
+        's' => {
+            let subst = parse_substitute_command(&command.arguments)?;
+            Some(format!(
+                "str replace{} {} {}",
+                if subst.global { " --all" } else { "" },
+                base.quote_arg(&subst.pattern),
+                base.quote_arg(&subst.replacement)
+            ))
+        }
+        'y' => {
+            let trans = parse_transliterate_command(&command.arguments)?;
+            Some(format!(
+                "str replace --all {} {}",
+                base.quote_arg(&trans.from),
+                base.quote_arg(&trans.to)
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Flush pending per-line operations as one `each` stage
+fn push_line_transforms(result: &mut String, transforms: &mut Vec<String>) {
+    if transforms.is_empty() {
+        return;
+    }
+    result.push_str(&format!(
+        " | each {{ |line| $line | {} }}",
+        transforms.join(" | ")
+    ));
+    transforms.clear();
+}
+
 /// Convert sed command to Nu operations
 fn convert_sed_command_to_nu(command: &SedCommand, base: &BaseConverter) -> Result<String> {
     let mut result = String::new();
 
+    // Deleting lines at a regex address keeps the others; `!` keeps the matches
+    if command.command == 'd' {
+        if let Some(pattern) = command
+            .address
+            .strip_prefix('/')
+            .and_then(|addr| addr.strip_suffix('/'))
+        {
+            return Ok(format!(" | where $it !~ {}", base.quote_arg(pattern)));
+        }
+        if let Some(pattern) = command
+            .address
+            .strip_prefix('/')
+            .and_then(|addr| addr.strip_suffix("/!"))
+        {
+            return Ok(format!(" | where $it =~ {}", base.quote_arg(pattern)));
+        }
+    }
+
     // Handle address (line selection)
     if !command.address.is_empty() {
         match command.address.as_str() {
@@ -568,6 +720,108 @@ fn test_sed_converter() {
         );
     }
 
+    #[test]
+    fn test_sed_expressions_compose() {
+        let converter = SedConverter;
+
+        // Each -e applies to the line in order within a single closure
+        assert_eq!(
+            converter
+                .convert(&[
+                    "-e".to_string(),
+                    "s/a/b/".to_string(),
+                    "-e".to_string(),
+                    "s/b/c/".to_string(),
+                ])
+                .unwrap(),
+            "lines | each { |line| $line | str replace a b | str replace b c }"
+        );
+
+        // Global substitutions replace every match
+        assert_eq!(
+            converter
+                .convert(&["-e".to_string(), "s/x/y/g".to_string()])
+                .unwrap(),
+            "lines | each { |line| $line | str replace --all x y }"
+        );
+    }
+
+    #[test]
+    fn test_sed_regex_delete() {
+        let converter = SedConverter;
+
+        assert_eq!(
+            converter.convert(&["/foo/d".to_string()]).unwrap(),
+            "lines | where $it !~ foo"
+        );
+        assert_eq!(
+            converter.convert(&["/foo/!d".to_string()]).unwrap(),
+            "lines | where $it =~ foo"
+        );
+
+        // Command letters inside the regex belong to the address
+        assert_eq!(
+            converter
+                .convert(&["'/bar/d'".to_string(), "f".to_string()])
+                .unwrap(),
+            "open f | lines | where $it !~ bar"
+        );
+
+        // A comma inside the regex doesn't make a range
+        assert_eq!(
+            converter
+                .convert(&["'/a,b/d'".to_string(), "f".to_string()])
+                .unwrap(),
+            "open f | lines | where $it !~ a,b"
+        );
+
+        // Ranges ending at a regex match run the external sed
+        assert_eq!(
+            converter
+                .convert(&["'/foo/,/bar/d'".to_string(), "f".to_string()])
+                .unwrap(),
+            "^sed '/foo/,/bar/d' f # Note: sed address ranges with regex ends have no Nushell equivalent"
+        );
+        assert_eq!(
+            converter
+                .convert(&["'2,/bar/d'".to_string(), "f".to_string()])
+                .unwrap(),
+            "^sed '2,/bar/d' f # Note: sed address ranges with regex ends have no Nushell equivalent"
+        );
+    }
+
+    #[test]
+    fn test_sed_script_file() {
+        let converter = SedConverter;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("swap.sed");
+        std::fs::write(&path, "# swap letters\ns/a/b/\n").unwrap();
+
+        let result = converter
+            .convert(&[
+                "-f".to_string(),
+                path.to_string_lossy().to_string(),
+                "input.txt".to_string(),
+            ])
+            .unwrap();
+        assert_eq!(
+            result,
+            "open input.txt | lines | each { |line| $line | str replace a b }"
+        );
+
+        // A missing script runs the external sed
+        assert_eq!(
+            converter
+                .convert(&[
+                    "-f".to_string(),
+                    "missing.sed".to_string(),
+                    "input.txt".to_string()
+                ])
+                .unwrap(),
+            "^sed -f missing.sed input.txt # Note: sed script missing.sed not readable at conversion time"
+        );
+    }
+
     #[test]
     fn test_parse_sed_script() {
         let commands = parse_sed_script("s/old/new/;d;p");
@@ -122,11 +122,19 @@ fn convert(&self, args: &[String]) -> Result<String> {
 
         // Handle formatting options
         if !format.is_empty() {
-            // Format string handling - simplified for common patterns
-            if format.contains("g") || format.contains("f") || format.contains("e") {
-                result.push_str(&format!(" | each {{ |n| $n | format {format} }}"));
-            } else {
-                result.push_str(&format!(" | each {{ |n| $n | format \"{format}\" }}"));
+            match convert_format(&format) {
+                Some(transform) => result.push_str(&transform),
+                // The external seq still understands the format
+                None => {
+                    return Ok(format!(
+                        "^seq {}{}",
+                        base.format_args(args),
+                        base.note(&format!(
+                            "unsupported seq format: {}",
+                            format.trim_matches(|c| c == '"' || c == '\'')
+                        ))
+                    ))
+                }
             }
         } else if equal_width {
             // Equal width formatting
@@ -159,6 +167,50 @@ fn description(&self) -> &'static str {
     }
 }
 
+/// Convert a `seq -f` printf format into an `each` transformation
+///
+/// Simple integer formats such as `%03g` or `file%2d.txt` become `fill`
+/// calls; anything else returns `None` so it can be noted in a comment.
+fn convert_format(format: &str) -> Option<String> {
+    let format = format.trim_matches(|c| c == '"' || c == '\'');
+
+    let (prefix, spec) = format.split_once('%')?;
+    let zero_pad = spec.starts_with('0');
+    let width_len = spec
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(spec.len());
+    let width: usize = spec[..width_len].parse().unwrap_or(0);
+    let rest = &spec[width_len..];
+
+    let suffix = match rest.strip_prefix(['g', 'd', 'i']) {
+        Some(suffix) if !prefix.contains('%') && !suffix.contains('%') => suffix,
+        _ => return None,
+    };
+
+    let value = if width > 0 {
+        let fill = if zero_pad { '0' } else { ' ' };
+        format!("$n | fill -a right -c '{}' -w {}", fill, width)
+    } else {
+        "$n".to_string()
+    };
+
+    if prefix.is_empty() && suffix.is_empty() {
+        if width == 0 {
+            // A plain format prints the numbers unchanged
+            return Some(String::new());
+        }
+        return Some(format!(" | each {{ |n| {} }}", value));
+    }
+
+    let escape = |text: &str| text.replace('"', "\\\"").replace('(', "\\(");
+    Some(format!(
+        " | each {{ |n| $\"{}({}){}\" }}",
+        escape(prefix),
+        value,
+        escape(suffix)
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -227,6 +279,47 @@ fn test_seq_converter() {
             "8..12 | each { |n| $n | into string }"
         );
 
+        // Zero-padded format
+        assert_eq!(
+            converter
+                .convert(&[
+                    "-f".to_string(),
+                    "%03g".to_string(),
+                    "1".to_string(),
+                    "5".to_string()
+                ])
+                .unwrap(),
+            "1..5 | each { |n| $n | fill -a right -c '0' -w 3 }"
+        );
+
+        // Format with surrounding text
+        assert_eq!(
+            converter
+                .convert(&[
+                    "-f".to_string(),
+                    "\"file%02d.txt\"".to_string(),
+                    "3".to_string()
+                ])
+                .unwrap(),
+            "1..3 | each { |n| $\"file($n | fill -a right -c '0' -w 2).txt\" }"
+        );
+
+        // Plain format leaves the numbers unchanged
+        assert_eq!(
+            converter
+                .convert(&["-f".to_string(), "%g".to_string(), "3".to_string()])
+                .unwrap(),
+            "1..3"
+        );
+
+        // Complex formats run the external seq with a note
+        assert_eq!(
+            converter
+                .convert(&["-f".to_string(), "%.2f".to_string(), "3".to_string()])
+                .unwrap(),
+            "^seq -f %.2f 3 # Note: unsupported seq format: %.2f"
+        );
+
         // Invalid arguments
         assert_eq!(
             converter.convert(&["invalid".to_string()]).unwrap(),
@@ -1,6 +1,9 @@
 //! Sort command converter
 //!
 //! Converts POSIX `sort` commands to Nushell `sort` commands
+//!
+//! Nushell's `sort` and `sort-by` are always stable, so `sort -s` needs no flag.
+//! `sort -c` becomes a boolean comparing the lines with their sorted order.
 
 use super::{BaseConverter, CommandConverter};
 use anyhow::Result;
@@ -20,6 +23,7 @@ fn convert(&self, args: &[String]) -> Result<String> {
         let mut numeric = false;
         let mut unique = false;
         let mut ignore_case = false;
+        let mut check = false;
         let mut key_field = String::new();
         let mut field_separator = String::new();
         let mut output_file = String::new();
@@ -30,12 +34,31 @@ fn convert(&self, args: &[String]) -> Result<String> {
             let arg = &args[i];
             if arg.starts_with('-') && arg.len() > 1 && !arg.starts_with("--") {
                 // Handle combined flags like -ru
-                for ch in arg.chars().skip(1) {
+                for (pos, ch) in arg.char_indices().skip(1) {
+                    // Options taking a value use the rest of the word or the next argument
+                    if matches!(ch, 'k' | 't') {
+                        let attached = &arg[pos + 1..];
+                        let value = if attached.is_empty() {
+                            i += 1;
+                            args.get(i).cloned().unwrap_or_default()
+                        } else {
+                            attached.to_string()
+                        };
+                        if ch == 'k' {
+                            key_field = value;
+                        } else {
+                            field_separator = value;
+                        }
+                        break;
+                    }
                     match ch {
                         'r' => reverse = true,
                         'n' => numeric = true,
                         'u' => unique = true,
                         'f' => ignore_case = true,
+                        'c' => check = true,
+                        // Stable sorting is Nushell's default
+                        's' => {}
                         'o' => {
                             // Output flag in combined form
                             if i + 1 < args.len() {
@@ -60,6 +83,12 @@ fn convert(&self, args: &[String]) -> Result<String> {
                     "-f" | "--ignore-case" => {
                         ignore_case = true;
                     }
+                    "-c" | "--check" => {
+                        check = true;
+                    }
+                    "-s" | "--stable" => {
+                        // Stable sorting is Nushell's default
+                    }
                     "-k" | "--key" => {
                         // Key field specification
                         if i + 1 < args.len() {
@@ -92,6 +121,28 @@ fn convert(&self, args: &[String]) -> Result<String> {
             i += 1;
         }
 
+        // Checking compares the input with its sorted order instead of sorting
+        if check {
+            let input = if files.is_empty() {
+                String::new()
+            } else {
+                format!("open {} | lines | ", base.format_args(&files))
+            };
+            let values = if numeric {
+                "each { |line| $line | into int } | "
+            } else {
+                ""
+            };
+            let mut sorted = String::from("sort");
+            if reverse {
+                sorted.push_str(" --reverse");
+            }
+            if ignore_case {
+                sorted.push_str(" --ignore-case");
+            }
+            return Ok(format!("{}{}$in == ($in | {})", input, values, sorted));
+        }
+
         let mut result = String::new();
 
         // Handle input files
@@ -110,7 +161,11 @@ fn convert(&self, args: &[String]) -> Result<String> {
                     field_separator, key_field
                 ));
             } else {
-                result.push_str(&format!("lines | sort-by {}", key_field));
+                // Without a separator, fields are split at runs of blanks
+                result.push_str(&format!(
+                    "lines | split column -r '\\s+' | sort-by column{}",
+                    key_field
+                ));
             }
         } else {
             result.push_str("lines | sort");
@@ -242,4 +297,73 @@ fn test_sort_complex() {
         assert_eq!(converter.convert(&["-nr".to_string(), "numbers.txt".to_string()]).unwrap(),
             "open numbers.txt | lines | where ($it | str trim | is-empty | not) | each { |line| $line | into int } | sort --reverse");
     }
+
+    #[test]
+    fn test_sort_stable() {
+        let converter = SortConverter;
+
+        assert_eq!(
+            converter
+                .convert(&["-s".to_string(), "file.txt".to_string()])
+                .unwrap(),
+            "open file.txt | lines | sort"
+        );
+        assert_eq!(
+            converter
+                .convert(&[
+                    "--stable".to_string(),
+                    "-r".to_string(),
+                    "file.txt".to_string()
+                ])
+                .unwrap(),
+            "open file.txt | lines | sort --reverse"
+        );
+
+        // Combined with a key, stability carries over to sort-by
+        assert_eq!(
+            converter
+                .convert(&[
+                    "-s".to_string(),
+                    "-t".to_string(),
+                    ",".to_string(),
+                    "-k".to_string(),
+                    "2".to_string(),
+                    "data.csv".to_string()
+                ])
+                .unwrap(),
+            "open data.csv | lines | split column ',' | sort-by column2"
+        );
+        assert_eq!(
+            converter
+                .convert(&["-s".to_string(), "-k2".to_string(), "file".to_string()])
+                .unwrap(),
+            "open file | lines | split column -r '\\s+' | sort-by column2"
+        );
+    }
+
+    #[test]
+    fn test_sort_check() {
+        let converter = SortConverter;
+
+        assert_eq!(
+            converter
+                .convert(&["-c".to_string(), "file.txt".to_string()])
+                .unwrap(),
+            "open file.txt | lines | $in == ($in | sort)"
+        );
+        assert_eq!(
+            converter
+                .convert(&[
+                    "--check".to_string(),
+                    "-r".to_string(),
+                    "file.txt".to_string()
+                ])
+                .unwrap(),
+            "open file.txt | lines | $in == ($in | sort --reverse)"
+        );
+        assert_eq!(
+            converter.convert(&["-cn".to_string()]).unwrap(),
+            "each { |line| $line | into int } | $in == ($in | sort)"
+        );
+    }
 }
@@ -1,6 +1,7 @@
 //! Tee command converter
 //!
-//! Converts POSIX `tee` commands to Nushell tee operations
+//! Converts POSIX `tee` commands to Nushell tee operations, saving the
+//! stream to each file from a `tee` closure
 
 use super::{BaseConverter, CommandConverter};
 use anyhow::Result;
@@ -50,43 +51,41 @@ fn convert(&self, args: &[String]) -> Result<String> {
             }
         }
 
-        if files.is_empty() {
-            return Ok("tee".to_string());
-        }
-
-        // Handle single file case
-        if files.len() == 1 {
-            let file = &files[0];
-            let result = if append {
-                format!("tee -a {}", base.quote_arg(file))
-            } else {
-                format!("tee {}", base.quote_arg(file))
-            };
-            return Ok(result);
+        // Targets that aren't files get their own stages or notes
+        let to_stderr = files.iter().any(|f| f == "/dev/stderr");
+        let (processes, files): (Vec<String>, Vec<String>) = files
+            .into_iter()
+            .filter(|f| f != "/dev/stderr")
+            .partition(|f| f.starts_with(">(") && f.ends_with(')'));
+
+        // Each file saves its own copy of the stream
+        let mut stages: Vec<String> = files
+            .iter()
+            .map(|f| {
+                format!(
+                    "tee {{ save {}{} }}",
+                    if append { "--append " } else { "" },
+                    base.quote_arg(f)
+                )
+            })
+            .collect();
+        if to_stderr {
+            stages.push("tee { print --stderr }".to_string());
         }
 
-        // Handle multiple files - use multiple tee commands
-        let mut result = String::new();
-
-        // For multiple files, we need to split the stream
-        if append {
-            result = format!(
-                "tee -a {}",
-                files
-                    .iter()
-                    .map(|f| base.quote_arg(f))
-                    .collect::<Vec<_>>()
-                    .join(" ")
-            );
+        let mut result = if stages.is_empty() {
+            "tee".to_string()
         } else {
-            result = format!(
-                "tee {}",
-                files
-                    .iter()
-                    .map(|f| base.quote_arg(f))
-                    .collect::<Vec<_>>()
-                    .join(" ")
-            );
+            stages.join(" | ")
+        };
+        // `>(cmd)` receives a copy of the stream, as a tee closure does
+        for process in &processes {
+            let command = &process[2..process.len() - 1];
+            if result == "tee" {
+                result = format!("tee {{ {} }}", command);
+            } else {
+                result.push_str(&format!(" | tee {{ {} }}", command));
+            }
         }
 
         Ok(result)
@@ -115,7 +114,7 @@ fn test_tee_converter() {
         // Single file
         assert_eq!(
             converter.convert(&["output.txt".to_string()]).unwrap(),
-            "tee output.txt"
+            "tee { save output.txt }"
         );
 
         // Single file with append
@@ -123,7 +122,7 @@ fn test_tee_converter() {
             converter
                 .convert(&["-a".to_string(), "output.txt".to_string()])
                 .unwrap(),
-            "tee -a output.txt"
+            "tee { save --append output.txt }"
         );
 
         // Multiple files
@@ -131,7 +130,7 @@ fn test_tee_converter() {
             converter
                 .convert(&["file1.txt".to_string(), "file2.txt".to_string()])
                 .unwrap(),
-            "tee file1.txt file2.txt"
+            "tee { save file1.txt } | tee { save file2.txt }"
         );
 
         // Multiple files with append
@@ -143,7 +142,7 @@ fn test_tee_converter() {
                     "file2.txt".to_string()
                 ])
                 .unwrap(),
-            "tee -a file1.txt file2.txt"
+            "tee { save --append file1.txt } | tee { save --append file2.txt }"
         );
 
         // File with spaces
@@ -151,7 +150,7 @@ fn test_tee_converter() {
             converter
                 .convert(&["file with spaces.txt".to_string()])
                 .unwrap(),
-            "tee \"file with spaces.txt\""
+            "tee { save \"file with spaces.txt\" }"
         );
 
         // Ignore interrupts flag
@@ -159,7 +158,7 @@ fn test_tee_converter() {
             converter
                 .convert(&["-i".to_string(), "output.txt".to_string()])
                 .unwrap(),
-            "tee output.txt"
+            "tee { save output.txt }"
         );
 
         // Combined flags
@@ -167,7 +166,7 @@ fn test_tee_converter() {
             converter
                 .convert(&["-a".to_string(), "-i".to_string(), "output.txt".to_string()])
                 .unwrap(),
-            "tee -a output.txt"
+            "tee { save --append output.txt }"
         );
 
         // Long form flags
@@ -175,7 +174,7 @@ fn test_tee_converter() {
             converter
                 .convert(&["--append".to_string(), "output.txt".to_string()])
                 .unwrap(),
-            "tee -a output.txt"
+            "tee { save --append output.txt }"
         );
 
         // Ignore interrupts long form
@@ -183,7 +182,36 @@ fn test_tee_converter() {
             converter
                 .convert(&["--ignore-interrupts".to_string(), "output.txt".to_string()])
                 .unwrap(),
-            "tee output.txt"
+            "tee { save output.txt }"
+        );
+    }
+
+    #[test]
+    fn test_tee_special_targets() {
+        let converter = TeeConverter;
+
+        assert_eq!(
+            converter.convert(&["/dev/stderr".to_string()]).unwrap(),
+            "tee { print --stderr }"
+        );
+
+        // A file and stderr together
+        assert_eq!(
+            converter
+                .convert(&["out.log".to_string(), "/dev/stderr".to_string()])
+                .unwrap(),
+            "tee { save out.log } | tee { print --stderr }"
+        );
+
+        assert_eq!(
+            converter.convert(&[">(gzip)".to_string()]).unwrap(),
+            "tee { gzip }"
+        );
+        assert_eq!(
+            converter
+                .convert(&["out.log".to_string(), ">(lines | length)".to_string()])
+                .unwrap(),
+            "tee { save out.log } | tee { lines | length }"
         );
     }
 }
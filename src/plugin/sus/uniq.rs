@@ -86,8 +86,11 @@ fn convert(&self, args: &[String]) -> Result<String> {
 
         // Basic uniq operation
         if count {
-            // Count occurrences
-            result.push_str("lines | group-by | transpose key count | select key count");
+            // uniq only counts adjacent duplicates, so count each run of equal
+            // lines rather than grouping every occurrence
+            result.push_str(
+                "lines | chunk-by { $in } | each { |run| {count: ($run | length), value: ($run | first)} }",
+            );
         } else if duplicates_only {
             // Only show duplicated lines
             result
@@ -102,22 +105,17 @@ fn convert(&self, args: &[String]) -> Result<String> {
         }
 
         // Handle field/character skipping (basic implementation)
+        let mut notes = Vec::new();
         if !skip_fields.is_empty() {
-            result.push_str(&format!(
-                " # Note: skip-fields {} not fully supported",
-                skip_fields
-            ));
+            notes.push(format!("skip-fields {} not fully supported", skip_fields));
         }
         if !skip_chars.is_empty() {
-            result.push_str(&format!(
-                " # Note: skip-chars {} not fully supported",
-                skip_chars
-            ));
+            notes.push(format!("skip-chars {} not fully supported", skip_chars));
         }
 
         // Handle case sensitivity
         if ignore_case {
-            result.push_str(" # Note: ignore-case not directly supported");
+            notes.push("ignore-case not directly supported".to_string());
         }
 
         // Handle output file
@@ -138,6 +136,11 @@ fn convert(&self, args: &[String]) -> Result<String> {
             result = "uniq".to_string();
         }
 
+        // Notes go last, after any `save` stage
+        for note in notes {
+            result.push_str(&base.note(&note));
+        }
+
         Ok(result)
     }
 
@@ -172,7 +175,7 @@ fn test_uniq_converter() {
             converter
                 .convert(&["-c".to_string(), "file.txt".to_string()])
                 .unwrap(),
-            "open file.txt | lines | group-by | transpose key count | select key count"
+            "open file.txt | lines | chunk-by { $in } | each { |run| {count: ($run | length), value: ($run | first)} }"
         );
 
         // Uniq duplicates only
@@ -200,6 +203,19 @@ fn test_uniq_converter() {
         );
     }
 
+    #[test]
+    fn test_uniq_count_adjacent() {
+        let converter = UniqConverter;
+
+        // Each run of equal lines is counted separately, as in `a a b a` -> 2 a, 1 b, 1 a
+        let result = converter.convert(&["-c".to_string()]).unwrap();
+        assert_eq!(
+            result,
+            "chunk-by { $in } | each { |run| {count: ($run | length), value: ($run | first)} }"
+        );
+        assert!(!result.contains("group-by"));
+    }
+
     #[test]
     fn test_uniq_complex() {
         let converter = UniqConverter;
@@ -209,7 +225,7 @@ fn test_uniq_complex() {
             converter
                 .convert(&["-ci".to_string(), "file.txt".to_string()])
                 .unwrap(),
-            "open file.txt | lines | group-by | transpose key count | select key count # Note: ignore-case not directly supported"
+            "open file.txt | lines | chunk-by { $in } | each { |run| {count: ($run | length), value: ($run | first)} } # Note: ignore-case not directly supported"
         );
 
         // Skip fields
@@ -219,5 +235,17 @@ fn test_uniq_complex() {
                 .unwrap(),
             "open file.txt | lines | uniq # Note: skip-fields 2 not fully supported"
         );
+
+        // Notes come after the output stage
+        assert_eq!(
+            converter
+                .convert(&[
+                    "-i".to_string(),
+                    "in.txt".to_string(),
+                    "out.txt".to_string()
+                ])
+                .unwrap(),
+            "open in.txt | lines | uniq | save out.txt # Note: ignore-case not directly supported"
+        );
     }
 }
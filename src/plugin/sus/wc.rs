@@ -118,23 +118,35 @@ fn convert(&self, args: &[String]) -> Result<String> {
                 }
             }
         } else {
-            // Multiple files - complex case
-            let mut result = String::new();
-            for (i, file) in files.iter().enumerate() {
-                if i > 0 {
-                    result.push_str("; ");
-                }
-                if operations.len() == 1 {
-                    result.push_str(&format!(
-                        "open --raw {} | {}",
-                        base.quote_arg(file),
-                        operations[0]
-                    ));
-                } else {
-                    result.push_str(&format!("open --raw {} | wc", base.quote_arg(file)));
-                }
+            // Multiple files get a record per file plus a total row, like wc's table
+            let mut columns = Vec::new();
+            if count_lines {
+                columns.push(("lines", "lines | length"));
+            }
+            if count_words {
+                columns.push(("words", "split words | length"));
+            }
+            if count_chars {
+                columns.push(("chars", "str length"));
+            }
+            if count_bytes {
+                columns.push(("bytes", "str length"));
             }
-            Ok(result)
+
+            let counts: Vec<String> = columns
+                .iter()
+                .map(|(name, operation)| format!("{}: ($in | {})", name, operation))
+                .collect();
+            let totals: Vec<String> = columns
+                .iter()
+                .map(|(name, _)| format!("{}: ($in | get {} | math sum)", name, name))
+                .collect();
+            Ok(format!(
+                "[{}] | each {{ |file| open --raw $file | {{file: $file, {}}} }} | append {{file: 'total', {}}}",
+                base.format_args(&files),
+                counts.join(", "),
+                totals.join(", ")
+            ))
         }
     }
 
@@ -211,13 +223,21 @@ fn test_wc_converter() {
         // Multiple files
         assert_eq!(
             converter
-                .convert(&[
-                    "-l".to_string(),
-                    "file1.txt".to_string(),
-                    "file2.txt".to_string()
-                ])
+                .convert(&["-l".to_string(), "a".to_string(), "b".to_string()])
+                .unwrap(),
+            "[a b] | each { |file| open --raw $file | {file: $file, lines: ($in | lines | length)} } | append {file: 'total', lines: ($in | get lines | math sum)}"
+        );
+    }
+
+    #[test]
+    fn test_wc_multiple_files_default_counts() {
+        let converter = WcConverter;
+
+        assert_eq!(
+            converter
+                .convert(&["a".to_string(), "b".to_string()])
                 .unwrap(),
-            "open --raw \"file1.txt\" | lines | length; open --raw \"file2.txt\" | lines | length"
+            "[a b] | each { |file| open --raw $file | {file: $file, lines: ($in | lines | length), words: ($in | split words | length), chars: ($in | str length)} } | append {file: 'total', lines: ($in | get lines | math sum), words: ($in | get words | math sum), chars: ($in | get chars | math sum)}"
         );
     }
 }
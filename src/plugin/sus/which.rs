@@ -22,20 +22,22 @@ fn convert(&self, args: &[String]) -> Result<String> {
 
         for arg in args {
             match arg.as_str() {
-                "-a" | "--all" => {
-                    all = true;
-                }
-                "-s" | "--silent" => {
-                    silent = true;
-                }
-                "-v" | "--version" => {
-                    // Version info - skip for now
-                }
-                "-h" | "--help" => {
-                    // Help info - skip for now
+                "--all" => all = true,
+                "--silent" => silent = true,
+                "--version" | "--help" => {
+                    // Version and help info - skip for now
                 }
                 arg if arg.starts_with('-') => {
-                    // Unknown flag, skip
+                    // Short flags may be combined, e.g. `-as`
+                    for flag in arg[1..].chars() {
+                        match flag {
+                            'a' => all = true,
+                            's' => silent = true,
+                            _ => {
+                                // Unknown flag, skip
+                            }
+                        }
+                    }
                 }
                 _ => {
                     commands.push(arg.to_string());
@@ -47,38 +49,13 @@ fn convert(&self, args: &[String]) -> Result<String> {
             return Ok("which".to_string());
         }
 
-        let mut result = String::new();
-
-        if commands.len() == 1 {
-            // Single command
-            let command = &commands[0];
-            if all {
-                // Show all matches
-                result.push_str(&format!("which -a {}", base.quote_arg(command)));
-            } else {
-                // Show first match
-                result.push_str(&format!("which {}", base.quote_arg(command)));
-            }
-        } else {
-            // Multiple commands
-            if all {
-                // Process each command separately to show all matches
-                let mut parts = Vec::new();
-                for command in commands {
-                    parts.push(format!("which -a {}", base.quote_arg(&command)));
-                }
-                result.push_str(&format!("[{}] | each {{ |cmd| ^$cmd }}", parts.join(", ")));
-            } else {
-                // Find first match for each command
-                result.push_str("which ");
-                for (i, command) in commands.iter().enumerate() {
-                    if i > 0 {
-                        result.push(' ');
-                    }
-                    result.push_str(&base.quote_arg(command));
-                }
-            }
+        let mut result = String::from("which");
+        if all {
+            // Show all matches instead of only the first
+            result.push_str(" --all");
         }
+        result.push(' ');
+        result.push_str(&base.format_args(&commands));
 
         // Handle silent flag
         if silent {
@@ -116,7 +93,7 @@ fn test_which_converter() {
             converter
                 .convert(&["-a".to_string(), "python".to_string()])
                 .unwrap(),
-            "which -a python"
+            "which --all python"
         );
 
         // Which with silent flag
@@ -151,7 +128,7 @@ fn test_which_complex() {
             converter
                 .convert(&["-a".to_string(), "python".to_string(), "node".to_string()])
                 .unwrap(),
-            "[which -a python, which -a node] | each { |cmd| ^$cmd }"
+            "which --all python node"
         );
 
         // Combined flags
@@ -159,7 +136,7 @@ fn test_which_complex() {
             converter
                 .convert(&["-as".to_string(), "python".to_string()])
                 .unwrap(),
-            "which -a python | ignore"
+            "which --all python | ignore"
         );
     }
 }
@@ -87,6 +87,7 @@ fn test_converter_priority_builtin_first() {
         args: vec!["-f".to_string(), "file.txt".to_string()],
         assignments: vec![],
         redirections: vec![],
+        span: (0, 0),
     };
 
     let result = converter.convert_simple_command(&cmd);
@@ -229,6 +230,7 @@ fn test_unknown_command_fallback() {
         args: vec!["arg1".to_string(), "arg2".to_string()],
         assignments: vec![],
         redirections: vec![],
+        span: (0, 0),
     };
 
     let result = converter.convert_simple_command(&cmd);
@@ -344,6 +346,7 @@ fn test_complex_command_conversion() {
             args: vec!["/tmp".to_string(), "-name".to_string(), "*.txt".to_string()],
             assignments: vec![],
             redirections: vec![],
+            span: (0, 0),
         },
         SimpleCommandData {
             name: "grep".to_string(),
@@ -354,12 +357,14 @@ fn test_complex_command_conversion() {
             ],
             assignments: vec![],
             redirections: vec![],
+            span: (0, 0),
         },
         SimpleCommandData {
             name: "sed".to_string(),
             args: vec!["s/old/new/g".to_string(), "file.txt".to_string()],
             assignments: vec![],
             redirections: vec![],
+            span: (0, 0),
         },
     ];
 